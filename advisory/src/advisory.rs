@@ -0,0 +1,242 @@
+use std::fmt;
+use std::fs;
+use std::path::{ Path };
+
+use semver::{ Version, VersionReq };
+
+use crate::{ Error, Result };
+
+/// Kind of an advisory which reports no vulnerability
+#[derive(Clone, Debug, PartialEq)]
+pub enum Informational {
+    Unmaintained,
+    Unsound,
+    Notice,
+    Other(String),
+}
+
+impl From<&str> for Informational {
+    fn from(value: &str) -> Informational {
+        match value {
+            "unmaintained" => Informational::Unmaintained,
+            "unsound" => Informational::Unsound,
+            "notice" => Informational::Notice,
+            other => Informational::Other(other.to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Informational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Informational::Unmaintained => "unmaintained",
+            Informational::Unsound => "unsound",
+            Informational::Notice => "notice",
+            Informational::Other(other) => other,
+        })
+    }
+}
+
+/// A RustSec advisory about a crate
+#[derive(Clone, Debug, PartialEq)]
+pub struct Advisory {
+    /// e.g. `RUSTSEC-2020-0071`
+    pub id: String,
+    pub package: String,
+    pub title: String,
+    pub description: String,
+    pub date: Option<String>,
+    pub url: Option<String>,
+    pub categories: Vec<String>,
+    pub keywords: Vec<String>,
+    /// Other identifiers such as CVEs
+    pub aliases: Vec<String>,
+    /// Set when the advisory reports no vulnerability
+    pub informational: Option<Informational>,
+    /// Date the advisory was withdrawn, after which it no longer applies
+    pub withdrawn: Option<String>,
+    /// Versions with the issue fixed
+    pub patched: Vec<VersionReq>,
+    /// Versions which never had the issue
+    pub unaffected: Vec<VersionReq>,
+}
+
+#[derive(Deserialize)]
+struct RawAdvisoryFile {
+    advisory: RawAdvisory,
+    #[serde(default)]
+    versions: RawVersions,
+}
+
+#[derive(Deserialize)]
+struct RawAdvisory {
+    id: String,
+    package: String,
+    title: Option<String>,
+    description: Option<String>,
+    date: Option<String>,
+    url: Option<String>,
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    informational: Option<String>,
+    withdrawn: Option<String>,
+    /// Ranges of the original format, which kept them in `[advisory]`
+    #[serde(default)]
+    patched_versions: Vec<String>,
+    #[serde(default)]
+    unaffected_versions: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct RawVersions {
+    #[serde(default)]
+    patched: Vec<String>,
+    #[serde(default)]
+    unaffected: Vec<String>,
+}
+
+fn requirements(ranges: &[String]) -> std::result::Result<Vec<VersionReq>, String> {
+    ranges.iter()
+        .map(|range| VersionReq::parse(range).map_err(|err| format!("invalid version range `{}`: {}", range, err)))
+        .collect()
+}
+
+/// Splits the markdown format into its TOML front matter, fenced by
+/// "```toml" and "```", and the text after it
+fn front_matter(data: &str) -> std::result::Result<(&str, &str), String> {
+    let data = data.trim_start();
+    let rest = data.strip_prefix("```toml").ok_or_else(|| "missing ```toml front matter".to_owned())?;
+    let end = rest.find("\n```").ok_or_else(|| "unterminated front matter".to_owned())?;
+    let text = &rest[end + 4..];
+    Ok((&rest[..end], text.strip_prefix('\n').unwrap_or(text)))
+}
+
+impl Advisory {
+    /// Reads an advisory in the markdown format of advisory-db, or in its
+    /// original TOML format when the file has a `.toml` extension
+    pub fn load(path: &Path) -> Result<Advisory> {
+        let data = fs::read_to_string(path).map_err(|err| Error::Io(path.to_owned(), err))?;
+        let parsed = if path.extension().is_some_and(|e| e == "toml") {
+            Advisory::parse_toml(&data, "")
+        } else {
+            front_matter(&data).and_then(|(toml, text)| Advisory::parse_toml(toml, text))
+        };
+        parsed.map_err(|reason| Error::InvalidAdvisory(path.to_owned(), reason))
+    }
+
+    /// Parses the TOML of an advisory, where the title and description
+    /// come from `text` when it is not empty: its first `# ` heading is
+    /// the title and the rest is the description
+    fn parse_toml(toml: &str, text: &str) -> std::result::Result<Advisory, String> {
+        let raw: RawAdvisoryFile = toml::from_str(toml).map_err(|err| err.to_string())?;
+        let advisory = raw.advisory;
+        let (title, description) = match text.trim_start().strip_prefix("# ") {
+            Some(text) => match text.find('\n') {
+                Some(end) => (text[..end].trim().to_owned(), text[end..].trim().to_owned()),
+                None => (text.trim().to_owned(), String::new()),
+            },
+            None => (advisory.title.unwrap_or_default(), advisory.description.unwrap_or_default()),
+        };
+        let mut patched = requirements(&raw.versions.patched)?;
+        patched.extend(requirements(&advisory.patched_versions)?);
+        let mut unaffected = requirements(&raw.versions.unaffected)?;
+        unaffected.extend(requirements(&advisory.unaffected_versions)?);
+        Ok(Advisory {
+            id: advisory.id,
+            package: advisory.package,
+            title,
+            description,
+            date: advisory.date,
+            url: advisory.url,
+            categories: advisory.categories,
+            keywords: advisory.keywords,
+            aliases: advisory.aliases,
+            informational: advisory.informational.as_deref().map(Informational::from),
+            withdrawn: advisory.withdrawn,
+            patched,
+            unaffected,
+        })
+    }
+
+    /// Whether `version` has the issue, that is it is neither patched nor
+    /// unaffected
+    pub fn affects(&self, version: &Version) -> bool {
+        self.withdrawn.is_none()
+            && !self.patched.iter().chain(&self.unaffected).any(|req| req.matches(version))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path, PathBuf };
+
+    use semver::{ Version };
+
+    use super::{ Advisory, Informational };
+    use crate::{ Error };
+
+    fn fixture(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(path)
+    }
+
+    fn load(path: &str) -> Advisory {
+        match Advisory::load(&fixture(path)) {
+            Ok (advisory) => advisory,
+            Err (err) => { assert!(false, "unexpected error: {}", err); unreachable!() },
+        }
+    }
+
+    #[test]
+    fn load_markdown_advisory() {
+        let advisory = load("advisory-db/crates/time/RUSTSEC-2020-0071.md");
+        assert_eq!("RUSTSEC-2020-0071", advisory.id);
+        assert_eq!("time", advisory.package);
+        assert_eq!("Potential segfault in the time crate", advisory.title);
+        assert!(advisory.description.starts_with("Unix-like operating systems may segfault"));
+        assert_eq!(vec!["CVE-2020-26235".to_owned()], advisory.aliases);
+        assert_eq!(None, advisory.informational);
+
+        let affects = |version: &str| advisory.affects(&Version::parse(version).unwrap());
+        assert!(affects("0.2.22"));
+        assert!(affects("0.1.45"));
+        assert!(!affects("0.2.23"));
+        assert!(!affects("0.3.0"));
+        assert!(!affects("0.1.0"));
+    }
+
+    #[test]
+    fn load_original_toml_advisory() {
+        let advisory = load("advisory-db/crates/smallvec/RUSTSEC-2018-0003.toml");
+        assert_eq!("Possible double free during unwinding in SmallVec::insert_many", advisory.title);
+        assert!(!advisory.affects(&Version::parse("0.6.3").unwrap()));
+        assert!(advisory.affects(&Version::parse("0.6.2").unwrap()));
+    }
+
+    #[test]
+    fn load_informational_advisory() {
+        let advisory = load("advisory-db/crates/term/RUSTSEC-2018-0015.md");
+        assert_eq!(Some(Informational::Unmaintained), advisory.informational);
+        assert!(advisory.affects(&Version::parse("0.5.2").unwrap()));
+    }
+
+    #[test]
+    fn not_apply_withdrawn_advisory() {
+        let advisory = load("advisory-db/crates/rand/RUSTSEC-2019-0999.md");
+        assert_eq!(Some("2019-06-01"), advisory.withdrawn.as_deref());
+        assert!(!advisory.affects(&Version::parse("0.6.0").unwrap()));
+    }
+
+    #[test]
+    fn fail_on_invalid_advisory() {
+        match Advisory::load(&fixture("invalid/RUSTSEC-0000-0000.md")) {
+            Ok (advisory) => assert!(false, "should not load: {:?}", advisory),
+            Err (Error::InvalidAdvisory(_, reason)) => assert!(reason.starts_with("invalid version range `>= banana`"), "{}", reason),
+            Err (err) => assert!(false, "expected InvalidAdvisory: {}", err),
+        }
+    }
+}
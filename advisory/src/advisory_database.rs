@@ -0,0 +1,74 @@
+use std::fs;
+use std::io;
+use std::path::{ Path, PathBuf };
+
+use crate::{ Advisory, Error, Result };
+
+/// Advisories of a local checkout of the RustSec advisory-db, which keeps
+/// one file per advisory under `crates/<package>/`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AdvisoryDatabase {
+    pub advisories: Vec<Advisory>,
+}
+
+fn read_dir(path: &Path) -> Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(path).map_err(|err| Error::Io(path.to_owned(), err))?;
+    let mut paths = entries
+        .map(|entry| entry.map(|e| e.path()).map_err(|err| Error::Io(path.to_owned(), err)))
+        .collect::<Result<Vec<_>>>()?;
+    paths.sort();
+    Ok(paths)
+}
+
+impl AdvisoryDatabase {
+    /// Loads every advisory about crates, ordered by package and file name
+    pub fn open(root: &Path) -> Result<AdvisoryDatabase> {
+        let crates = root.join("crates");
+        if !crates.is_dir() {
+            return Err(Error::Io(crates, io::Error::new(io::ErrorKind::NotFound, "advisory directory not found")));
+        }
+        let mut advisories = vec![];
+        for package in read_dir(&crates)?.into_iter().filter(|p| p.is_dir()) {
+            for path in read_dir(&package)? {
+                if path.extension().is_some_and(|e| e == "md" || e == "toml") {
+                    advisories.push(Advisory::load(&path)?);
+                }
+            }
+        }
+        Ok(AdvisoryDatabase { advisories })
+    }
+
+    pub fn for_package<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Advisory> + 'a {
+        self.advisories.iter().filter(move |a| a.package == name)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path };
+
+    use super::{ AdvisoryDatabase };
+    use crate::{ Error };
+
+    #[test]
+    fn load_every_advisory() {
+        match AdvisoryDatabase::open(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/advisory-db")) {
+            Ok (database) => {
+                let ids: Vec<_> = database.advisories.iter().map(|a| a.id.as_str()).collect();
+                assert_eq!(vec!["RUSTSEC-2019-0999", "RUSTSEC-2018-0003", "RUSTSEC-2018-0015", "RUSTSEC-2020-0071"], ids);
+                assert_eq!(1, database.for_package("time").count());
+            },
+            Err (err) => assert!(false, "unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn fail_without_crates_directory() {
+        match AdvisoryDatabase::open(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")) {
+            Ok (_) => assert!(false, "should require a crates directory"),
+            Err (Error::Io(path, _)) => assert!(path.ends_with("fixtures/crates")),
+            Err (err) => assert!(false, "expected Io: {}", err),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt;
+
+use semver::Version;
+
+use crate::{ Advisory, AdvisoryDatabase, Error, Result };
+use crate::registry_index::{ RegistryIndex };
+
+/// Why a locked package is reported
+#[derive(Clone, Debug, PartialEq)]
+pub enum AuditIssue {
+    /// A security vulnerability which the locked version is affected by
+    Vulnerability(Advisory),
+    /// An informational advisory, such as the package being unmaintained
+    Warning(Advisory),
+    /// The locked version has been yanked from the registry
+    Yanked,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditFinding {
+    pub name: String,
+    pub version: Version,
+    pub issue: AuditIssue,
+    /// How the package is brought in, from the closest workspace member
+    pub path: Vec<(String, Version)>,
+}
+
+impl AuditFinding {
+    /// `vulnerability`, `yanked` or the informational kind of a warning
+    /// such as `unmaintained`
+    pub fn kind(&self) -> String {
+        match &self.issue {
+            AuditIssue::Vulnerability(_) => "vulnerability".to_owned(),
+            AuditIssue::Warning(advisory) => advisory.informational.as_ref().map_or_else(|| "warning".to_owned(), |i| i.to_string()),
+            AuditIssue::Yanked => "yanked".to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for AuditFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.issue {
+            AuditIssue::Vulnerability(advisory) => {
+                write!(f, "{}: `{} {}` is vulnerable: {}", advisory.id, self.name, self.version, advisory.title)?;
+                let patched: Vec<_> = advisory.patched.iter().map(|req| req.to_string()).collect();
+                let patched = if patched.is_empty() { "none".to_owned() } else { patched.join(", ") };
+                write!(f, "\n  patched: {}", patched)?;
+            },
+            AuditIssue::Warning(advisory) => {
+                write!(f, "{}: `{} {}` is {}: {}", advisory.id, self.name, self.version, self.kind(), advisory.title)?;
+            },
+            AuditIssue::Yanked => write!(f, "`{} {}` is yanked", self.name, self.version)?,
+        }
+        let path: Vec<_> = self.path.iter().map(|(name, version)| format!("{} {}", name, version)).collect();
+        write!(f, "\n  brought in by {}", path.join(" -> "))
+    }
+}
+
+/// Advisories which apply to the packages of a lockfile.
+///
+/// `Display` lists every finding followed by a summary.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AuditReport {
+    pub findings: Vec<AuditFinding>,
+}
+
+impl AuditReport {
+    pub fn vulnerabilities(&self) -> impl Iterator<Item = &AuditFinding> {
+        self.findings.iter().filter(|f| matches!(f.issue, AuditIssue::Vulnerability(_)))
+    }
+
+    /// Informational advisories and yanked packages
+    pub fn warnings(&self) -> impl Iterator<Item = &AuditFinding> {
+        self.findings.iter().filter(|f| !matches!(f.issue, AuditIssue::Vulnerability(_)))
+    }
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for finding in &self.findings {
+            writeln!(f, "{}", finding)?;
+        }
+        writeln!(f, "{} vulnerabilities and {} warnings found", self.vulnerabilities().count(), self.warnings().count())
+    }
+}
+
+/// Matches the registry packages of `lockfile` against `database`, and
+/// against `index` for yanked versions when it is given.
+///
+/// Paths start at the workspace members, which are the path packages of
+/// the lockfile.
+pub fn audit(lockfile: &lockfile::Lockfile, database: &AdvisoryDatabase, index: Option<&RegistryIndex>) -> Result<AuditReport> {
+    let members: Vec<_> = lockfile.packages.iter().filter(|p| p.is_path()).collect();
+    let mut report = AuditReport::default();
+    for path in lockfile.shortest_paths(&members) {
+        let package = path[path.len() - 1];
+        if !package.is_registry() {
+            continue;
+        }
+        let path: Vec<_> = path.iter().map(|p| (p.name.to_owned(), p.version.to_owned())).collect();
+        let mut finding = |issue| report.findings.push(AuditFinding {
+            name: package.name.to_owned(),
+            version: package.version.to_owned(),
+            issue,
+            path: path.to_owned(),
+        });
+        for advisory in database.for_package(&package.name).filter(|a| a.affects(&package.version)) {
+            finding(match advisory.informational {
+                Some(_) => AuditIssue::Warning(advisory.to_owned()),
+                None => AuditIssue::Vulnerability(advisory.to_owned()),
+            });
+        }
+        if let Some(index) = index {
+            let versions = index.versions(&package.name).map_err(Error::Index)?.unwrap_or_default();
+            if versions.iter().any(|v| v.version == package.version && v.yanked) {
+                finding(AuditIssue::Yanked);
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path };
+
+    use super::{ audit, AuditReport };
+    use crate::{ AdvisoryDatabase };
+    use crate::registry_index::{ RegistryIndex };
+
+    const LOCKFILE: &str = r#"
+        version = 3
+
+        [[package]]
+        name = "app"
+        version = "0.1.0"
+        dependencies = ["chrono", "log", "rand", "smallvec"]
+
+        [[package]]
+        name = "chrono"
+        version = "0.4.19"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        dependencies = ["time"]
+
+        [[package]]
+        name = "cli"
+        version = "0.1.0"
+        dependencies = ["app", "term"]
+
+        [[package]]
+        name = "log"
+        version = "0.4.20"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "rand"
+        version = "0.6.0"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "smallvec"
+        version = "0.6.3"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "term"
+        version = "0.5.2"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "time"
+        version = "0.2.22"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+    "#;
+
+    fn report(with_index: bool) -> AuditReport {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let lockfile = lockfile::parse_lockfile(LOCKFILE).unwrap();
+        let database = AdvisoryDatabase::open(&manifest_dir.join("fixtures/advisory-db")).unwrap();
+        let index = RegistryIndex::open(&manifest_dir.join("../registry-index/fixtures/index")).unwrap();
+        match audit(&lockfile, &database, if with_index { Some(&index) } else { None }) {
+            Ok (report) => report,
+            Err (err) => { assert!(false, "unexpected error: {}", err); AuditReport::default() },
+        }
+    }
+
+    #[test]
+    fn match_locked_versions() {
+        let report = report(true);
+        let findings: Vec<_> = report.findings.iter().map(|f| (f.name.as_str(), f.kind())).collect();
+        assert_eq!(vec![
+            ("log", "yanked".to_owned()),
+            ("term", "unmaintained".to_owned()),
+            ("time", "vulnerability".to_owned()),
+        ], findings);
+        assert_eq!(1, report.vulnerabilities().count());
+        assert_eq!(2, report.warnings().count());
+    }
+
+    #[test]
+    fn skip_yanked_check_without_index() {
+        let report = report(false);
+        let findings: Vec<_> = report.findings.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(vec!["term", "time"], findings);
+    }
+
+    #[test]
+    fn render_findings_with_paths() {
+        assert_eq!("\
+`log 0.4.20` is yanked
+  brought in by app 0.1.0 -> log 0.4.20
+RUSTSEC-2018-0015: `term 0.5.2` is unmaintained: term is looking for a new maintainer
+  brought in by cli 0.1.0 -> term 0.5.2
+RUSTSEC-2020-0071: `time 0.2.22` is vulnerable: Potential segfault in the time crate
+  patched: >= 0.2.23
+  brought in by app 0.1.0 -> chrono 0.4.19 -> time 0.2.22
+1 vulnerabilities and 2 warnings found
+", report(true).to_string());
+    }
+}
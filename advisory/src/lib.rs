@@ -0,0 +1,38 @@
+extern crate omni_manifest_lockfile as lockfile;
+extern crate omni_manifest_registry_index as registry_index;
+extern crate semver;
+#[macro_use]
+extern crate serde;
+extern crate toml;
+
+mod advisory;
+mod advisory_database;
+mod audit;
+
+pub use self::advisory::*;
+pub use self::advisory_database::*;
+pub use self::audit::*;
+
+use std::fmt;
+use std::io;
+use std::path::{ PathBuf };
+
+#[derive(Debug)]
+pub enum Error {
+    /// An advisory file which cannot be parsed, with the reason
+    InvalidAdvisory(PathBuf, String),
+    Index(registry_index::Error),
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidAdvisory(path, reason) => write!(f, "{}: invalid advisory: {}", path.display(), reason),
+            Error::Index(err) => write!(f, "{}", err),
+            Error::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
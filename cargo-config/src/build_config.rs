@@ -0,0 +1,21 @@
+use schema_v1::string_or_vec::StringOrVec;
+
+use crate::{ StringList };
+
+/// Represents the `[build]` section of a cargo configuration file.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BuildConfig {
+    pub jobs: Option<i32>,
+    pub rustc: Option<String>,
+    pub rustc_wrapper: Option<String>,
+    pub rustc_workspace_wrapper: Option<String>,
+    pub rustdoc: Option<String>,
+    pub target: Option<StringOrVec>,
+    pub target_dir: Option<String>,
+    pub rustflags: Option<StringList>,
+    pub rustdocflags: Option<StringList>,
+    pub incremental: Option<bool>,
+    pub dep_info_basedir: Option<String>,
+    pub pipelining: Option<bool>,
+}
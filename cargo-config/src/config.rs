@@ -0,0 +1,37 @@
+use std::collections::{ BTreeMap };
+
+use schema_v1::Profile;
+
+use crate::{ BuildConfig, EnvConfigValue, NetConfig, StringList, TargetConfig };
+
+/// Represents a `.cargo/config.toml` (or legacy `.cargo/config`) file, or
+/// the merged result of every configuration file which applies to a
+/// directory.
+///
+/// Sections this crate does not interpret are kept as raw TOML values so
+/// that they are not reported as unused keys.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    pub alias: Option<BTreeMap<String, StringList>>,
+    pub build: Option<BuildConfig>,
+    pub env: Option<BTreeMap<String, EnvConfigValue>>,
+    pub net: Option<NetConfig>,
+    pub profile: Option<BTreeMap<String, Profile>>,
+    pub target: Option<BTreeMap<String, TargetConfig>>,
+
+    // Sections which are accepted but not modeled.
+    pub cargo_new: Option<toml::Value>,
+    pub credential_alias: Option<toml::Value>,
+    pub doc: Option<toml::Value>,
+    pub future_incompat_report: Option<toml::Value>,
+    pub http: Option<toml::Value>,
+    pub install: Option<toml::Value>,
+    pub patch: Option<toml::Value>,
+    pub paths: Option<Vec<String>>,
+    pub registries: Option<toml::Value>,
+    pub registry: Option<toml::Value>,
+    pub source: Option<toml::Value>,
+    pub term: Option<toml::Value>,
+    pub unstable: Option<toml::Value>,
+}
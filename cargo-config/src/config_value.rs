@@ -0,0 +1,172 @@
+use std::collections::{ BTreeMap };
+use std::fmt;
+use std::path::{ PathBuf };
+
+use crate::{ Error, Result };
+
+/// Location a configuration value was read from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Definition {
+    /// Defined in the configuration file at this path.
+    Path(PathBuf),
+    /// Defined by the named environment variable.
+    Environment(String),
+}
+
+impl fmt::Display for Definition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Definition::Path(path) => write!(f, "`{}`", path.display()),
+            Definition::Environment(key) => write!(f, "environment variable `{}`", key),
+        }
+    }
+}
+
+/// An untyped configuration value which remembers where each of its parts
+/// were defined.
+///
+/// Lists keep a definition per item because merging concatenates lists
+/// from several files.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigValue {
+    Integer(i64, Definition),
+    String(String, Definition),
+    Boolean(bool, Definition),
+    List(Vec<(String, Definition)>, Definition),
+    Table(BTreeMap<String, ConfigValue>, Definition),
+}
+
+fn join_key(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+impl ConfigValue {
+    /// Converts a parsed TOML document into a value defined by `definition`
+    pub fn from_toml(value: toml::Value, definition: &Definition) -> Result<ConfigValue> {
+        ConfigValue::from_toml_key(value, definition, "")
+    }
+
+    fn from_toml_key(value: toml::Value, definition: &Definition, key: &str) -> Result<ConfigValue> {
+        match value {
+            toml::Value::String(s) => Ok(ConfigValue::String(s, definition.to_owned())),
+            toml::Value::Integer(i) => Ok(ConfigValue::Integer(i, definition.to_owned())),
+            toml::Value::Boolean(b) => Ok(ConfigValue::Boolean(b, definition.to_owned())),
+            toml::Value::Array(items) => {
+                items.into_iter()
+                    .map(|item| match item {
+                        toml::Value::String(s) => Ok((s, definition.to_owned())),
+                        other => Err(Error::UnsupportedValue {
+                            key: key.to_owned(),
+                            found: other.type_str().to_owned(),
+                            definition: definition.to_owned(),
+                        }),
+                    })
+                    .collect::<Result<Vec<_>>>()
+                    .map(|list| ConfigValue::List(list, definition.to_owned()))
+            },
+            toml::Value::Table(table) => {
+                table.into_iter()
+                    .map(|(k, v)| {
+                        let child = join_key(key, &k);
+                        ConfigValue::from_toml_key(v, definition, &child).map(|v| (k, v))
+                    })
+                    .collect::<Result<BTreeMap<_, _>>>()
+                    .map(|table| ConfigValue::Table(table, definition.to_owned()))
+            },
+            other => Err(Error::UnsupportedValue {
+                key: key.to_owned(),
+                found: other.type_str().to_owned(),
+                definition: definition.to_owned(),
+            }),
+        }
+    }
+
+    /// Converts back into a plain TOML value, dropping the definitions
+    pub fn to_toml(&self) -> toml::Value {
+        match self {
+            ConfigValue::Integer(i, _) => toml::Value::Integer(*i),
+            ConfigValue::String(s, _) => toml::Value::String(s.to_owned()),
+            ConfigValue::Boolean(b, _) => toml::Value::Boolean(*b),
+            ConfigValue::List(list, _) => toml::Value::Array(
+                list.iter().map(|(s, _)| toml::Value::String(s.to_owned())).collect()
+            ),
+            ConfigValue::Table(table, _) => toml::Value::Table(
+                table.iter().map(|(k, v)| (k.to_owned(), v.to_toml())).collect()
+            ),
+        }
+    }
+
+    pub fn definition(&self) -> &Definition {
+        match self {
+            ConfigValue::Integer(_, def)
+            | ConfigValue::String(_, def)
+            | ConfigValue::Boolean(_, def)
+            | ConfigValue::List(_, def)
+            | ConfigValue::Table(_, def) => def,
+        }
+    }
+
+    pub fn desc(&self) -> &'static str {
+        match self {
+            ConfigValue::Integer(..) => "integer",
+            ConfigValue::String(..) => "string",
+            ConfigValue::Boolean(..) => "boolean",
+            ConfigValue::List(..) => "array",
+            ConfigValue::Table(..) => "table",
+        }
+    }
+
+    /// Whether the value is neither a list nor a table
+    fn is_scalar(&self) -> bool {
+        !matches!(self, ConfigValue::List(..) | ConfigValue::Table(..))
+    }
+
+    /// Looks up a dotted key such as `build.target`
+    pub fn get(&self, key: &str) -> Option<&ConfigValue> {
+        key.split('.').try_fold(self, |value, part| match value {
+            ConfigValue::Table(table, _) => table.get(part),
+            _ => None,
+        })
+    }
+
+    /// Merges `from`, which takes priority over `self`, into this value.
+    ///
+    /// Tables are merged key by key, lists are concatenated with the higher
+    /// priority items last and any other value is replaced by another value
+    /// which is neither a list nor a table.
+    pub fn merge(&mut self, from: ConfigValue) -> Result<()> {
+        self.merge_key(from, "")
+    }
+
+    fn merge_key(&mut self, from: ConfigValue, key: &str) -> Result<()> {
+        match (self, from) {
+            (ConfigValue::List(old, _), ConfigValue::List(new, _)) => {
+                old.extend(new);
+            },
+            (ConfigValue::Table(old, _), ConfigValue::Table(new, _)) => {
+                for (k, v) in new {
+                    let child = join_key(key, &k);
+                    if let Some(existing) = old.get_mut(&k) {
+                        existing.merge_key(v, &child)?;
+                        continue;
+                    }
+                    old.insert(k, v);
+                }
+            },
+            (old, new) if old.is_scalar() && new.is_scalar() => *old = new,
+            (expected, found) => {
+                return Err(Error::MismatchedTypes {
+                    key: key.to_owned(),
+                    expected: expected.desc(),
+                    found: found.desc(),
+                    definition: found.definition().to_owned(),
+                })
+            },
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::{ BTreeMap, BTreeSet };
+
+use crate::{ ConfigValue, Definition, Error, Result };
+
+/// How the string value of an environment variable is interpreted for a key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    String,
+    Integer,
+    Boolean,
+    /// Whitespace separated items appended to any configured list.
+    List,
+    /// `true`/`false` as a boolean, then an integer, otherwise a string;
+    /// as cargo does for keys which accept several types.
+    Any,
+}
+
+const BUILD_KEYS: &[(&str, Kind)] = &[
+    ("build.jobs", Kind::Integer),
+    ("build.rustc", Kind::String),
+    ("build.rustc-wrapper", Kind::String),
+    ("build.rustc-workspace-wrapper", Kind::String),
+    ("build.rustdoc", Kind::String),
+    ("build.target", Kind::String),
+    ("build.target-dir", Kind::String),
+    ("build.rustflags", Kind::List),
+    ("build.rustdocflags", Kind::List),
+    ("build.incremental", Kind::Boolean),
+    ("build.dep-info-basedir", Kind::String),
+    ("build.pipelining", Kind::Boolean),
+    ("net.retry", Kind::Integer),
+    ("net.git-fetch-with-cli", Kind::Boolean),
+    ("net.offline", Kind::Boolean),
+];
+
+const TARGET_KEYS: &[(&str, Kind)] = &[
+    ("linker", Kind::String),
+    ("runner", Kind::List),
+    ("rustflags", Kind::List),
+    ("rustdocflags", Kind::List),
+];
+
+const PROFILE_KEYS: &[(&str, Kind)] = &[
+    ("opt-level", Kind::Any),
+    ("lto", Kind::Any),
+    ("codegen-units", Kind::Integer),
+    ("debug", Kind::Any),
+    ("debug-assertions", Kind::Boolean),
+    ("rpath", Kind::Boolean),
+    ("panic", Kind::String),
+    ("overflow-checks", Kind::Boolean),
+    ("incremental", Kind::Boolean),
+];
+
+const BUILTIN_PROFILES: &[&str] = &["bench", "dev", "doc", "release", "test"];
+
+const ALIAS_PREFIX: &str = "CARGO_ALIAS_";
+
+/// Name of the environment variable which overrides a config key, e.g.
+/// `build.target-dir` is `CARGO_BUILD_TARGET_DIR`.
+pub fn env_key(key: &str) -> String {
+    format!("CARGO_{}", key.to_uppercase().replace(['.', '-'], "_"))
+}
+
+fn table_keys(value: &ConfigValue, key: &str) -> BTreeSet<String> {
+    match value.get(key) {
+        Some(ConfigValue::Table(table, _)) => table.keys().cloned().collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+fn string_items(value: Option<&ConfigValue>) -> Vec<String> {
+    match value {
+        Some(ConfigValue::String(s, _)) => vec![s.to_owned()],
+        Some(ConfigValue::List(list, _)) => list.iter().map(|(s, _)| s.to_owned()).collect(),
+        _ => vec![],
+    }
+}
+
+fn parse_env_value(var: &str, value: &str, kind: Kind) -> Result<ConfigValue> {
+    let definition = Definition::Environment(var.to_owned());
+    let invalid = |expected: &str| Error::InvalidEnvValue {
+        var: var.to_owned(),
+        value: value.to_owned(),
+        expected: expected.to_owned(),
+    };
+    match kind {
+        Kind::String => Ok(ConfigValue::String(value.to_owned(), definition)),
+        Kind::Integer => value.parse()
+            .map(|i| ConfigValue::Integer(i, definition))
+            .map_err(|_| invalid("an integer")),
+        Kind::Boolean => value.parse()
+            .map(|b| ConfigValue::Boolean(b, definition))
+            .map_err(|_| invalid("a boolean")),
+        Kind::List => {
+            let items = value.split_whitespace()
+                .map(|s| (s.to_owned(), definition.to_owned()))
+                .collect();
+            Ok(ConfigValue::List(items, definition))
+        },
+        Kind::Any => {
+            if let Ok(b) = value.parse() {
+                Ok(ConfigValue::Boolean(b, definition))
+            } else if let Ok(i) = value.parse() {
+                Ok(ConfigValue::Integer(i, definition))
+            } else {
+                Ok(ConfigValue::String(value.to_owned(), definition))
+            }
+        },
+    }
+}
+
+/// Places `value` at the key `path`, creating any missing tables.
+///
+/// Environment lists are appended to a configured list, splitting a string
+/// value into its items first, while any other value replaces what the
+/// files configured.
+fn set(root: &mut ConfigValue, path: &[String], value: ConfigValue) -> Result<()> {
+    let definition = value.definition().to_owned();
+    let key = path.join(".");
+    let (last, parts) = match path.split_last() {
+        Some(split) => split,
+        None => return Ok(()),
+    };
+
+    let mut current = root;
+    for part in parts {
+        current = match current {
+            ConfigValue::Table(table, _) => table
+                .entry(part.to_owned())
+                .or_insert_with(|| ConfigValue::Table(BTreeMap::new(), definition.to_owned())),
+            other => return Err(Error::MismatchedTypes {
+                key: key.to_owned(),
+                expected: other.desc(),
+                found: "table",
+                definition,
+            }),
+        };
+    }
+
+    match current {
+        ConfigValue::Table(table, _) => {
+            if let (Some(ConfigValue::String(s, def)), ConfigValue::List(..)) = (table.get(last), &value) {
+                let items = s.split_whitespace()
+                    .map(|item| (item.to_owned(), def.to_owned()))
+                    .collect();
+                table.insert(last.to_owned(), ConfigValue::List(items, def.to_owned()));
+            }
+            match (table.get_mut(last), value) {
+                (Some(existing @ ConfigValue::List(..)), value @ ConfigValue::List(..)) => {
+                    existing.merge(value)
+                },
+                (Some(existing), value) => {
+                    *existing = value;
+                    Ok(())
+                },
+                (None, value) => {
+                    table.insert(last.to_owned(), value);
+                    Ok(())
+                },
+            }
+        },
+        other => Err(Error::MismatchedTypes {
+            key: key.to_owned(),
+            expected: other.desc(),
+            found: value.desc(),
+            definition,
+        }),
+    }
+}
+
+/// Applies `CARGO_*` environment variable overrides on top of the values
+/// merged from configuration files.
+///
+/// Like cargo, an environment variable is only consulted for a key it can
+/// name unambiguously: the fixed `[build]` and `[net]` keys, the
+/// `[target.<triple>]` tables for any triple which is configured or named
+/// by `build.target`, the built-in and configured profiles and aliases.
+pub fn apply_env(root: &mut ConfigValue, env: &BTreeMap<String, String>) -> Result<()> {
+    let mut keys: Vec<(Vec<String>, Kind)> = BUILD_KEYS.iter()
+        .filter(|(key, _)| *key != "build.target")
+        .map(|(key, kind)| (key.split('.').map(str::to_owned).collect(), *kind))
+        .collect();
+
+    // `build.target` is resolved first so its triple is known below.
+    let build_target = env_key("build.target");
+    if let Some(value) = env.get(&build_target) {
+        let value = parse_env_value(&build_target, value, Kind::String)?;
+        set(root, &["build".to_owned(), "target".to_owned()], value)?;
+    }
+
+    let mut triples = table_keys(root, "target");
+    triples.extend(string_items(root.get("build.target")));
+    for triple in triples.iter().filter(|t| !t.starts_with("cfg(")) {
+        for (key, kind) in TARGET_KEYS {
+            keys.push((vec!["target".to_owned(), triple.to_owned(), key.to_string()], *kind));
+        }
+    }
+
+    let mut profiles = table_keys(root, "profile");
+    profiles.extend(BUILTIN_PROFILES.iter().map(|p| p.to_string()));
+    for profile in &profiles {
+        for (key, kind) in PROFILE_KEYS {
+            keys.push((vec!["profile".to_owned(), profile.to_owned(), key.to_string()], *kind));
+            keys.push((
+                vec!["profile".to_owned(), profile.to_owned(), "build-override".to_owned(), key.to_string()],
+                *kind,
+            ));
+        }
+    }
+
+    let mut aliases = table_keys(root, "alias");
+    aliases.extend(env.keys()
+        .filter(|var| var.starts_with(ALIAS_PREFIX))
+        .map(|var| var[ALIAS_PREFIX.len()..].to_lowercase()));
+    for alias in &aliases {
+        keys.push((vec!["alias".to_owned(), alias.to_owned()], Kind::List));
+    }
+
+    for (path, kind) in keys {
+        let var = env_key(&path.join("."));
+        if let Some(value) = env.get(&var) {
+            let value = parse_env_value(&var, value, kind)?;
+            set(root, &path, value)?;
+        }
+    }
+    Ok(())
+}
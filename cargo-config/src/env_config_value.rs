@@ -0,0 +1,40 @@
+/// Represents a value in the `[env]` section of a cargo configuration file.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum EnvConfigValue {
+    Simple(String),
+    Detailed(DetailedEnvConfigValue),
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DetailedEnvConfigValue {
+    pub value: String,
+    /// Overwrite a variable which is already set in the environment
+    pub force: Option<bool>,
+    /// Value is a path relative to the directory containing `.cargo/`
+    pub relative: Option<bool>,
+}
+
+impl EnvConfigValue {
+    pub fn value(&self) -> &str {
+        match self {
+            EnvConfigValue::Simple(value) => value,
+            EnvConfigValue::Detailed(detailed) => &detailed.value,
+        }
+    }
+
+    pub fn is_force(&self) -> bool {
+        match self {
+            EnvConfigValue::Simple(_) => false,
+            EnvConfigValue::Detailed(detailed) => detailed.force.unwrap_or(false),
+        }
+    }
+
+    pub fn is_relative(&self) -> bool {
+        match self {
+            EnvConfigValue::Simple(_) => false,
+            EnvConfigValue::Detailed(detailed) => detailed.relative.unwrap_or(false),
+        }
+    }
+}
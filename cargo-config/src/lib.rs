@@ -0,0 +1,421 @@
+#[macro_use]
+extern crate serde;
+extern crate serde_ignored;
+extern crate toml;
+extern crate omni_manifest_toml_parser as parser;
+extern crate omni_manifest_toml_schema_v1 as schema_v1;
+
+mod build_config;
+mod config;
+mod config_value;
+mod env;
+mod env_config_value;
+mod net_config;
+mod paths;
+mod string_list;
+mod target_config;
+
+pub use self::build_config::*;
+pub use self::config::*;
+pub use self::config_value::*;
+pub use self::env::*;
+pub use self::env_config_value::*;
+pub use self::net_config::*;
+pub use self::paths::*;
+pub use self::string_list::*;
+pub use self::target_config::*;
+
+use std::collections::{ BTreeMap };
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{ Path, PathBuf };
+
+#[derive(Debug)]
+pub enum Error {
+    DeserializerError(toml::de::Error),
+    InvalidConfigFile(PathBuf, Box<Error>),
+    InvalidEnvValue {
+        var: String,
+        value: String,
+        expected: String,
+    },
+    Io(PathBuf, io::Error),
+    MismatchedTypes {
+        key: String,
+        expected: &'static str,
+        found: &'static str,
+        definition: Definition,
+    },
+    Parser(parser::Error),
+    UnsupportedValue {
+        key: String,
+        found: String,
+        definition: Definition,
+    },
+    UnusedKeys(Vec<String>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DeserializerError(err) => {
+                write!(f, "Failed to Deserialize TOML into Config: {}", err)
+            },
+            Error::InvalidConfigFile(path, err) => {
+                write!(f, "could not load config file `{}`: {}", path.display(), err)
+            },
+            Error::InvalidEnvValue { var, value, expected } => {
+                write!(f, "environment variable `{}` is `{}` but expected {}", var, value, expected)
+            },
+            Error::Io(path, err) => {
+                write!(f, "failed to read `{}`: {}", path.display(), err)
+            },
+            Error::MismatchedTypes { key, expected, found, definition } => {
+                write!(f, "failed to merge key `{}`: expected {} but found {} in {}", key, expected, found, definition)
+            },
+            Error::Parser(err) => err.fmt(f),
+            Error::UnsupportedValue { key, found, definition } => {
+                write!(f, "key `{}` in {} has unsupported value type {}", key, definition, found)
+            },
+            Error::UnusedKeys(keys) => {
+                let mut r = write!(f, "Unused keys:");
+                for key in keys {
+                    r = r.and_then(|_| write!(f, "\n- {}", key));
+                }
+                r
+            },
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq)]
+pub enum Warning {
+    BothConfigFiles {
+        used: PathBuf,
+        ignored: PathBuf,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::BothConfigFiles { used, ignored } => write!(
+                f,
+                "both `{}` and `{}` exist, `{}` will be used",
+                used.display(), ignored.display(), used.display()
+            ),
+        }
+    }
+}
+
+/// Deserializes a TOML value into the typed `Config`, reporting any keys
+/// which are not part of the model
+pub fn config_from_toml(mut value: toml::Value) -> Result<Config> {
+    let mut ignored = target_config::remove_unused_target_keys(&mut value);
+    serde_ignored::deserialize(value, |path| {
+        let mut path_str = String::new();
+        parser::stringify_serde_ignored_path(&mut path_str, &path);
+        ignored.push(path_str);
+    })
+    .map_err(Error::DeserializerError)
+    .and_then(|c| {
+        if ignored.is_empty() {
+            Ok(c)
+        } else {
+            Err(Error::UnusedKeys(ignored))
+        }
+    })
+}
+
+/// Attempts to parse the supplied string as a single cargo configuration file
+pub fn parse_config(data: &str) -> Result<Config> {
+    parser::parse_toml(data)
+        .map_err(Error::Parser)
+        .and_then(config_from_toml)
+}
+
+/// Reads a configuration file, recording the file as the definition of
+/// every value in it
+pub fn load_file(path: &Path) -> Result<ConfigValue> {
+    fs::read_to_string(path)
+        .map_err(|err| Error::Io(path.to_owned(), err))
+        .and_then(|data| parser::parse_toml(&data).map_err(Error::Parser))
+        .and_then(|value| ConfigValue::from_toml(value, &Definition::Path(path.to_owned())))
+        .map_err(|err| match err {
+            err @ Error::Io(..) => err,
+            err => Error::InvalidConfigFile(path.to_owned(), Box::new(err)),
+        })
+}
+
+/// The configuration which applies to a directory, along with where each
+/// value was defined
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadedConfig {
+    pub config: Config,
+    pub value: ConfigValue,
+    /// Files which were merged, highest priority first
+    pub paths: Vec<PathBuf>,
+}
+
+impl LoadedConfig {
+    /// Where the value at the dotted `key` was defined
+    pub fn definition(&self, key: &str) -> Option<&Definition> {
+        self.value.get(key).map(ConfigValue::definition)
+    }
+
+    /// Where each item of the list at the dotted `key` was defined
+    pub fn item_definitions(&self, key: &str) -> Vec<(&str, &Definition)> {
+        match self.value.get(key) {
+            Some(ConfigValue::List(list, _)) => list.iter().map(|(s, d)| (s.as_str(), d)).collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// Loads and merges every configuration file which applies to `cwd` then
+/// applies the `CARGO_*` overrides found in `env`.
+///
+/// Files closer to `cwd` take priority over their ancestors and the cargo
+/// home directory, while lists are concatenated across all of them.
+pub fn load(
+    cwd: &Path,
+    cargo_home: Option<&Path>,
+    env: &BTreeMap<String, String>,
+) -> Result<(LoadedConfig, Option<Vec<Warning>>)> {
+    let (paths, warnings) = config_paths(cwd, cargo_home);
+    let mut value = ConfigValue::Table(BTreeMap::new(), Definition::Path(cwd.to_owned()));
+    for path in paths.iter().rev() {
+        value.merge(load_file(path)?)?;
+    }
+    apply_env(&mut value, env)?;
+    let config = config_from_toml(value.to_toml())?;
+    Ok((LoadedConfig { config, value, paths }, warnings))
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
+mod tests {
+    extern crate tempfile;
+
+    use std::collections::{ BTreeMap };
+    use std::fs;
+    use std::path::{ Path };
+
+    use schema_v1::string_or_vec::StringOrVec;
+
+    use crate::{ load, parse_config, Definition, EnvConfigValue, Error, StringList, Warning };
+
+    fn write_config(dir: &Path, file: &str, data: &str) {
+        let dir = dir.join(".cargo");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(file), data).unwrap();
+    }
+
+    #[test]
+    fn parse_requested_sections() {
+        match parse_config(r#"
+            [alias]
+            b = "build --release"
+
+            [build]
+            target = "x86_64-unknown-linux-gnu"
+            rustflags = ["-C", "target-cpu=native"]
+
+            [target.x86_64-unknown-linux-gnu]
+            linker = "clang"
+            runner = "qemu -L /usr"
+
+            [target.x86_64-unknown-linux-gnu.foo]
+            rustc-link-lib = ["foo"]
+
+            [env]
+            FOO = "bar"
+            BAZ = { value = "qux", force = true }
+
+            [net]
+            retry = 3
+
+            [profile.dev]
+            opt-level = 1
+        "#) {
+            Ok(c) => {
+                let alias = c.alias.unwrap();
+                assert_eq!(StringList(vec!["build".to_owned(), "--release".to_owned()]), alias["b"]);
+                let build = c.build.unwrap();
+                assert_eq!(Some(StringOrVec(vec!["x86_64-unknown-linux-gnu".to_owned()])), build.target);
+                assert_eq!(2, build.rustflags.unwrap().0.len());
+                let target = &c.target.unwrap()["x86_64-unknown-linux-gnu"];
+                assert_eq!(Some("clang".to_owned()), target.linker);
+                assert_eq!(Some(StringList(vec!["qemu".to_owned(), "-L".to_owned(), "/usr".to_owned()])), target.runner);
+                assert!(target.links.contains_key("foo"), "should have kept links override: {:?}", target);
+                let env = c.env.unwrap();
+                assert_eq!(EnvConfigValue::Simple("bar".to_owned()), env["FOO"]);
+                assert!(env["BAZ"].is_force());
+                assert_eq!(Some(3), c.net.unwrap().retry);
+                assert!(c.profile.unwrap()["dev"].opt_level.is_some());
+            },
+            Err(err) => assert!(false, "should have parsed config but was:\n{}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_parse_with_unused_keys() {
+        match parse_config(r#"
+            [build]
+            invalid = 1
+        "#) {
+            Ok(_) => assert!(false, "should have failed to parse due to unused key"),
+            Err(Error::UnusedKeys(keys)) => assert_eq!(vec!["build.invalid".to_owned()], keys),
+            Err(err) => assert!(false, "invalid error type {}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_parse_with_unused_target_keys() {
+        match parse_config(r#"
+            [target.x86_64-unknown-linux-gnu]
+            linkr = "clang"
+
+            [target.x86_64-unknown-linux-gnu.foo]
+            rustc-link-lib = ["foo"]
+
+            [target.'cfg(unix)']
+            rustflags = ["-Cforce-frame-pointers"]
+
+            [target.'cfg(unix)'.bar]
+            rustc-link-lib = ["bar"]
+        "#) {
+            Ok(_) => assert!(false, "should have failed to parse due to unused keys"),
+            Err(Error::UnusedKeys(keys)) => assert_eq!(
+                vec!["target.cfg(unix).bar".to_owned(), "target.x86_64-unknown-linux-gnu.linkr".to_owned()],
+                keys,
+            ),
+            Err(err) => assert!(false, "invalid error type {}", err),
+        }
+    }
+
+    #[test]
+    fn merge_nested_configs_with_concatenated_arrays() {
+        let root = tempfile::tempdir().unwrap();
+        let child = root.path().join("child");
+        write_config(root.path(), "config.toml", r#"
+            [build]
+            jobs = 1
+            target-dir = "outer"
+            rustflags = ["-Aouter"]
+        "#);
+        write_config(&child, "config.toml", r#"
+            [build]
+            jobs = 2
+            rustflags = ["-Ainner"]
+        "#);
+        match load(&child, None, &BTreeMap::new()) {
+            Ok((loaded, None)) => {
+                let build = loaded.config.build.to_owned().unwrap();
+                assert_eq!(Some(2), build.jobs);
+                assert_eq!(Some("outer".to_owned()), build.target_dir);
+                assert_eq!(Some(StringList(vec!["-Aouter".to_owned(), "-Ainner".to_owned()])), build.rustflags);
+                assert_eq!(
+                    Some(&Definition::Path(child.join(".cargo").join("config.toml"))),
+                    loaded.definition("build.jobs")
+                );
+                assert_eq!(
+                    Some(&Definition::Path(root.path().join(".cargo").join("config.toml"))),
+                    loaded.definition("build.target-dir")
+                );
+            },
+            Ok((_, Some(w))) => assert!(false, "should not have warnings: {:?}", w),
+            Err(err) => assert!(false, "should have loaded config but was:\n{}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_merge_string_with_array() {
+        let root = tempfile::tempdir().unwrap();
+        let child = root.path().join("child");
+        write_config(root.path(), "config.toml", "[build]\nrustflags = [\"-C b\"]\n");
+        write_config(&child, "config.toml", "[build]\nrustflags = \"-C a\"\n");
+        match load(&child, None, &BTreeMap::new()) {
+            Ok(_) => assert!(false, "should not have merged a string into an array"),
+            Err(Error::MismatchedTypes { key, expected, found, definition }) => {
+                assert_eq!("build.rustflags", key);
+                assert_eq!("array", expected);
+                assert_eq!("string", found);
+                assert_eq!(Definition::Path(child.join(".cargo").join("config.toml")), definition);
+            },
+            Err(err) => assert!(false, "invalid error type {}", err),
+        }
+
+        write_config(root.path(), "config.toml", "[build]\nrustflags = \"-C a\"\n");
+        write_config(&child, "config.toml", "[build]\nrustflags = [\"-C b\"]\n");
+        match load(&child, None, &BTreeMap::new()) {
+            Ok(_) => assert!(false, "should not have merged an array into a string"),
+            Err(Error::MismatchedTypes { expected, found, .. }) => assert_eq!(("string", "array"), (expected, found)),
+            Err(err) => assert!(false, "invalid error type {}", err),
+        }
+    }
+
+    #[test]
+    fn prefer_legacy_config_when_both_exist() {
+        let root = tempfile::tempdir().unwrap();
+        write_config(root.path(), "config", "[build]\njobs = 1\n");
+        write_config(root.path(), "config.toml", "[build]\njobs = 2\n");
+        match load(root.path(), None, &BTreeMap::new()) {
+            Ok((loaded, Some(w))) => {
+                assert_eq!(Some(1), loaded.config.build.unwrap().jobs);
+                assert!(w.iter().any(|w| match w {
+                    Warning::BothConfigFiles { .. } => true,
+                }), "should have warned about both files: {:?}", w);
+            },
+            Ok((_, None)) => assert!(false, "should have warned about both config files"),
+            Err(err) => assert!(false, "should have loaded config but was:\n{}", err),
+        }
+    }
+
+    #[test]
+    fn apply_environment_overrides() {
+        let root = tempfile::tempdir().unwrap();
+        write_config(root.path(), "config.toml", r#"
+            [build]
+            target = "wasm32-unknown-unknown"
+            rustflags = "-Aconfig"
+        "#);
+        let mut env = BTreeMap::new();
+        env.insert("CARGO_BUILD_TARGET".to_owned(), "x86_64-unknown-linux-gnu".to_owned());
+        env.insert("CARGO_TARGET_X86_64_UNKNOWN_LINUX_GNU_LINKER".to_owned(), "clang".to_owned());
+        env.insert("CARGO_BUILD_RUSTFLAGS".to_owned(), "-Aenv".to_owned());
+        env.insert("CARGO_PROFILE_RELEASE_LTO".to_owned(), "true".to_owned());
+        match load(root.path(), None, &env) {
+            Ok((loaded, _)) => {
+                let build = loaded.config.build.to_owned().unwrap();
+                assert_eq!(Some(StringOrVec(vec!["x86_64-unknown-linux-gnu".to_owned()])), build.target);
+                assert_eq!(Some(StringList(vec!["-Aconfig".to_owned(), "-Aenv".to_owned()])), build.rustflags);
+                let target = &loaded.config.target.to_owned().unwrap()["x86_64-unknown-linux-gnu"];
+                assert_eq!(Some("clang".to_owned()), target.linker);
+                assert!(loaded.config.profile.to_owned().unwrap()["release"].lto.is_some());
+                assert_eq!(
+                    Some(&Definition::Environment("CARGO_BUILD_TARGET".to_owned())),
+                    loaded.definition("build.target")
+                );
+                let definitions = loaded.item_definitions("build.rustflags");
+                assert_eq!(Definition::Environment("CARGO_BUILD_RUSTFLAGS".to_owned()), *definitions[1].1);
+            },
+            Err(err) => assert!(false, "should have loaded config but was:\n{}", err),
+        }
+    }
+
+    #[test]
+    fn fail_with_invalid_environment_value() {
+        let root = tempfile::tempdir().unwrap();
+        let mut env = BTreeMap::new();
+        env.insert("CARGO_BUILD_JOBS".to_owned(), "many".to_owned());
+        match load(root.path(), None, &env) {
+            Ok(_) => assert!(false, "should not have accepted a non-integer job count"),
+            Err(Error::InvalidEnvValue { var, .. }) => assert_eq!("CARGO_BUILD_JOBS", var),
+            Err(err) => assert!(false, "invalid error type {}", err),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+/// Represents the `[net]` section of a cargo configuration file.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NetConfig {
+    pub retry: Option<u32>,
+    pub git_fetch_with_cli: Option<bool>,
+    pub offline: Option<bool>,
+    pub ssh: Option<toml::Value>,
+}
@@ -0,0 +1,49 @@
+use std::path::{ Path, PathBuf };
+
+use crate::{ Warning };
+
+pub const CONFIG_DIR: &str = ".cargo";
+pub const LEGACY_CONFIG_FILE: &str = "config";
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Finds the configuration file in `dir`, preferring the legacy extensionless
+/// `config` when both exist as cargo does.
+fn config_file(dir: &Path, warnings: &mut Vec<Warning>) -> Option<PathBuf> {
+    let legacy = dir.join(LEGACY_CONFIG_FILE);
+    let current = dir.join(CONFIG_FILE);
+    match ( legacy.is_file(), current.is_file() ) {
+        ( true, true ) => {
+            warnings.push(Warning::BothConfigFiles { used: legacy.to_owned(), ignored: current });
+            Some(legacy)
+        },
+        ( true, false ) => Some(legacy),
+        ( false, true ) => Some(current),
+        ( false, false ) => None,
+    }
+}
+
+/// Lists the configuration files which apply to `cwd`, highest priority
+/// first: `.cargo/` in `cwd` and each of its ancestors, followed by the
+/// cargo home directory unless it was already visited.
+pub fn config_paths(cwd: &Path, cargo_home: Option<&Path>) -> (Vec<PathBuf>, Option<Vec<Warning>>) {
+    let mut warnings = vec![];
+    let mut paths = vec![];
+    let mut visited_home = false;
+    for dir in cwd.ancestors() {
+        let dir = dir.join(CONFIG_DIR);
+        if cargo_home == Some(dir.as_path()) {
+            visited_home = true;
+        }
+        if let Some(path) = config_file(&dir, &mut warnings) {
+            paths.push(path);
+        }
+    }
+    if let Some(home) = cargo_home {
+        if !visited_home {
+            if let Some(path) = config_file(home, &mut warnings) {
+                paths.push(path);
+            }
+        }
+    }
+    (paths, if warnings.is_empty() { None } else { Some(warnings) })
+}
@@ -0,0 +1,42 @@
+use serde::de;
+use serde::{ Deserialize, Serialize };
+use std::fmt;
+
+/// A list of arguments which may also be written as a single whitespace
+/// separated string, e.g. `rustflags = "-C target-cpu=native"`.
+#[derive(Clone, Debug, Default, Serialize, Eq, PartialEq)]
+pub struct StringList(pub Vec<String>);
+
+impl<'de> de::Deserialize<'de> for StringList {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = StringList;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a whitespace separated string or list of strings")
+            }
+
+            fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(StringList(s.split_whitespace().map(str::to_owned).collect()))
+            }
+
+            fn visit_seq<V>(self, v: V) -> Result<Self::Value, V::Error>
+            where
+                V: de::SeqAccess<'de>,
+            {
+                let seq = de::value::SeqAccessDeserializer::new(v);
+                Vec::deserialize(seq).map(StringList)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
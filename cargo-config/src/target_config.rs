@@ -0,0 +1,53 @@
+use std::collections::{ BTreeMap };
+
+use crate::{ StringList };
+
+/// Represents a `[target.<triple>]` or `[target.'cfg(..)']` section of a
+/// cargo configuration file.
+///
+/// Any table which is not one of the known keys is a build script override
+/// for the native library of that name, e.g. `[target.<triple>.foo]` for a
+/// package with `links = "foo"`. Overrides are only read for a triple, never
+/// under `cfg(..)`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TargetConfig {
+    pub linker: Option<String>,
+    pub runner: Option<StringList>,
+    pub rustflags: Option<StringList>,
+    pub rustdocflags: Option<StringList>,
+    #[serde(flatten)]
+    pub links: BTreeMap<String, toml::value::Table>,
+}
+
+impl TargetConfig {
+    const KEYS: &'static [&'static str] = &["linker", "runner", "rustflags", "rustdocflags"];
+}
+
+/// Removes the keys of the `[target.*]` tables of `config` which are
+/// neither known keys nor `links` overrides, returning their dotted paths.
+///
+/// `links` overrides are flattened into `TargetConfig`, which would
+/// otherwise hide any misspelt key from `serde_ignored`.
+pub(crate) fn remove_unused_target_keys(config: &mut toml::Value) -> Vec<String> {
+    let targets = match config.get_mut("target").and_then(toml::Value::as_table_mut) {
+        Some(targets) => targets,
+        None => return vec![],
+    };
+    let mut unused = vec![];
+    for (name, target) in targets.iter_mut() {
+        if let Some(target) = target.as_table_mut() {
+            let keys: Vec<_> = target.iter()
+                .filter(|(key, value)| {
+                    !TargetConfig::KEYS.contains(&key.as_str()) && (name.starts_with("cfg(") || !value.is_table())
+                })
+                .map(|(key, _)| key.to_owned())
+                .collect();
+            for key in keys {
+                target.remove(&key);
+                unused.push(format!("target.{}.{}", name, key));
+            }
+        }
+    }
+    unused
+}
@@ -0,0 +1,613 @@
+use toml_edit::{ Array, Decor, InlineTable, Item, KeyMut, RawString, Table, Value };
+
+use crate::{ Error, ManifestDocument, Result };
+
+/// Keys of `[package]` in the order of the fields of `schema_v1::Project`,
+/// which is the order it is serialized in
+pub const PACKAGE_KEY_ORDER: &[&str] = &[
+    "edition",
+    "rust-version",
+    "name",
+    "version",
+    "authors",
+    "build",
+    "metabuild",
+    "links",
+    "exclude",
+    "include",
+    "publish",
+    "publish-lockfile",
+    "workspace",
+    "autobins",
+    "autoexamples",
+    "autotests",
+    "autobenches",
+    "namespaced-features",
+    "default-run",
+    "resolver",
+    "description",
+    "homepage",
+    "documentation",
+    "readme",
+    "keywords",
+    "categories",
+    "license",
+    "license-file",
+    "repository",
+    "metadata",
+];
+
+/// How a dependency which only sets a version is written
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DependencyStyle {
+    /// Keep whichever form is used
+    Preserve,
+    /// `log = "0.4"`
+    Simple,
+    /// `log = { version = "0.4" }`
+    Table,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FormatOptions {
+    pub dependency_style: DependencyStyle,
+    /// Sorts the entries of dependency tables by name
+    pub sort_dependencies: bool,
+    /// Orders the keys of `[package]` as `PACKAGE_KEY_ORDER`
+    pub order_package_keys: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions {
+            dependency_style: DependencyStyle::Simple,
+            sort_dependencies: true,
+            order_package_keys: true,
+        }
+    }
+}
+
+/// Whether a blank line is kept before the first comment of a prefix
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Leading {
+    Drop,
+    Keep,
+    Force,
+}
+
+fn raw(value: Option<&RawString>) -> &str {
+    value.and_then(|r| r.as_str()).unwrap_or("")
+}
+
+/// The comment lines of a decor prefix without their indentation, with
+/// runs of blank lines collapsed into one.
+///
+/// The last line of a prefix is the indentation of the key or header it
+/// decorates, which is dropped.
+fn normalize_prefix(prefix: &str, leading: Leading) -> String {
+    let mut lines: Vec<&str> = prefix.split('\n').collect();
+    lines.pop();
+    let mut normalized = String::new();
+    let mut blank = leading == Leading::Force;
+    let mut first = true;
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            blank = blank || !first || leading == Leading::Keep;
+            continue;
+        }
+        if blank {
+            normalized.push('\n');
+        }
+        normalized.push_str(line);
+        normalized.push('\n');
+        blank = false;
+        first = false;
+    }
+    if blank {
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// The trailing comment of a line, separated by a single space
+fn normalize_suffix(suffix: &str) -> String {
+    match suffix.find('#') {
+        Some(start) => format!(" {}", suffix[start..].trim_end()),
+        None => String::new(),
+    }
+}
+
+fn has_comment(decor: &Decor) -> bool {
+    raw(decor.prefix()).contains('#') || raw(decor.suffix()).contains('#')
+}
+
+fn format_key(key: &mut KeyMut, leading: Leading) {
+    let prefix = normalize_prefix(raw(key.leaf_decor().prefix()), leading);
+    key.fmt();
+    key.leaf_decor_mut().set_prefix(prefix);
+}
+
+/// Arrays are written on a single line, unless they hold comments which
+/// would be lost
+fn format_array(array: &mut Array) {
+    let commented = raw(Some(array.trailing())).contains('#') || array.iter().any(|v| has_comment(v.decor()));
+    for value in array.iter_mut() {
+        format_value(value);
+    }
+    if !commented {
+        array.fmt();
+    }
+}
+
+fn format_inline_table(table: &mut InlineTable) {
+    for (mut key, value) in table.iter_mut() {
+        key.fmt();
+        format_value(value);
+    }
+    table.fmt();
+}
+
+/// Resets the representation of a value, which normalizes quoting
+fn format_value(value: &mut Value) {
+    match value {
+        Value::String(v) => v.fmt(),
+        Value::Integer(v) => v.fmt(),
+        Value::Float(v) => v.fmt(),
+        Value::Boolean(v) => v.fmt(),
+        Value::Datetime(v) => v.fmt(),
+        Value::Array(array) => format_array(array),
+        Value::InlineTable(table) => format_inline_table(table),
+    }
+}
+
+/// Formats the value of a `key = value` line, keeping its trailing comment
+fn format_line_value(value: &mut Value) {
+    let suffix = normalize_suffix(raw(value.decor().suffix()));
+    format_value(value);
+    value.decor_mut().set_prefix(" ");
+    value.decor_mut().set_suffix(suffix);
+}
+
+/// Formats the keys of a dotted table such as `version.workspace = true`
+fn format_dotted(table: &mut Table) {
+    for (mut key, item) in table.iter_mut() {
+        key.fmt();
+        match item {
+            Item::Value(value) => format_line_value(value),
+            Item::Table(table) => format_dotted(table),
+            _ => {},
+        }
+    }
+    table.decor_mut().clear();
+}
+
+/// Every header is preceded by a blank line except the first line of the
+/// document, at `first_position`
+fn format_header(table: &mut Table, first_position: Option<isize>) {
+    if table.is_implicit() {
+        return;
+    }
+    let leading = if table.position().is_some() && table.position() == first_position {
+        Leading::Drop
+    } else {
+        Leading::Force
+    };
+    let prefix = normalize_prefix(raw(table.decor().prefix()), leading);
+    let suffix = normalize_suffix(raw(table.decor().suffix()));
+    table.decor_mut().set_prefix(prefix);
+    table.decor_mut().set_suffix(suffix);
+}
+
+/// Formats the entries of a table and its sub-tables
+fn format_table(table: &mut Table, first_position: Option<isize>) {
+    let mut first = true;
+    for (mut key, item) in table.iter_mut() {
+        match item {
+            Item::Value(value) => {
+                format_key(&mut key, if first { Leading::Drop } else { Leading::Keep });
+                format_line_value(value);
+                first = false;
+            },
+            Item::Table(sub) if sub.is_dotted() => {
+                format_key(&mut key, if first { Leading::Drop } else { Leading::Keep });
+                format_dotted(sub);
+                first = false;
+            },
+            Item::Table(sub) => {
+                key.fmt();
+                format_header(sub, first_position);
+                format_table(sub, first_position);
+            },
+            Item::ArrayOfTables(tables) => {
+                key.fmt();
+                for sub in tables.iter_mut() {
+                    format_header(sub, first_position);
+                    format_table(sub, first_position);
+                }
+            },
+            Item::None => {},
+        }
+    }
+}
+
+/// The position of the first header in the document, when nothing but
+/// comments come before it
+fn first_header_position(root: &Table) -> Option<isize> {
+    fn visit(table: &Table, min: &mut Option<isize>) {
+        for (_, item) in table.iter() {
+            let tables: Vec<&Table> = match item {
+                Item::Table(sub) if !sub.is_dotted() => vec![sub],
+                Item::ArrayOfTables(tables) => tables.iter().collect(),
+                _ => continue,
+            };
+            for sub in tables {
+                if !sub.is_implicit() {
+                    *min = match (*min, sub.position()) {
+                        (Some(min), Some(position)) => Some(min.min(position)),
+                        (min, position) => min.or(position),
+                    };
+                }
+                visit(sub, min);
+            }
+        }
+    }
+    let has_values = root.iter().any(|(_, item)| item.is_value() || item.as_table().is_some_and(|t| t.is_dotted()));
+    if has_values {
+        return None;
+    }
+    let mut min = None;
+    visit(root, &mut min);
+    min
+}
+
+/// The version of a dependency which sets nothing else
+fn only_version(item: &Item) -> Option<String> {
+    let table = item.as_table_like()?;
+    match table.get("version").and_then(|v| v.as_str()) {
+        Some(version) if table.len() == 1 => Some(version.to_owned()),
+        _ => None,
+    }
+}
+
+fn apply_dependency_style(deps: &mut Item, style: DependencyStyle) {
+    let is_explicit = deps.as_table().is_none_or(|t| !t.is_implicit());
+    let deps = match deps.as_table_like_mut() {
+        Some(deps) => deps,
+        None => return,
+    };
+    for (mut key, item) in deps.iter_mut() {
+        match style {
+            DependencyStyle::Preserve => {},
+            DependencyStyle::Simple => {
+                let version = match only_version(item) {
+                    Some(version) => version,
+                    None => continue,
+                };
+                let (comments, suffix) = match &*item {
+                    // A `[dependencies.name]` table can only become a line
+                    // of a `[dependencies]` table which is written out
+                    Item::Table(table) if !table.is_dotted() && !is_explicit => continue,
+                    Item::Table(table) if !table.is_dotted() => (raw(table.decor().prefix()).to_owned(), String::new()),
+                    Item::Value(value) => (String::new(), raw(value.decor().suffix()).to_owned()),
+                    _ => (String::new(), String::new()),
+                };
+                if !comments.is_empty() {
+                    key.leaf_decor_mut().set_prefix(comments);
+                }
+                *item = Item::Value(Value::from(version).decorated(" ", suffix));
+            },
+            DependencyStyle::Table => {
+                let (version, suffix) = match &*item {
+                    Item::Value(Value::String(version)) => {
+                        (version.value().to_owned(), raw(version.decor().suffix()).to_owned())
+                    },
+                    _ => continue,
+                };
+                let mut table = InlineTable::new();
+                table.insert("version", Value::from(version));
+                *item = Item::Value(Value::InlineTable(table).decorated(" ", suffix));
+            },
+        }
+    }
+}
+
+/// Sorts the entries of a dependency table by name, including those written
+/// as `[dependencies.name]` tables
+fn sort_dependencies(deps: &mut Item) {
+    match deps {
+        Item::Table(table) => {
+            table.sort_values_by(|a, _, b, _| a.get().cmp(b.get()));
+            // Tables are written in the order of their positions, which are
+            // handed out again in the order of the sorted names
+            let is_header = |item: &Item| item.as_table().is_some_and(|t| !t.is_dotted() && t.position().is_some());
+            let mut positions: Vec<isize> = table.iter()
+                .filter(|(_, item)| is_header(item))
+                .filter_map(|(_, item)| item.as_table().and_then(|t| t.position()))
+                .collect();
+            positions.sort();
+            let mut positions = positions.into_iter();
+            for (_, item) in table.iter_mut() {
+                if is_header(item) {
+                    if let Some(sub) = item.as_table_mut() {
+                        sub.set_position(positions.next());
+                    }
+                }
+            }
+        },
+        Item::Value(Value::InlineTable(table)) => table.sort_values(),
+        _ => {},
+    }
+}
+
+fn order_package_keys(package: &mut Item) {
+    let rank = |key: &str| PACKAGE_KEY_ORDER.iter().position(|k| *k == key).unwrap_or(PACKAGE_KEY_ORDER.len());
+    if let Some(table) = package.as_table_mut() {
+        table.sort_values_by(|a, _, b, _| rank(a.get()).cmp(&rank(b.get())));
+    }
+}
+
+/// Formats a manifest in place.
+///
+/// Keys and values are written with single spaces around `=`, without
+/// indentation and with their default quoting, and every table header is
+/// preceded by a single blank line. Comments stay attached to the entry
+/// or header below them, or to the end of the line they are on.
+pub fn format_document(document: &mut ManifestDocument, options: &FormatOptions) {
+    document.for_each_dependency_table(|_, deps| {
+        apply_dependency_style(deps, options.dependency_style);
+        if options.sort_dependencies {
+            sort_dependencies(deps);
+        }
+    });
+    if options.order_package_keys {
+        if let Some(package) = document.package_mut() {
+            order_package_keys(package);
+        }
+    }
+
+    let document = document.document_mut();
+    let first_position = first_header_position(document.as_table());
+    format_table(document.as_table_mut(), first_position);
+
+    let trailing = normalize_prefix(&format!("{}\n", raw(Some(document.trailing()))), Leading::Keep);
+    let trailing = trailing.trim_end();
+    document.set_trailing(if trailing.is_empty() { String::new() } else { format!("\n{}\n", trailing.trim_start()) });
+}
+
+/// Formats the text of a manifest
+pub fn format_manifest(data: &str, options: &FormatOptions) -> Result<String> {
+    let mut document = ManifestDocument::parse(data)?;
+    format_document(&mut document, options);
+    Ok(document.to_string())
+}
+
+/// Checks that a manifest is already formatted, failing with the first line
+/// which differs otherwise
+pub fn check_format(data: &str, options: &FormatOptions) -> Result<()> {
+    let formatted = format_manifest(data, options)?;
+    if data == formatted {
+        return Ok(());
+    }
+    let line = data.lines().zip(formatted.lines())
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| data.lines().count().min(formatted.lines().count()));
+    Err(Error::NotFormatted(line + 1))
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    extern crate toml;
+
+    use super::{ check_format, format_manifest, DependencyStyle, FormatOptions, PACKAGE_KEY_ORDER };
+    use crate::{ Error };
+
+    fn format(data: &str) -> String {
+        match format_manifest(data, &FormatOptions::default()) {
+            Ok (formatted) => formatted,
+            Err (err) => { assert!(false, "unexpected error: {}", err); String::new() },
+        }
+    }
+
+    #[test]
+    fn normalize_spacing_and_quoting() {
+        let formatted = format(concat!(
+            "[package]\n",
+            "  name='foo'   # the name\n",
+            "\n\n\n",
+            "\"version\"=\"0.1.0\"\n",
+            "authors = [ 'a' ,\"b\" , ]\n",
+            "[dependencies]\n",
+            "serde={version='1.0',features=['derive']}\n",
+        ));
+        assert_eq!(concat!(
+            "[package]\n",
+            "name = \"foo\" # the name\n",
+            "\n",
+            "version = \"0.1.0\"\n",
+            "authors = [\"a\", \"b\"]\n",
+            "\n",
+            "[dependencies]\n",
+            "serde = { version = \"1.0\", features = [\"derive\"] }\n",
+        ), formatted);
+    }
+
+    #[test]
+    fn sort_dependencies_with_their_comments() {
+        let formatted = format(concat!(
+            "[dependencies]\n",
+            "# logging\n",
+            "log = \"0.4\"\n",
+            "anyhow = \"1\" # errors\n",
+            "\n",
+            "[dependencies.zstd]\n",
+            "version = \"0.13\"\n",
+            "features = [\"experimental\"]\n",
+            "\n",
+            "# serialization\n",
+            "[dependencies.serde]\n",
+            "version = \"1.0\"\n",
+            "default-features = false\n",
+            "\n",
+            "[target.'cfg(unix)'.dependencies]\n",
+            "nix = \"0.27\"\n",
+            "libc = \"0.2\"\n",
+        ));
+        assert_eq!(concat!(
+            "[dependencies]\n",
+            "anyhow = \"1\" # errors\n",
+            "# logging\n",
+            "log = \"0.4\"\n",
+            "\n",
+            "# serialization\n",
+            "[dependencies.serde]\n",
+            "version = \"1.0\"\n",
+            "default-features = false\n",
+            "\n",
+            "[dependencies.zstd]\n",
+            "version = \"0.13\"\n",
+            "features = [\"experimental\"]\n",
+            "\n",
+            "[target.\"cfg(unix)\".dependencies]\n",
+            "libc = \"0.2\"\n",
+            "nix = \"0.27\"\n",
+        ), formatted);
+    }
+
+    #[test]
+    fn order_package_keys() {
+        let formatted = format(concat!(
+            "[package]\n",
+            "license = \"MIT\"\n",
+            "description = \"Foo\"\n",
+            "version.workspace = true\n",
+            "custom = 1\n",
+            "name = \"foo\"\n",
+            "edition = \"2021\"\n",
+        ));
+        assert_eq!(concat!(
+            "[package]\n",
+            "edition = \"2021\"\n",
+            "name = \"foo\"\n",
+            "version.workspace = true\n",
+            "description = \"Foo\"\n",
+            "license = \"MIT\"\n",
+            "custom = 1\n",
+        ), formatted);
+    }
+
+    #[test]
+    fn package_key_order_follows_project_fields() {
+        let project: schema_v1::Project = toml::from_str(r#"
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+            rust-version = "1.70"
+            authors = []
+            build = "build.rs"
+            metabuild = []
+            links = "z"
+            exclude = []
+            include = []
+            publish = false
+            publish-lockfile = false
+            workspace = ".."
+            autobins = false
+            autoexamples = false
+            autotests = false
+            autobenches = false
+            namespaced-features = false
+            default-run = "foo"
+            resolver = "2"
+            description = ""
+            homepage = ""
+            documentation = ""
+            readme = ""
+            keywords = []
+            categories = []
+            license = ""
+            license-file = ""
+            repository = ""
+            metadata = {}
+        "#).unwrap();
+        let serialized = toml::to_string(&project).unwrap();
+        let keys: Vec<_> = serialized.lines()
+            .filter_map(|line| line.split(" = ").next().filter(|_| line.contains(" = ")))
+            .chain(Some("metadata"))
+            .collect();
+        assert_eq!(PACKAGE_KEY_ORDER, &keys[..]);
+    }
+
+    #[test]
+    fn convert_dependency_style() {
+        let data = concat!(
+            "[dependencies]\n",
+            "log = { version = \"0.4\" } # logging\n",
+            "serde = \"1.0\"\n",
+            "rand = { version = \"0.8\", default-features = false }\n",
+            "\n",
+            "# compression\n",
+            "[dependencies.zstd]\n",
+            "version = \"0.13\"\n",
+        );
+        assert_eq!(concat!(
+            "[dependencies]\n",
+            "log = \"0.4\" # logging\n",
+            "rand = { version = \"0.8\", default-features = false }\n",
+            "serde = \"1.0\"\n",
+            "\n",
+            "# compression\n",
+            "zstd = \"0.13\"\n",
+        ), format(data));
+
+        let options = FormatOptions { dependency_style: DependencyStyle::Table, .. FormatOptions::default() };
+        assert_eq!(concat!(
+            "[dependencies]\n",
+            "log = { version = \"0.4\" } # logging\n",
+            "rand = { version = \"0.8\", default-features = false }\n",
+            "serde = { version = \"1.0\" }\n",
+            "\n",
+            "# compression\n",
+            "[dependencies.zstd]\n",
+            "version = \"0.13\"\n",
+        ), format_manifest(data, &options).unwrap());
+    }
+
+    #[test]
+    fn keep_commented_arrays_on_their_lines() {
+        let data = concat!(
+            "[features]\n",
+            "default = [\n",
+            "    \"std\", # needed by most users\n",
+            "    \"derive\",\n",
+            "]\n",
+        );
+        assert_eq!(data, format(data));
+    }
+
+    #[test]
+    fn format_fixtures_idempotently_without_changing_meaning() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../toml-schema-v1/fixtures");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let data = std::fs::read_to_string(&path).unwrap();
+            let formatted = format(&data);
+            assert_eq!(formatted, format(&formatted), "{}", path.display());
+            let before = parser::parse_schema_v1(&data).unwrap();
+            let after = parser::parse_schema_v1(&formatted).unwrap();
+            assert_eq!(before, after, "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn check_reports_first_unformatted_line() {
+        let options = FormatOptions::default();
+        assert!(check_format("[package]\nname = \"foo\"\n", &options).is_ok());
+        match check_format("[package]\nname = \"foo\"\nversion=\"1.0.0\"\n", &options) {
+            Ok (_) => assert!(false, "should report the unformatted line"),
+            Err (Error::NotFormatted(line)) => assert_eq!(3, line),
+            Err (err) => assert!(false, "expected NotFormatted: {:?}", err),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+extern crate globset;
+extern crate omni_manifest_toml_parser as parser;
+extern crate omni_manifest_toml_schema_v1 as schema_v1;
+extern crate omni_manifest_v1 as v1;
+extern crate semver;
+extern crate toml_edit;
+
+mod format;
+mod manifest_document;
+mod migrate;
+mod version_bump;
+mod workspace_document;
+
+pub use self::format::*;
+pub use self::manifest_document::*;
+pub use self::migrate::*;
+pub use self::version_bump::*;
+pub use self::workspace_document::*;
+
+use std::fmt;
+use std::io;
+use std::path::{ PathBuf };
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidMemberPattern(String, globset::Error),
+    /// A dependency of the manifest at the path has an unparsable `version`
+    InvalidRequirement(PathBuf, String, semver::ReqParseError),
+    Io(PathBuf, io::Error),
+    Manifest(PathBuf, Box<Error>),
+    /// The package has no version, or one which is not valid semver
+    MissingVersion(String),
+    MissingWorkspace(PathBuf),
+    /// The manifest differs from its formatted form, starting at this line
+    NotFormatted(usize),
+    Parser(Box<parser::Error>),
+    Toml(toml_edit::TomlError),
+    /// No migration has this name
+    UnknownMigration(String),
+    /// No workspace member has this package name
+    UnknownPackage(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidMemberPattern(pattern, err) => {
+                write!(f, "invalid workspace member pattern `{}`: {}", pattern, err)
+            },
+            Error::InvalidRequirement(path, dep, err) => {
+                write!(f, "{}: invalid version requirement of dependency `{}`: {}", path.display(), dep, err)
+            },
+            Error::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            Error::Manifest(path, err) => write!(f, "{}: {}", path.display(), err),
+            Error::MissingVersion(package) => write!(f, "package `{}` has no valid version", package),
+            Error::MissingWorkspace(path) => write!(f, "{}: no `[workspace]` section", path.display()),
+            Error::NotFormatted(line) => {
+                write!(f, "manifest is not formatted, first difference at line {}", line)
+            },
+            Error::Parser(err) => write!(f, "failed to parse manifest: {}", err),
+            Error::Toml(err) => write!(f, "failed to parse TOML: {}", err),
+            Error::UnknownMigration(name) => write!(f, "unknown migration `{}`", name),
+            Error::UnknownPackage(package) => write!(f, "no workspace member is named `{}`", package),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Writes a test fixture file, creating the directories leading up to it
+#[cfg(test)]
+fn write(root: &std::path::Path, file: &str, data: &str) {
+    let path = root.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, data).unwrap();
+}
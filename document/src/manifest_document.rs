@@ -0,0 +1,103 @@
+use std::fmt;
+use std::str::FromStr;
+
+use toml_edit::{ DocumentMut, Item };
+
+use crate::{ Error, Result };
+
+/// Tables holding dependencies, directly under the root or a `[target.*]`
+pub const DEPENDENCY_TABLES: &[&str] = &[
+    "dependencies",
+    "dev-dependencies",
+    "dev_dependencies",
+    "build-dependencies",
+    "build_dependencies",
+];
+
+/// A `Cargo.toml` kept exactly as written, comments and formatting
+/// included, so it can be edited and written back with only the edited
+/// parts changed.
+#[derive(Clone, Debug)]
+pub struct ManifestDocument {
+    document: DocumentMut,
+}
+
+impl ManifestDocument {
+    pub fn parse(data: &str) -> Result<ManifestDocument> {
+        DocumentMut::from_str(data)
+            .map(|document| ManifestDocument { document })
+            .map_err(Error::Toml)
+    }
+
+    pub fn document(&self) -> &DocumentMut {
+        &self.document
+    }
+
+    pub fn document_mut(&mut self) -> &mut DocumentMut {
+        &mut self.document
+    }
+
+    /// Parses the current contents into the schema types
+    pub fn to_schema_v1(&self) -> Result<schema_v1::Manifest> {
+        parser::parse_schema_v1(&self.to_string()).map_err(|err| Error::Parser(Box::new(err)))
+    }
+
+    /// The `[package]` table, or the deprecated `[project]`
+    pub fn package_mut(&mut self) -> Option<&mut Item> {
+        let root = self.document.as_table_mut();
+        let key = if root.contains_key("package") { "package" } else { "project" };
+        root.get_mut(key)
+    }
+
+    /// Calls `f` with the name and item of every dependency table, including
+    /// those of `[target.*]` and `[workspace.dependencies]`.
+    ///
+    /// The name is the path of the table, e.g. `target.'cfg(unix)'.dependencies`.
+    pub fn for_each_dependency_table<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &mut Item),
+    {
+        for (key, item) in self.document.as_table_mut().iter_mut() {
+            match key.get() {
+                name if DEPENDENCY_TABLES.contains(&name) => f(name, item),
+                "target" => {
+                    let platforms = match item.as_table_like_mut() {
+                        Some(platforms) => platforms,
+                        None => continue,
+                    };
+                    for (platform, item) in platforms.iter_mut() {
+                        let tables = match item.as_table_like_mut() {
+                            Some(tables) => tables,
+                            None => continue,
+                        };
+                        for (name, item) in tables.iter_mut() {
+                            if DEPENDENCY_TABLES.contains(&name.get()) {
+                                f(&format!("target.'{}'.{}", platform.get(), name.get()), item);
+                            }
+                        }
+                    }
+                },
+                "workspace" => {
+                    if let Some(deps) = item.get_mut("dependencies") {
+                        f("workspace.dependencies", deps);
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+}
+
+impl FromStr for ManifestDocument {
+    type Err = Error;
+
+    fn from_str(data: &str) -> Result<ManifestDocument> {
+        ManifestDocument::parse(data)
+    }
+}
+
+impl fmt::Display for ManifestDocument {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.document.fmt(f)
+    }
+}
@@ -0,0 +1,760 @@
+use std::fmt;
+use std::path::{ PathBuf };
+use std::str::FromStr;
+
+use toml_edit::{ Item, Key, Table, TableLike, Value };
+
+use crate::{ Error, ManifestDocument, Result, WorkspaceDocument };
+
+/// Editions a manifest can be upgraded to, oldest first
+const EDITIONS: [schema_v1::Edition; 4] = [
+    schema_v1::Edition::Edition2015,
+    schema_v1::Edition::Edition2018,
+    schema_v1::Edition::Edition2021,
+    schema_v1::Edition::Edition2024,
+];
+
+/// Legacy underscore keys of dependency tables, targets and dependencies,
+/// with the kebab-case key replacing each
+const TABLE_ALIASES: [(&str, &str); 2] = [
+    ("dev_dependencies", "dev-dependencies"),
+    ("build_dependencies", "build-dependencies"),
+];
+const TARGET_ALIASES: [(&str, &str); 2] = [
+    ("crate_type", "crate-type"),
+    ("proc_macro", "proc-macro"),
+];
+const DEPENDENCY_ALIASES: [(&str, &str); 1] = [
+    ("default_features", "default-features"),
+];
+
+/// Lines of context around each change of a diff
+const DIFF_CONTEXT: usize = 2;
+
+/// A rewrite of a manifest which can be selected on its own
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Migration {
+    /// `[project]` to `[package]`
+    ProjectTable,
+    /// `dev_dependencies`, `build_dependencies`, `default_features`,
+    /// `crate_type` and `proc_macro` to their kebab-case names
+    UnderscoreKeys,
+    /// `[replace]` entries to `[patch]`, keyed by the source they patch
+    ReplaceToPatch,
+    /// `/`-separated licenses to an SPDX `OR` expression
+    SpdxLicense,
+    /// Upgrades the edition, pinning the resolver of virtual workspaces and
+    /// dropping the legacy syntax the edition rejects
+    Edition(schema_v1::Edition),
+}
+
+impl Migration {
+    /// Every migration, upgrading to the latest edition
+    pub const ALL: [Migration; 5] = [
+        Migration::ProjectTable,
+        Migration::UnderscoreKeys,
+        Migration::ReplaceToPatch,
+        Migration::SpdxLicense,
+        Migration::Edition(schema_v1::Edition::Edition2024),
+    ];
+}
+
+impl fmt::Display for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Migration::ProjectTable => write!(f, "project-table"),
+            Migration::UnderscoreKeys => write!(f, "underscore-keys"),
+            Migration::ReplaceToPatch => write!(f, "replace-to-patch"),
+            Migration::SpdxLicense => write!(f, "spdx-license"),
+            Migration::Edition(edition) => write!(f, "edition-{}", edition),
+        }
+    }
+}
+
+impl FromStr for Migration {
+    type Err = Error;
+
+    /// Parses the name a migration is displayed with, e.g. `edition-2021`
+    fn from_str(name: &str) -> Result<Migration> {
+        let edition = name.strip_prefix("edition-").and_then(parse_edition);
+        match name {
+            "project-table" => Ok(Migration::ProjectTable),
+            "underscore-keys" => Ok(Migration::UnderscoreKeys),
+            "replace-to-patch" => Ok(Migration::ReplaceToPatch),
+            "spdx-license" => Ok(Migration::SpdxLicense),
+            _ => edition.map(Migration::Edition).ok_or_else(|| Error::UnknownMigration(name.to_owned())),
+        }
+    }
+}
+
+/// A change a migration made to a manifest, or one it had to skip
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationChange {
+    pub migration: Migration,
+    /// Dotted path of the changed key, e.g. `dependencies.serde.default_features`
+    pub path: String,
+    pub message: String,
+    /// Whether the manifest was changed, which it is not when the change
+    /// would conflict with what is already there
+    pub applied: bool,
+}
+
+impl fmt::Display for MigrationChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = if self.applied { "" } else { "skipped " };
+        write!(f, "[{}] {}{}: {}", self.migration, status, self.path, self.message)
+    }
+}
+
+/// What migrating a manifest would change, without changing it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MigrationReport {
+    pub changes: Vec<MigrationChange>,
+    /// Unified diff of the manifest, empty when nothing changes
+    pub diff: String,
+}
+
+struct Changes<'a> {
+    migration: Migration,
+    changes: &'a mut Vec<MigrationChange>,
+}
+
+impl Changes<'_> {
+    fn applied(&mut self, path: &str, message: String) {
+        self.push(path, message, true);
+    }
+
+    fn skipped(&mut self, path: &str, message: String) {
+        self.push(path, message, false);
+    }
+
+    fn push(&mut self, path: &str, message: String, applied: bool) {
+        self.changes.push(MigrationChange { migration: self.migration, path: path.to_owned(), message, applied });
+    }
+}
+
+fn parse_edition(edition: &str) -> Option<schema_v1::Edition> {
+    EDITIONS.iter().find(|e| e.as_str() == edition).copied()
+}
+
+fn join(table: &str, key: &str) -> String {
+    if table.is_empty() { key.to_owned() } else { format!("{}.{}", table, key) }
+}
+
+/// Renames `from` to `to` in place, keeping the order of the keys and the
+/// decor of the renamed one
+fn rename_key(table: &mut dyn TableLike, from: &str, to: &str) {
+    let keys: Vec<String> = table.iter().map(|(key, _)| key.to_owned()).collect();
+    let mut moved = vec![];
+    for key in keys.iter().skip_while(|key| *key != from) {
+        let mut formatted = table.key(key).cloned().unwrap_or_else(|| Key::new(key.as_str()));
+        if key == from {
+            let decor = formatted.leaf_decor().to_owned();
+            formatted = Key::new(to);
+            *formatted.leaf_decor_mut() = decor;
+        }
+        if let Some(item) = table.remove(key) {
+            moved.push((formatted, item));
+        }
+    }
+    for (key, item) in moved {
+        table.entry_format(&key).or_insert(item);
+    }
+}
+
+/// Renames the underscore keys of `aliases` found in `table`, unless the
+/// kebab-case key is also there
+fn rename_aliases(table: &mut dyn TableLike, path: &str, aliases: &[(&str, &str)], changes: &mut Changes) {
+    for (alias, key) in aliases {
+        if !table.contains_key(alias) {
+            continue;
+        }
+        if table.contains_key(key) {
+            changes.skipped(&join(path, alias), format!("`{}` is also set, remove one of them", key));
+        } else {
+            rename_key(table, alias, key);
+            changes.applied(&join(path, alias), format!("renamed to `{}`", key));
+        }
+    }
+}
+
+fn migrate_project_table(document: &mut ManifestDocument, changes: &mut Changes) {
+    let root = document.document_mut().as_table_mut();
+    if !root.contains_key("project") {
+        return;
+    }
+    if root.contains_key("package") {
+        changes.skipped("project", "`[package]` is also set, merge them by hand".to_owned());
+    } else {
+        rename_key(root, "project", "package");
+        changes.applied("project", "renamed to `[package]`".to_owned());
+    }
+}
+
+fn migrate_underscore_keys(document: &mut ManifestDocument, changes: &mut Changes) {
+    let root = document.document_mut().as_table_mut();
+    rename_aliases(root, "", &TABLE_ALIASES, changes);
+    let platforms = root.get_mut("target").and_then(Item::as_table_like_mut);
+    for (platform, item) in platforms.into_iter().flat_map(|platforms| platforms.iter_mut()) {
+        if let Some(tables) = item.as_table_like_mut() {
+            rename_aliases(tables, &format!("target.'{}'", platform.get()), &TABLE_ALIASES, changes);
+        }
+    }
+
+    if let Some(lib) = root.get_mut("lib").and_then(Item::as_table_like_mut) {
+        rename_aliases(lib, "lib", &TARGET_ALIASES, changes);
+    }
+    for kind in &["bin", "example", "test", "bench"] {
+        let targets = root.get_mut(kind).and_then(Item::as_array_of_tables_mut);
+        for (index, target) in targets.into_iter().flat_map(|targets| targets.iter_mut()).enumerate() {
+            let name = target.get("name").and_then(Item::as_str).map(str::to_owned).unwrap_or_else(|| index.to_string());
+            rename_aliases(target, &format!("{}.{}", kind, name), &TARGET_ALIASES, changes);
+        }
+    }
+
+    document.for_each_dependency_table(|table, deps| {
+        let deps = match deps.as_table_like_mut() {
+            Some(deps) => deps,
+            None => return,
+        };
+        for (name, dep) in deps.iter_mut() {
+            if let Some(dep) = dep.as_table_like_mut() {
+                rename_aliases(dep, &format!("{}.{}", table, name.get()), &DEPENDENCY_ALIASES, changes);
+            }
+        }
+    });
+}
+
+/// The `[patch]` source and package name of a `[replace]` package id spec,
+/// e.g. `crates-io` and `foo` for `foo:1.0.0`
+fn replaced_package(spec: &str) -> (String, String) {
+    let (source, package) = match spec.rsplit_once('#') {
+        Some((source, package)) => (Some(source), package),
+        None => (None, spec),
+    };
+    let name = package.split([':', '@']).next().unwrap_or(package).to_owned();
+    let source = match source.map(|source| source.trim_start_matches("registry+").trim_start_matches("git+")) {
+        Some(url) if !url.contains("github.com/rust-lang/crates.io-index") && !url.contains("index.crates.io") => url.to_owned(),
+        _ => "crates-io".to_owned(),
+    };
+    (source, name)
+}
+
+fn implicit_table() -> Item {
+    let mut table = Table::new();
+    table.set_implicit(true);
+    Item::Table(table)
+}
+
+fn migrate_replace(document: &mut ManifestDocument, changes: &mut Changes) {
+    let root = document.document_mut().as_table_mut();
+    let mut replace = match root.remove("replace") {
+        Some(Item::Table(replace)) => replace,
+        Some(other) => {
+            root.insert("replace", other);
+            return;
+        },
+        None => return,
+    };
+    let specs: Vec<String> = replace.iter().map(|(spec, _)| spec.to_owned()).collect();
+    for spec in specs {
+        let (source, name) = replaced_package(&spec);
+        let path = format!("replace.'{}'", spec);
+        let patch = root.entry("patch").or_insert_with(implicit_table);
+        let patched = match patch.as_table_like_mut() {
+            Some(patch) => patch.entry(&source).or_insert_with(implicit_table),
+            None => {
+                changes.skipped(&path, "`patch` is not a table".to_owned());
+                continue;
+            },
+        };
+        match patched.as_table_like_mut() {
+            Some(patched) if !patched.contains_key(&name) => {
+                if let Some(dep) = replace.remove(&spec) {
+                    patched.insert(&name, dep);
+                }
+                changes.applied(&path, format!("moved to `patch.{}.{}`", source, name));
+            },
+            _ => changes.skipped(&path, format!("`patch.{}.{}` is already set", source, name)),
+        }
+    }
+    if !replace.is_empty() {
+        root.insert("replace", Item::Table(replace));
+    }
+}
+
+/// `MIT/Apache-2.0` as `MIT OR Apache-2.0`, or `None` when the license is
+/// not `/`-separated
+fn spdx_license(license: &str) -> Option<String> {
+    if !license.contains('/') {
+        return None;
+    }
+    let licenses: Vec<_> = license.split('/').map(str::trim).collect();
+    if licenses.iter().any(|license| license.is_empty() || license.contains(' ')) {
+        return None;
+    }
+    Some(licenses.join(" OR "))
+}
+
+/// Replaces a value keeping its comments and whitespace
+fn set_value(item: &mut Item, value: &str) {
+    if let Some(current) = item.as_value_mut() {
+        let decor = current.decor().to_owned();
+        let mut new = Value::from(value);
+        *new.decor_mut() = decor;
+        *current = new;
+    }
+}
+
+fn migrate_license(document: &mut ManifestDocument, changes: &mut Changes) {
+    let root = document.document_mut().as_table_mut();
+    for table in &["package", "project", "workspace.package"] {
+        let package = table.split('.').try_fold(&mut *root as &mut dyn TableLike, |table, key| {
+            table.get_mut(key).and_then(Item::as_table_like_mut)
+        });
+        let license = match package.and_then(|package| package.get_mut("license")) {
+            Some(license) => license,
+            None => continue,
+        };
+        if let Some(spdx) = license.as_str().and_then(spdx_license) {
+            changes.applied(&join(table, "license"), format!("rewritten to `{}`", spdx));
+            set_value(license, &spdx);
+        }
+    }
+}
+
+/// The resolver an edition makes the default, when it changes it
+fn default_resolver(edition: schema_v1::Edition) -> Option<&'static str> {
+    match edition {
+        schema_v1::Edition::Edition2015 | schema_v1::Edition::Edition2018 => None,
+        schema_v1::Edition::Edition2021 => Some("2"),
+        schema_v1::Edition::Edition2024 => Some("3"),
+    }
+}
+
+/// Upgrades the `edition` of `table` to `target`, where a missing edition
+/// is the 2015 one and an inherited edition is upgraded in the workspace
+fn upgrade_edition(table: &mut dyn TableLike, path: &str, target: schema_v1::Edition, changes: &mut Changes) {
+    let current = match table.get("edition") {
+        Some(item) => match item.as_str() {
+            Some(edition) => match parse_edition(edition) {
+                Some(edition) => edition,
+                None => return changes.skipped(&join(path, "edition"), format!("unknown edition `{}`", edition)),
+            },
+            None => return,
+        },
+        None => schema_v1::Edition::Edition2015,
+    };
+    if current >= target {
+        return;
+    }
+    let mut message = format!("upgraded from {} to {}", current, target);
+    let resolver = EDITIONS.iter()
+        .filter(|edition| **edition > current && **edition <= target)
+        .filter_map(|edition| default_resolver(*edition))
+        .next_back();
+    if let (Some(resolver), false) = (resolver, table.contains_key("resolver")) {
+        message.push_str(&format!(", which makes resolver \"{}\" the default", resolver));
+    }
+    match table.get_mut("edition") {
+        Some(edition) => set_value(edition, target.as_str()),
+        None => {
+            table.insert("edition", toml_edit::value(target.as_str()));
+        },
+    }
+    changes.applied(&join(path, "edition"), message);
+}
+
+fn migrate_edition(document: &mut ManifestDocument, target: schema_v1::Edition, changes: &mut Changes) {
+    if target >= schema_v1::Edition::Edition2024 {
+        migrate_project_table(document, changes);
+        migrate_underscore_keys(document, changes);
+    }
+    let root = document.document_mut().as_table_mut();
+    let is_virtual = !root.contains_key("package") && !root.contains_key("project");
+    for key in &["package", "project"] {
+        if let Some(package) = root.get_mut(key).and_then(Item::as_table_like_mut) {
+            upgrade_edition(package, key, target, changes);
+        }
+    }
+    let workspace = match root.get_mut("workspace").and_then(Item::as_table_like_mut) {
+        Some(workspace) => workspace,
+        None => return,
+    };
+    if let Some(package) = workspace.get_mut("package").and_then(Item::as_table_like_mut) {
+        upgrade_edition(package, "workspace.package", target, changes);
+    }
+    if let (true, false, Some(resolver)) = (is_virtual, workspace.contains_key("resolver"), default_resolver(target)) {
+        workspace.insert("resolver", toml_edit::value(resolver));
+        changes.applied(
+            "workspace.resolver",
+            format!("set to \"{}\" since a virtual workspace has no edition to imply it", resolver),
+        );
+    }
+}
+
+/// Applies `migrations` to the manifest in order, returning what each one
+/// changed or had to skip
+pub fn migrate(document: &mut ManifestDocument, migrations: &[Migration]) -> Vec<MigrationChange> {
+    let mut changes = vec![];
+    for migration in migrations {
+        let mut recorded = Changes { migration: *migration, changes: &mut changes };
+        match migration {
+            Migration::ProjectTable => migrate_project_table(document, &mut recorded),
+            Migration::UnderscoreKeys => migrate_underscore_keys(document, &mut recorded),
+            Migration::ReplaceToPatch => migrate_replace(document, &mut recorded),
+            Migration::SpdxLicense => migrate_license(document, &mut recorded),
+            Migration::Edition(edition) => migrate_edition(document, *edition, &mut recorded),
+        }
+    }
+    changes
+}
+
+/// Reports what `migrations` would change in the manifest `data`, along
+/// with the diff of the migrated manifest
+pub fn dry_run(data: &str, migrations: &[Migration]) -> Result<MigrationReport> {
+    let mut document = ManifestDocument::parse(data)?;
+    let changes = migrate(&mut document, migrations);
+    Ok(MigrationReport { changes, diff: line_diff(data, &document.to_string()) })
+}
+
+/// Applies `migrations` to every manifest of the workspace, without saving
+/// them, so `WorkspaceManifest::diff` shows what a dry run would change
+pub fn migrate_workspace(workspace: &mut WorkspaceDocument, migrations: &[Migration]) -> Vec<(PathBuf, Vec<MigrationChange>)> {
+    workspace.manifests.iter_mut()
+        .map(|manifest| {
+            let changes = migrate(&mut manifest.document, migrations);
+            (manifest.path(), changes)
+        })
+        .filter(|(_, changes)| !changes.is_empty())
+        .collect()
+}
+
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let (n, m) = (old.len(), new.len());
+    let mut common = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut lines = vec![];
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+/// Unified diff of two texts by line, without file headers, which is
+/// empty when they are the same
+pub fn line_diff(old: &str, new: &str) -> String {
+    let old: Vec<_> = old.lines().collect();
+    let new: Vec<_> = new.lines().collect();
+    let lines = diff_lines(&old, &new);
+
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for (index, line) in lines.iter().enumerate() {
+        if let Line::Same(_) = line {
+            continue;
+        }
+        let start = index.saturating_sub(DIFF_CONTEXT);
+        let end = (index + 1 + DIFF_CONTEXT).min(lines.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut positions = vec![(0, 0)];
+    for line in &lines {
+        let (old, new) = *positions.last().unwrap();
+        positions.push(match line {
+            Line::Same(_) => (old + 1, new + 1),
+            Line::Removed(_) => (old + 1, new),
+            Line::Added(_) => (old, new + 1),
+        });
+    }
+    let range = |start: usize, end: usize| {
+        let count = end - start;
+        format!("{},{}", if count == 0 { start } else { start + 1 }, count)
+    };
+
+    let mut diff = String::new();
+    for (start, end) in hunks {
+        let ((old_start, new_start), (old_end, new_end)) = (positions[start], positions[end]);
+        diff.push_str(&format!("@@ -{} +{} @@\n", range(old_start, old_end), range(new_start, new_end)));
+        for line in &lines[start..end] {
+            let (prefix, text) = match line {
+                Line::Same(text) => (' ', text),
+                Line::Removed(text) => ('-', text),
+                Line::Added(text) => ('+', text),
+            };
+            diff.push(prefix);
+            diff.push_str(text);
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    extern crate tempfile;
+
+    use std::fs;
+    use std::str::FromStr;
+
+    use super::{ dry_run, line_diff, migrate, migrate_workspace, Migration };
+    use crate::{ Error, ManifestDocument, WorkspaceDocument };
+
+    fn migrated(data: &str, migrations: &[Migration]) -> String {
+        let mut document = ManifestDocument::parse(data).unwrap();
+        migrate(&mut document, migrations);
+        document.to_string()
+    }
+
+    #[test]
+    fn select_migrations_by_name() {
+        for migration in Migration::ALL.iter().chain(&[Migration::Edition(schema_v1::Edition::Edition2021)]) {
+            assert_eq!(Ok(*migration), Migration::from_str(&migration.to_string()).map_err(|err| err.to_string()));
+        }
+        match Migration::from_str("edition-2027") {
+            Ok (_) => assert!(false, "should not know an edition 2027"),
+            Err (Error::UnknownMigration(name)) => assert_eq!("edition-2027", name),
+            Err (err) => assert!(false, "expected UnknownMigration: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn rename_project_and_underscore_keys_in_place() {
+        let data = concat!(
+            "[project]\n",
+            "name = \"foo\" # the name\n",
+            "version = \"0.1.0\"\n",
+            "\n",
+            "[lib]\n",
+            "crate_type = [\"cdylib\"]\n",
+            "doctest = false\n",
+            "\n",
+            "[dependencies]\n",
+            "serde = { version = \"1.0\", default_features = false, features = [\"derive\"] }\n",
+            "\n",
+            "[dev_dependencies.log]\n",
+            "version = \"0.4\"\n",
+            "default_features = false\n",
+            "\n",
+            "[target.'cfg(unix)'.build_dependencies]\n",
+            "cc = \"1.0\"\n",
+        );
+        assert_eq!(concat!(
+            "[package]\n",
+            "name = \"foo\" # the name\n",
+            "version = \"0.1.0\"\n",
+            "\n",
+            "[lib]\n",
+            "crate-type = [\"cdylib\"]\n",
+            "doctest = false\n",
+            "\n",
+            "[dependencies]\n",
+            "serde = { version = \"1.0\", default-features = false, features = [\"derive\"] }\n",
+            "\n",
+            "[dev-dependencies.log]\n",
+            "version = \"0.4\"\n",
+            "default-features = false\n",
+            "\n",
+            "[target.'cfg(unix)'.build-dependencies]\n",
+            "cc = \"1.0\"\n",
+        ), migrated(data, &[Migration::ProjectTable, Migration::UnderscoreKeys]));
+        assert_eq!(data, migrated(data, &[Migration::SpdxLicense]));
+    }
+
+    #[test]
+    fn skip_conflicting_spellings() {
+        let data = concat!(
+            "[package]\n",
+            "name = \"foo\"\n",
+            "\n",
+            "[project]\n",
+            "name = \"foo\"\n",
+            "\n",
+            "[dependencies]\n",
+            "serde = { version = \"1.0\", default-features = false, default_features = true }\n",
+        );
+        let report = dry_run(data, &[Migration::ProjectTable, Migration::UnderscoreKeys]).unwrap();
+        assert_eq!("", report.diff);
+        let changes: Vec<_> = report.changes.iter().map(|change| change.to_string()).collect();
+        assert_eq!(vec![
+            "[project-table] skipped project: `[package]` is also set, merge them by hand",
+            "[underscore-keys] skipped dependencies.serde.default_features: `default-features` is also set, remove one of them",
+        ], changes);
+    }
+
+    #[test]
+    fn move_replace_to_patch() {
+        let data = concat!(
+            "[package]\n",
+            "name = \"foo\"\n",
+            "\n",
+            "[replace]\n",
+            "\"log:0.4.8\" = { path = \"../log\" }\n",
+            "\"https://example.com/index#bar:1.0.0\" = { git = \"https://example.com/bar\" }\n",
+            "\n",
+            "[patch.crates-io]\n",
+            "serde = { path = \"../serde\" }\n",
+        );
+        assert_eq!(concat!(
+            "[package]\n",
+            "name = \"foo\"\n",
+            "\n",
+            "[patch.crates-io]\n",
+            "serde = { path = \"../serde\" }\n",
+            "log = { path = \"../log\" }\n",
+            "\n",
+            "[patch.\"https://example.com/index\"]\n",
+            "bar = { git = \"https://example.com/bar\" }\n",
+        ), migrated(data, &[Migration::ReplaceToPatch]));
+
+        let conflicting = data.replace("log:0.4.8", "serde:1.0.0");
+        let report = dry_run(&conflicting, &[Migration::ReplaceToPatch]).unwrap();
+        assert_eq!(vec![false, true], report.changes.iter().map(|change| change.applied).collect::<Vec<_>>());
+        assert!(migrated(&conflicting, &[Migration::ReplaceToPatch]).contains("[replace]\n\"serde:1.0.0\""));
+    }
+
+    #[test]
+    fn rewrite_licenses_as_spdx() {
+        let data = concat!(
+            "[package]\n",
+            "license = \"MIT/Apache-2.0\" # dual\n",
+            "\n",
+            "[workspace.package]\n",
+            "license = \"MIT OR Apache-2.0\"\n",
+        );
+        assert_eq!(concat!(
+            "[package]\n",
+            "license = \"MIT OR Apache-2.0\" # dual\n",
+            "\n",
+            "[workspace.package]\n",
+            "license = \"MIT OR Apache-2.0\"\n",
+        ), migrated(data, &[Migration::SpdxLicense]));
+    }
+
+    #[test]
+    fn upgrade_editions() {
+        let edition_2024 = Migration::Edition(schema_v1::Edition::Edition2024);
+        let report = dry_run(concat!(
+            "[package]\n",
+            "name = \"foo\"\n",
+            "edition = \"2018\"\n",
+            "\n",
+            "[build_dependencies]\n",
+            "cc = { version = \"1.0\", default_features = false }\n",
+        ), &[edition_2024]).unwrap();
+        assert_eq!(concat!(
+            "@@ -1,6 +1,6 @@\n",
+            " [package]\n",
+            " name = \"foo\"\n",
+            "-edition = \"2018\"\n",
+            "+edition = \"2024\"\n",
+            " \n",
+            "-[build_dependencies]\n",
+            "-cc = { version = \"1.0\", default_features = false }\n",
+            "+[build-dependencies]\n",
+            "+cc = { version = \"1.0\", default-features = false }\n",
+        ), report.diff);
+        assert_eq!(
+            "[edition-2024] package.edition: upgraded from 2018 to 2024, which makes resolver \"3\" the default",
+            report.changes[2].to_string(),
+        );
+
+        let workspace = concat!(
+            "[workspace]\n",
+            "members = [\"crates/*\"]\n",
+            "\n",
+            "[workspace.package]\n",
+            "edition = \"2018\"\n",
+        );
+        assert_eq!(concat!(
+            "[workspace]\n",
+            "members = [\"crates/*\"]\n",
+            "resolver = \"2\"\n",
+            "\n",
+            "[workspace.package]\n",
+            "edition = \"2021\"\n",
+        ), migrated(workspace, &[Migration::Edition(schema_v1::Edition::Edition2021)]));
+
+        let inherited = "[package]\nname = \"foo\"\nedition.workspace = true\n";
+        assert_eq!(inherited, migrated(inherited, &[edition_2024]));
+        assert_eq!(
+            "[package]\nname = \"foo\"\nedition = \"2021\"\n",
+            migrated("[package]\nname = \"foo\"\n", &[Migration::Edition(schema_v1::Edition::Edition2021)]),
+        );
+    }
+
+    #[test]
+    fn dry_run_workspace_migrations() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = "[workspace]\nmembers = [\"crates/*\"]\n";
+        let member = "[project]\nname = \"a\"\nversion = \"0.1.0\"\nlicense = \"MIT/Apache-2.0\"\n";
+        fs::write(dir.path().join("Cargo.toml"), root).unwrap();
+        fs::create_dir_all(dir.path().join("crates/a")).unwrap();
+        fs::write(dir.path().join("crates/a/Cargo.toml"), member).unwrap();
+
+        let mut workspace = WorkspaceDocument::load(dir.path()).unwrap();
+        let changes = migrate_workspace(&mut workspace, &[Migration::ProjectTable, Migration::SpdxLicense]);
+        assert_eq!(vec![dir.path().join("crates/a/Cargo.toml")], changes.iter().map(|(path, _)| path.to_owned()).collect::<Vec<_>>());
+        assert_eq!("", workspace.root().diff());
+        assert_eq!(format!(concat!(
+            "--- {0}\n",
+            "+++ {0}\n",
+            "@@ -1,4 +1,4 @@\n",
+            "-[project]\n",
+            "+[package]\n",
+            " name = \"a\"\n",
+            " version = \"0.1.0\"\n",
+            "-license = \"MIT/Apache-2.0\"\n",
+            "+license = \"MIT OR Apache-2.0\"\n",
+        ), dir.path().join("crates/a/Cargo.toml").display()), workspace.manifests[1].diff());
+        assert_eq!(member, fs::read_to_string(dir.path().join("crates/a/Cargo.toml")).unwrap());
+    }
+
+    #[test]
+    fn diff_lines_with_context() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\n";
+        assert_eq!(concat!(
+            "@@ -1,4 +1,4 @@\n",
+            " a\n",
+            "-b\n",
+            "+B\n",
+            " c\n",
+            " d\n",
+            "@@ -7,3 +7,2 @@\n",
+            " g\n",
+            " h\n",
+            "-i\n",
+        ), line_diff(old, new));
+        assert_eq!("", line_diff(old, old));
+    }
+}
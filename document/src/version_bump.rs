@@ -0,0 +1,439 @@
+use std::collections::{ BTreeMap, BTreeSet };
+use std::path::{ PathBuf };
+
+use semver::{ Identifier, Version, VersionReq };
+use toml_edit::{ Item, Value };
+
+use crate::{ Error, Result, WorkspaceDocument, WorkspaceManifest };
+
+/// How a version is bumped
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+    /// Starts or continues a pre-release with this label, so `1.2.3`
+    /// becomes `1.2.4-alpha.1` and then `1.2.4-alpha.2`
+    Pre(String),
+}
+
+impl Bump {
+    /// The bumped version, where bumping a pre-release to the version it
+    /// precedes releases it, e.g. a patch bump of `1.2.4-alpha.1` is `1.2.4`
+    pub fn apply(&self, version: &Version) -> Version {
+        let mut bumped = version.to_owned();
+        let releases = version.is_prerelease() && match self {
+            Bump::Major => version.minor == 0 && version.patch == 0,
+            Bump::Minor => version.patch == 0,
+            Bump::Patch => true,
+            Bump::Pre(_) => false,
+        };
+        if releases {
+            bumped.pre.clear();
+            return bumped;
+        }
+        match self {
+            Bump::Major => bumped.increment_major(),
+            Bump::Minor => bumped.increment_minor(),
+            Bump::Patch => bumped.increment_patch(),
+            Bump::Pre(label) => {
+                let label = Identifier::AlphaNumeric(label.to_owned());
+                let number = match version.pre.as_slice() {
+                    [current, Identifier::Numeric(n)] if *current == label => n + 1,
+                    _ => {
+                        if !version.is_prerelease() {
+                            bumped.increment_patch();
+                        }
+                        1
+                    },
+                };
+                bumped.pre = vec![label, Identifier::Numeric(number)];
+            },
+        }
+        bumped
+    }
+}
+
+/// Whether `new` is a semver incompatible release after `old`, which a
+/// pre-release always is
+pub fn is_breaking(old: &Version, new: &Version) -> bool {
+    v1::compatibility(old) != v1::compatibility(new) || new.is_prerelease()
+}
+
+/// A package whose version was bumped
+#[derive(Clone, Debug, PartialEq)]
+pub struct BumpedPackage {
+    pub name: String,
+    pub old: Version,
+    pub new: Version,
+}
+
+/// A dependency requirement rewritten to match a bumped version
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequirementUpdate {
+    /// Path of the manifest declaring the dependency
+    pub manifest: PathBuf,
+    /// Path of the dependency table, e.g. `target.'cfg(unix)'.dependencies`
+    pub table: String,
+    /// Key the dependency is declared under
+    pub dependency: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// Everything changed by `bump_version`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BumpReport {
+    /// The bumped package, along with every member sharing its version
+    /// through `version.workspace = true`
+    pub bumped: Vec<BumpedPackage>,
+    pub requirements: Vec<RequirementUpdate>,
+    /// Members which are not bumped but depend on a bumped package in their
+    /// public API, directly or through each other, after a breaking bump
+    pub needs_bump: Vec<String>,
+}
+
+/// Rewrites `req` to require `version`, keeping its operator and the
+/// number of version components when it is a single comparison
+fn updated_requirement(req: &str, version: &Version) -> String {
+    let req = req.trim();
+    let (op, current) = req.split_at(req.find(|c: char| c.is_ascii_digit()).unwrap_or(0));
+    let op = op.trim();
+    let is_single = ["", "^", "~", "="].contains(&op) && !current.contains([',', '*']);
+    if !is_single {
+        return version.to_string();
+    }
+    if op == "=" || version.is_prerelease() {
+        return format!("{}{}", op, version);
+    }
+    let parts = [version.major, version.minor, version.patch];
+    let precision = current.split('.').count().clamp(1, 3);
+    let version = parts[..precision].iter().map(|part| part.to_string()).collect::<Vec<_>>().join(".");
+    format!("{}{}", op, version)
+}
+
+/// Replaces a string value, keeping its comments and spacing
+fn set_string(item: &mut Item, value: &str) {
+    let mut replaced = Value::from(value);
+    if let Some(current) = item.as_value() {
+        *replaced.decor_mut() = current.decor().to_owned();
+    }
+    *item = Item::Value(replaced);
+}
+
+/// Whether a dependency table is for normal dependencies, rather than
+/// development, build or workspace ones
+fn is_normal_table(table: &str) -> bool {
+    table == "dependencies" || table.ends_with(".dependencies") && table != "workspace.dependencies"
+}
+
+/// Updates the requirements of `manifest` on the `bumped` packages which no
+/// longer match, returning every package it depends on in its public API.
+///
+/// Since `public` is unstable and rarely set, any normal dependency not
+/// marked `public = false` counts as public.
+fn update_dependents(
+    manifest: &mut WorkspaceManifest,
+    bumped: &BTreeMap<String, Version>,
+    updates: &mut Vec<RequirementUpdate>,
+) -> Result<BTreeSet<String>> {
+    let path = manifest.path();
+    let mut public = BTreeSet::new();
+    let mut result = Ok(());
+    manifest.document.for_each_dependency_table(|table, deps| {
+        let deps = match deps.as_table_like_mut() {
+            Some(deps) => deps,
+            None => return,
+        };
+        for (key, dep) in deps.iter_mut() {
+            if dep.is_str() {
+                if is_normal_table(table) {
+                    public.insert(key.get().to_owned());
+                }
+                continue;
+            }
+            let dep = match dep.as_table_like_mut() {
+                Some(dep) => dep,
+                None => continue,
+            };
+            let package = dep.get("package").and_then(|p| p.as_str()).unwrap_or(key.get()).to_owned();
+            if is_normal_table(table) && dep.get("public").and_then(|p| p.as_bool()) != Some(false) {
+                public.insert(package.to_owned());
+            }
+            let version = match bumped.get(&package) {
+                Some(version) => version,
+                None => continue,
+            };
+            if dep.get("path").is_none() {
+                continue;
+            }
+            let req = match dep.get_mut("version") {
+                Some(req) => req,
+                None => continue,
+            };
+            let old = match req.as_str() {
+                Some(old) => old.to_owned(),
+                None => continue,
+            };
+            match VersionReq::parse(&old) {
+                Ok(parsed) if parsed.matches(version) => continue,
+                Ok(_) => {},
+                Err(err) => {
+                    result = Err(Error::InvalidRequirement(path.to_owned(), key.get().to_owned(), err));
+                    continue;
+                },
+            }
+            let new = updated_requirement(&old, version);
+            set_string(req, &new);
+            updates.push(RequirementUpdate {
+                manifest: path.to_owned(),
+                table: table.to_owned(),
+                dependency: key.get().to_owned(),
+                old,
+                new,
+            });
+        }
+    });
+    result.map(|_| public)
+}
+
+/// Whether the `[package]` version of `manifest` is `version.workspace = true`
+fn inherits_version(manifest: &WorkspaceManifest) -> bool {
+    let root = manifest.document.document().as_table();
+    root.get("package").or_else(|| root.get("project"))
+        .and_then(|package| package.get("version"))
+        .and_then(|version| version.get("workspace"))
+        .and_then(|workspace| workspace.as_bool())
+        == Some(true)
+}
+
+fn parse_version(package: &str, item: Option<&Item>) -> Result<Version> {
+    let version = item.and_then(|v| v.as_str()).ok_or_else(|| Error::MissingVersion(package.to_owned()))?;
+    Version::parse(version).map_err(|_| Error::MissingVersion(package.to_owned()))
+}
+
+/// Bumps the version of the workspace member `package` and updates the
+/// `path` dependencies of every member which no longer match it.
+///
+/// A version inherited with `version.workspace = true` is bumped in
+/// `[workspace.package]`, which bumps every member inheriting it as well.
+/// Requirements keep their operator and precision where they can, so
+/// `0.3` becomes `0.4`, while `[workspace.dependencies]` entries are
+/// updated along with those of members. Nothing is written to disk until
+/// the workspace is saved.
+pub fn bump_version(workspace: &mut WorkspaceDocument, package: &str, bump: &Bump) -> Result<BumpReport> {
+    let member = workspace.member(package).ok_or_else(|| Error::UnknownPackage(package.to_owned()))?;
+    let mut report = BumpReport::default();
+    if inherits_version(member) {
+        let shared = workspace.root_mut().document.document_mut()
+            .get_mut("workspace")
+            .and_then(|workspace| workspace.get_mut("package"))
+            .and_then(|package| package.get_mut("version"))
+            .ok_or_else(|| Error::MissingVersion(package.to_owned()))?;
+        let old = parse_version(package, Some(shared))?;
+        let new = bump.apply(&old);
+        set_string(shared, &new.to_string());
+        for member in workspace.members().filter(|m| inherits_version(m)) {
+            let name = member.package_name().unwrap_or_default().to_owned();
+            report.bumped.push(BumpedPackage { name, old: old.to_owned(), new: new.to_owned() });
+        }
+    } else {
+        let member = workspace.member_mut(package).ok_or_else(|| Error::UnknownPackage(package.to_owned()))?;
+        let version = member.document.package_mut()
+            .and_then(|package| package.get_mut("version"))
+            .ok_or_else(|| Error::MissingVersion(package.to_owned()))?;
+        let old = parse_version(package, Some(version))?;
+        let new = bump.apply(&old);
+        set_string(version, &new.to_string());
+        report.bumped.push(BumpedPackage { name: package.to_owned(), old, new });
+    }
+
+    let bumped: BTreeMap<_, _> = report.bumped.iter().map(|b| (b.name.to_owned(), b.new.to_owned())).collect();
+    let mut public_deps = BTreeMap::new();
+    for manifest in workspace.manifests.iter_mut() {
+        let public = update_dependents(manifest, &bumped, &mut report.requirements)?;
+        if let Some(name) = manifest.package_name() {
+            public_deps.insert(name.to_owned(), public);
+        }
+    }
+
+    let breaking = report.bumped.iter().any(|b| is_breaking(&b.old, &b.new));
+    if breaking {
+        let mut affected: BTreeSet<_> = bumped.keys().cloned().collect();
+        loop {
+            let next: Vec<_> = public_deps.iter()
+                .filter(|(name, deps)| !affected.contains(*name) && deps.iter().any(|dep| affected.contains(dep)))
+                .map(|(name, _)| name.to_owned())
+                .collect();
+            if next.is_empty() {
+                break;
+            }
+            report.needs_bump.extend(next.iter().cloned());
+            affected.extend(next);
+        }
+        report.needs_bump.sort();
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    extern crate tempfile;
+
+    use std::fs;
+
+    use semver::Version;
+
+    use super::{ bump_version, updated_requirement, Bump };
+    use crate::{ write, Error, WorkspaceDocument };
+
+    fn bump(version: &str, bump: Bump) -> String {
+        bump.apply(&Version::parse(version).unwrap()).to_string()
+    }
+
+    #[test]
+    fn apply_bumps() {
+        assert_eq!("2.0.0", bump("1.2.3", Bump::Major));
+        assert_eq!("1.3.0", bump("1.2.3", Bump::Minor));
+        assert_eq!("1.2.4", bump("1.2.3", Bump::Patch));
+        assert_eq!("1.2.4-alpha.1", bump("1.2.3", Bump::Pre("alpha".to_owned())));
+        assert_eq!("1.2.4-alpha.2", bump("1.2.4-alpha.1", Bump::Pre("alpha".to_owned())));
+        assert_eq!("1.2.4-beta.1", bump("1.2.4-alpha.2", Bump::Pre("beta".to_owned())));
+        assert_eq!("1.2.4", bump("1.2.4-beta.1", Bump::Patch));
+        assert_eq!("2.0.0", bump("2.0.0-rc.1", Bump::Major));
+        assert_eq!("1.3.0", bump("1.2.4-rc.1", Bump::Minor));
+    }
+
+    #[test]
+    fn update_requirements_keeping_their_form() {
+        let version = Version::parse("0.4.0").unwrap();
+        assert_eq!("0.4", updated_requirement("0.3", &version));
+        assert_eq!("^0.4.0", updated_requirement("^0.3.1", &version));
+        assert_eq!("~0.4", updated_requirement("~0.3", &version));
+        assert_eq!("=0.4.0", updated_requirement("= 0.3.2", &version));
+        assert_eq!("0.4.0", updated_requirement(">=0.2, <0.4", &version));
+        assert_eq!("1.0.0-rc.1", updated_requirement("0.9", &Version::parse("1.0.0-rc.1").unwrap()));
+    }
+
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "Cargo.toml", r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.dependencies]
+a = { path = "crates/a", version = "0.3.0" }
+"#);
+        write(root, "crates/a/Cargo.toml", r#"
+[package]
+name = "a"
+version = "0.3.1" # released together with b
+"#);
+        write(root, "crates/b/Cargo.toml", r#"
+[package]
+name = "b"
+version = "1.0.0"
+
+[dependencies]
+# the core types
+a = { path = "../a", version = "0.3" }
+"#);
+        write(root, "crates/c/Cargo.toml", r#"
+[package]
+name = "c"
+version = "2.1.0"
+
+[dependencies.b]
+path = "../b"
+version = "1.0.0"
+"#);
+        write(root, "crates/d/Cargo.toml", r#"
+[package]
+name = "d"
+version = "0.1.0"
+
+[dependencies]
+a = { workspace = true, public = false }
+
+[dev-dependencies]
+b = { path = "../b" }
+"#);
+        dir
+    }
+
+    #[test]
+    fn bump_and_update_dependents() {
+        let dir = workspace();
+        let mut workspace = WorkspaceDocument::load(dir.path()).unwrap();
+        let report = match bump_version(&mut workspace, "a", &Bump::Minor) {
+            Ok (report) => report,
+            Err (err) => { assert!(false, "unexpected error: {}", err); return },
+        };
+        assert_eq!("0.4.0", report.bumped[0].new.to_string());
+        let updates: Vec<_> = report.requirements.iter()
+            .map(|u| (u.table.as_str(), u.dependency.as_str(), u.old.as_str(), u.new.as_str()))
+            .collect();
+        assert_eq!(vec![
+            ("workspace.dependencies", "a", "0.3.0", "0.4.0"),
+            ("dependencies", "a", "0.3", "0.4"),
+        ], updates);
+        assert_eq!(vec!["b".to_owned(), "c".to_owned()], report.needs_bump);
+
+        let saved = workspace.save().unwrap();
+        assert_eq!(3, saved.len());
+        let a = fs::read_to_string(dir.path().join("crates/a/Cargo.toml")).unwrap();
+        assert!(a.contains(r#"version = "0.4.0" # released together with b"#), "{}", a);
+        let b = fs::read_to_string(dir.path().join("crates/b/Cargo.toml")).unwrap();
+        assert!(b.contains("# the core types\na = { path = \"../a\", version = \"0.4\" }"), "{}", b);
+    }
+
+    #[test]
+    fn not_require_dependents_to_bump_for_compatible_release() {
+        let dir = workspace();
+        let mut workspace = WorkspaceDocument::load(dir.path()).unwrap();
+        let report = bump_version(&mut workspace, "b", &Bump::Patch).unwrap();
+        assert!(report.requirements.is_empty());
+        assert!(report.needs_bump.is_empty());
+        assert_eq!(1, workspace.save().unwrap().len());
+    }
+
+    #[test]
+    fn bump_version_inherited_from_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Cargo.toml", r#"
+[workspace]
+members = ["x", "y"]
+
+[workspace.package]
+version = "1.4.2"
+"#);
+        write(dir.path(), "x/Cargo.toml", "[package]\nname = \"x\"\nversion.workspace = true\n");
+        write(dir.path(), "y/Cargo.toml", r#"
+[package]
+name = "y"
+version.workspace = true
+
+[dependencies]
+x = { path = "../x", version = "=1.4.2" }
+"#);
+        let mut workspace = WorkspaceDocument::load(dir.path()).unwrap();
+        let report = bump_version(&mut workspace, "x", &Bump::Patch).unwrap();
+        let bumped: Vec<_> = report.bumped.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(vec!["x", "y"], bumped);
+        assert_eq!("=1.4.3", report.requirements[0].new);
+        assert!(workspace.root().document.to_string().contains(r#"version = "1.4.3""#));
+    }
+
+    #[test]
+    fn fail_on_unknown_package() {
+        let dir = workspace();
+        let mut workspace = WorkspaceDocument::load(dir.path()).unwrap();
+        match bump_version(&mut workspace, "z", &Bump::Major) {
+            Ok (_) => assert!(false, "should not bump a package outside of the workspace"),
+            Err (Error::UnknownPackage(name)) => assert_eq!("z", name),
+            Err (err) => assert!(false, "expected UnknownPackage: {:?}", err),
+        }
+    }
+}
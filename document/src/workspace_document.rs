@@ -0,0 +1,209 @@
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use globset::{ Glob };
+
+use crate::{ line_diff, Error, ManifestDocument, Result };
+
+pub const MANIFEST_FILE: &str = "Cargo.toml";
+
+/// A manifest of a workspace along with the directory it was read from
+#[derive(Clone, Debug)]
+pub struct WorkspaceManifest {
+    pub dir: PathBuf,
+    pub document: ManifestDocument,
+    original: String,
+}
+
+impl WorkspaceManifest {
+    pub fn load(dir: &Path) -> Result<WorkspaceManifest> {
+        let path = dir.join(MANIFEST_FILE);
+        let original = fs::read_to_string(&path).map_err(|err| Error::Io(path.to_owned(), err))?;
+        let document = ManifestDocument::parse(&original).map_err(|err| Error::Manifest(path, Box::new(err)))?;
+        Ok(WorkspaceManifest { dir: dir.to_owned(), document, original })
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(MANIFEST_FILE)
+    }
+
+    /// Name of the package, if the manifest has a `[package]`
+    pub fn package_name(&self) -> Option<&str> {
+        let root = self.document.document().as_table();
+        root.get("package").or_else(|| root.get("project"))?
+            .get("name")?
+            .as_str()
+    }
+
+    /// Whether the document differs from what was read
+    pub fn is_modified(&self) -> bool {
+        self.document.to_string() != self.original
+    }
+
+    /// Unified diff of the edits not saved yet, headed by the path
+    pub fn diff(&self) -> String {
+        let diff = line_diff(&self.original, &self.document.to_string());
+        if diff.is_empty() {
+            return diff;
+        }
+        format!("--- {0}\n+++ {0}\n{1}", self.path().display(), diff)
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        let data = self.document.to_string();
+        let path = self.path();
+        fs::write(&path, &data).map_err(|err| Error::Io(path, err))?;
+        self.original = data;
+        Ok(())
+    }
+}
+
+fn is_glob(part: &str) -> bool {
+    part.contains(['*', '?', '['])
+}
+
+/// Expands a `members` entry, where each path component may be a glob, to
+/// the directories it matches
+fn expand_member(root: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let mut dirs = vec![root.to_owned()];
+    for part in pattern.split('/').filter(|part| !part.is_empty() && *part != ".") {
+        if !is_glob(part) {
+            dirs = dirs.into_iter().map(|dir| dir.join(part)).collect();
+            continue;
+        }
+        let matcher = Glob::new(part)
+            .map_err(|err| Error::InvalidMemberPattern(pattern.to_owned(), err))?
+            .compile_matcher();
+        let mut matched = vec![];
+        for dir in dirs {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries {
+                let path = entry.map_err(|err| Error::Io(dir.to_owned(), err))?.path();
+                if path.is_dir() && path.file_name().is_some_and(|name| matcher.is_match(name)) {
+                    matched.push(path);
+                }
+            }
+        }
+        matched.sort();
+        dirs = matched;
+    }
+    Ok(dirs)
+}
+
+/// Every manifest of a workspace kept as written so edits across members
+/// can be made and saved together.
+///
+/// The root manifest comes first and is also a member when it has a
+/// `[package]`. Member directories without a `Cargo.toml` matched by a glob
+/// are skipped, as are those under an `exclude` path.
+#[derive(Clone, Debug)]
+pub struct WorkspaceDocument {
+    pub manifests: Vec<WorkspaceManifest>,
+}
+
+impl WorkspaceDocument {
+    pub fn load(root: &Path) -> Result<WorkspaceDocument> {
+        let root_manifest = WorkspaceManifest::load(root)?;
+        let workspace = root_manifest.document.to_schema_v1()
+            .map_err(|err| Error::Manifest(root_manifest.path(), Box::new(err)))?
+            .workspace
+            .ok_or_else(|| Error::MissingWorkspace(root_manifest.path()))?;
+        let excluded: Vec<_> = workspace.exclude.iter().flatten().map(|path| root.join(path)).collect();
+
+        let mut manifests = vec![root_manifest];
+        for pattern in workspace.members.iter().flatten() {
+            for dir in expand_member(root, pattern)? {
+                let is_new = !manifests.iter().any(|m| m.dir == dir);
+                let is_excluded = excluded.iter().any(|path| dir.starts_with(path));
+                if is_new && !is_excluded && (dir.join(MANIFEST_FILE).is_file() || !is_glob(pattern)) {
+                    manifests.push(WorkspaceManifest::load(&dir)?);
+                }
+            }
+        }
+        Ok(WorkspaceDocument { manifests })
+    }
+
+    pub fn root(&self) -> &WorkspaceManifest {
+        &self.manifests[0]
+    }
+
+    pub fn root_mut(&mut self) -> &mut WorkspaceManifest {
+        &mut self.manifests[0]
+    }
+
+    /// Manifests with a `[package]`
+    pub fn members(&self) -> impl Iterator<Item = &WorkspaceManifest> {
+        self.manifests.iter().filter(|m| m.package_name().is_some())
+    }
+
+    pub fn member(&self, name: &str) -> Option<&WorkspaceManifest> {
+        self.members().find(|m| m.package_name() == Some(name))
+    }
+
+    pub fn member_mut(&mut self, name: &str) -> Option<&mut WorkspaceManifest> {
+        self.manifests.iter_mut().find(|m| m.package_name() == Some(name))
+    }
+
+    /// Writes every modified manifest, returning their paths
+    pub fn save(&mut self) -> Result<Vec<PathBuf>> {
+        let mut saved = vec![];
+        for manifest in self.manifests.iter_mut().filter(|m| m.is_modified()) {
+            manifest.save()?;
+            saved.push(manifest.path());
+        }
+        Ok(saved)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    extern crate tempfile;
+
+    use std::fs;
+
+    use super::{ WorkspaceDocument };
+    use crate::{ write, Error };
+
+    #[test]
+    fn load_members_from_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Cargo.toml", r#"
+            [package]
+            name = "root"
+            version = "0.1.0"
+
+            [workspace]
+            members = ["crates/*", "tools/gen"]
+            exclude = ["crates/old"]
+        "#);
+        for member in &["crates/b", "crates/a", "crates/old", "tools/gen"] {
+            let name = member.rsplit('/').next().unwrap();
+            write(dir.path(), &format!("{}/Cargo.toml", member), &format!("[package]\nname = \"{}\"\nversion = \"0.1.0\"\n", name));
+        }
+        fs::create_dir_all(dir.path().join("crates/docs")).unwrap();
+
+        match WorkspaceDocument::load(dir.path()) {
+            Ok (workspace) => {
+                let names: Vec<_> = workspace.members().filter_map(|m| m.package_name()).collect();
+                assert_eq!(vec!["root", "a", "b", "gen"], names);
+                assert!(workspace.manifests.iter().all(|m| !m.is_modified()));
+            },
+            Err (err) => assert!(false, "unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn fail_without_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Cargo.toml", "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n");
+        match WorkspaceDocument::load(dir.path()) {
+            Ok (_) => assert!(false, "should require a [workspace]"),
+            Err (Error::MissingWorkspace(_)) => {},
+            Err (err) => assert!(false, "expected MissingWorkspace: {:?}", err),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+extern crate omni_manifest_lockfile as lockfile;
+extern crate omni_manifest_v1 as v1;
+extern crate semver;
+
+mod license_expression;
+mod license_policy;
+mod license_report;
+
+pub use self::license_expression::*;
+pub use self::license_policy::*;
+pub use self::license_report::*;
+
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// An SPDX expression which cannot be parsed, with the reason
+    InvalidExpression(String, String),
+    Lockfile(lockfile::Error),
+    /// Packages whose license the policy does not accept
+    Violations(Vec<LicenseViolation>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidExpression(expression, reason) => {
+                write!(f, "invalid license expression `{}`: {}", expression, reason)
+            },
+            Error::Lockfile(err) => write!(f, "{}", err),
+            Error::Violations(violations) => {
+                write!(f, "{} package(s) fail the license policy", violations.len())?;
+                for violation in violations {
+                    write!(f, "\n{}", violation)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
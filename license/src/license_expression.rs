@@ -0,0 +1,238 @@
+use std::fmt;
+
+use crate::{ Error, Result };
+
+/// A single license of an expression, such as `Apache-2.0`,
+/// `GPL-2.0+` or `GPL-2.0-only WITH Classpath-exception-2.0`
+#[derive(Clone, Debug, PartialEq)]
+pub struct LicenseTerm {
+    pub id: String,
+    /// Written with a trailing `+`
+    pub or_later: bool,
+    /// Exception added with `WITH`
+    pub exception: Option<String>,
+}
+
+impl LicenseTerm {
+    /// The license without its exception, e.g. `GPL-2.0+`
+    pub fn license(&self) -> String {
+        if self.or_later { format!("{}+", self.id) } else { self.id.to_owned() }
+    }
+}
+
+impl fmt::Display for LicenseTerm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.license())?;
+        if let Some(exception) = &self.exception {
+            write!(f, " WITH {}", exception)?;
+        }
+        Ok(())
+    }
+}
+
+/// A parsed SPDX license expression of a `license` field.
+///
+/// The `/` separator of old manifests is read as `OR`, so `MIT/Apache-2.0`
+/// displays as `MIT OR Apache-2.0`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LicenseExpression {
+    License(LicenseTerm),
+    /// Every one of the licenses applies
+    And(Vec<LicenseExpression>),
+    /// Any one of the licenses may be chosen
+    Or(Vec<LicenseExpression>),
+}
+
+impl LicenseExpression {
+    pub fn parse(expression: &str) -> Result<LicenseExpression> {
+        let invalid = |reason: String| Error::InvalidExpression(expression.to_owned(), reason);
+        let tokens = tokenize(expression);
+        let mut parser = Parser { tokens: &tokens, next: 0 };
+        let parsed = parser.or().map_err(invalid)?;
+        match parser.peek() {
+            Some(token) => Err(invalid(format!("unexpected `{}`", token))),
+            None => Ok(parsed),
+        }
+    }
+
+    /// Every license named by the expression, in order
+    pub fn terms(&self) -> Vec<&LicenseTerm> {
+        match self {
+            LicenseExpression::License(term) => vec![term],
+            LicenseExpression::And(all) | LicenseExpression::Or(all) => all.iter().flat_map(|e| e.terms()).collect(),
+        }
+    }
+}
+
+impl fmt::Display for LicenseExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LicenseExpression::License(term) => write!(f, "{}", term),
+            LicenseExpression::And(all) => {
+                let all: Vec<_> = all.iter()
+                    .map(|e| match e {
+                        LicenseExpression::Or(_) => format!("({})", e),
+                        _ => e.to_string(),
+                    })
+                    .collect();
+                write!(f, "{}", all.join(" AND "))
+            },
+            LicenseExpression::Or(all) => {
+                let all: Vec<_> = all.iter().map(|e| e.to_string()).collect();
+                write!(f, "{}", all.join(" OR "))
+            },
+        }
+    }
+}
+
+fn tokenize(expression: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut word = String::new();
+    for c in expression.chars() {
+        if c.is_whitespace() || ['(', ')', '/'].contains(&c) {
+            if !word.is_empty() {
+                tokens.push(word.split_off(0));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+fn is_keyword(token: &str, keyword: &str) -> bool {
+    token.eq_ignore_ascii_case(keyword)
+}
+
+/// Recursive descent over the tokens, where `WITH` binds tighter than
+/// `AND`, which binds tighter than `OR`
+struct Parser<'a> {
+    tokens: &'a [String],
+    next: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.next).map(|t| t.as_str())
+    }
+
+    fn take(&mut self) -> std::result::Result<&str, String> {
+        let token = self.tokens.get(self.next).ok_or_else(|| "unexpected end of expression".to_owned())?;
+        self.next += 1;
+        Ok(token)
+    }
+
+    fn or(&mut self) -> std::result::Result<LicenseExpression, String> {
+        let mut all = vec![self.and()?];
+        while self.peek().is_some_and(|t| is_keyword(t, "OR") || t == "/") {
+            self.next += 1;
+            all.push(self.and()?);
+        }
+        Ok(if all.len() == 1 { all.remove(0) } else { LicenseExpression::Or(all) })
+    }
+
+    fn and(&mut self) -> std::result::Result<LicenseExpression, String> {
+        let mut all = vec![self.primary()?];
+        while self.peek().is_some_and(|t| is_keyword(t, "AND")) {
+            self.next += 1;
+            all.push(self.primary()?);
+        }
+        Ok(if all.len() == 1 { all.remove(0) } else { LicenseExpression::And(all) })
+    }
+
+    fn primary(&mut self) -> std::result::Result<LicenseExpression, String> {
+        let token = self.take()?.to_owned();
+        if token == "(" {
+            let inner = self.or()?;
+            return match self.take() {
+                Ok(")") => Ok(inner),
+                Ok(token) => Err(format!("expected `)` but found `{}`", token)),
+                Err(_) => Err("unclosed `(`".to_owned()),
+            };
+        }
+        let id = identifier(&token)?;
+        let (id, or_later) = match id.strip_suffix('+') {
+            Some(id) => (id.to_owned(), true),
+            None => (id.to_owned(), false),
+        };
+        let exception = if self.peek().is_some_and(|t| is_keyword(t, "WITH")) {
+            self.next += 1;
+            let exception = self.take()?.to_owned();
+            Some(identifier(&exception)?.to_owned())
+        } else {
+            None
+        };
+        Ok(LicenseExpression::License(LicenseTerm { id, or_later, exception }))
+    }
+}
+
+/// Accepts the characters SPDX allows in license identifiers
+fn identifier(token: &str) -> std::result::Result<&str, String> {
+    let valid = !token.is_empty()
+        && !["AND", "OR", "WITH"].iter().any(|k| is_keyword(token, k))
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || ['.', '-', '+', ':'].contains(&c));
+    if valid { Ok(token) } else { Err(format!("unexpected `{}`", token)) }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use super::{ LicenseExpression };
+    use crate::{ Error };
+
+    #[test]
+    fn parse_expressions() {
+        let cases = vec![
+            ("MIT", "MIT"),
+            ("MIT OR Apache-2.0", "MIT OR Apache-2.0"),
+            ("MIT/Apache-2.0", "MIT OR Apache-2.0"),
+            ("(MIT OR Apache-2.0) AND Unicode-DFS-2016", "(MIT OR Apache-2.0) AND Unicode-DFS-2016"),
+            ("MIT AND BSD-3-Clause OR Zlib", "MIT AND BSD-3-Clause OR Zlib"),
+            ("GPL-2.0+ with Classpath-exception-2.0", "GPL-2.0+ WITH Classpath-exception-2.0"),
+            ("((Apache-2.0))", "Apache-2.0"),
+        ];
+        for (expression, expected) in cases {
+            match LicenseExpression::parse(expression) {
+                Ok (parsed) => assert_eq!(expected, parsed.to_string()),
+                Err (err) => assert!(false, "unexpected error: {}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn bind_and_tighter_than_or() {
+        let parsed = LicenseExpression::parse("MIT AND BSD-3-Clause OR Zlib").unwrap();
+        match parsed {
+            LicenseExpression::Or(all) => {
+                assert_eq!(2, all.len());
+                assert_eq!("MIT AND BSD-3-Clause", all[0].to_string());
+            },
+            parsed => assert!(false, "expected OR: {:?}", parsed),
+        }
+    }
+
+    #[test]
+    fn fail_on_invalid_expressions() {
+        let cases = vec![
+            ("", "unexpected end of expression"),
+            ("MIT OR", "unexpected end of expression"),
+            ("(MIT", "unclosed `(`"),
+            ("MIT)", "unexpected `)`"),
+            ("MIT Apache-2.0", "unexpected `Apache-2.0`"),
+            ("MIT OR OR", "unexpected `OR`"),
+            ("MIT, Apache-2.0", "unexpected `MIT,`"),
+        ];
+        for (expression, reason) in cases {
+            match LicenseExpression::parse(expression) {
+                Ok (parsed) => assert!(false, "should fail to parse `{}`: {}", expression, parsed),
+                Err (err) => assert_eq!(Error::InvalidExpression(expression.to_owned(), reason.to_owned()), err),
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::{ BTreeMap, BTreeSet };
+
+use crate::{ LicenseExpression, LicenseTerm };
+
+/// Which licenses dependencies may be used under.
+///
+/// Entries name a license such as `MIT`, or a license with its exception
+/// such as `GPL-2.0-only WITH Classpath-exception-2.0`. A license with an
+/// exception is accepted when either form is allowed, and denied when
+/// either form is denied unless the form with the exception is allowed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LicensePolicy {
+    pub allow: BTreeSet<String>,
+    pub deny: BTreeSet<String>,
+    /// Licenses accepted for a package by name on top of `allow`, even when
+    /// they are denied
+    pub exceptions: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Outcome of checking an expression against a policy
+#[derive(Clone, Debug, PartialEq)]
+pub enum LicenseVerdict {
+    /// The licenses chosen to satisfy the expression
+    Accepted(Vec<String>),
+    /// No choice of licenses satisfies the policy, because of licenses
+    /// which are denied or not allowed
+    Rejected { denied: Vec<String>, not_allowed: Vec<String> },
+}
+
+impl LicenseVerdict {
+    pub fn is_accepted(&self) -> bool {
+        match self {
+            LicenseVerdict::Accepted(_) => true,
+            LicenseVerdict::Rejected { .. } => false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Rejection {
+    denied: Vec<String>,
+    not_allowed: Vec<String>,
+}
+
+impl Rejection {
+    fn merge(&mut self, other: Rejection) {
+        for license in other.denied {
+            if !self.denied.contains(&license) {
+                self.denied.push(license);
+            }
+        }
+        for license in other.not_allowed {
+            if !self.not_allowed.contains(&license) {
+                self.not_allowed.push(license);
+            }
+        }
+    }
+}
+
+impl LicensePolicy {
+    fn check_term(&self, package: &str, term: &LicenseTerm) -> std::result::Result<String, Rejection> {
+        let forms = [term.to_string(), term.license()];
+        let listed = |set: &BTreeSet<String>| forms.iter().any(|form| set.contains(form));
+        let excepted = self.exceptions.get(package).is_some_and(listed);
+        if excepted || (listed(&self.allow) && !listed(&self.deny)) || self.allow.contains(&forms[0]) {
+            Ok(forms[0].to_owned())
+        } else if listed(&self.deny) {
+            Err(Rejection { denied: vec![forms[0].to_owned()], not_allowed: vec![] })
+        } else {
+            Err(Rejection { denied: vec![], not_allowed: vec![forms[0].to_owned()] })
+        }
+    }
+
+    fn check(&self, package: &str, expression: &LicenseExpression) -> std::result::Result<Vec<String>, Rejection> {
+        match expression {
+            LicenseExpression::License(term) => self.check_term(package, term).map(|license| vec![license]),
+            LicenseExpression::And(all) => {
+                let mut chosen = vec![];
+                let mut rejection: Option<Rejection> = None;
+                for expression in all {
+                    match self.check(package, expression) {
+                        Ok(licenses) => chosen.extend(licenses),
+                        Err(err) => rejection.get_or_insert_with(Default::default).merge(err),
+                    }
+                }
+                match rejection {
+                    Some(rejection) => Err(rejection),
+                    None => Ok(chosen),
+                }
+            },
+            LicenseExpression::Or(all) => {
+                let mut rejection = Rejection::default();
+                for expression in all {
+                    match self.check(package, expression) {
+                        Ok(licenses) => return Ok(licenses),
+                        Err(err) => rejection.merge(err),
+                    }
+                }
+                Err(rejection)
+            },
+        }
+    }
+
+    /// Checks the license expression of `package`, where `OR` accepts the
+    /// first alternative the policy accepts and `AND` needs every license
+    /// accepted
+    pub fn evaluate(&self, package: &str, expression: &LicenseExpression) -> LicenseVerdict {
+        match self.check(package, expression) {
+            Ok(licenses) => LicenseVerdict::Accepted(licenses),
+            Err(Rejection { denied, not_allowed }) => LicenseVerdict::Rejected { denied, not_allowed },
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use super::{ LicensePolicy, LicenseVerdict };
+    use crate::{ LicenseExpression };
+
+    fn set(licenses: &[&str]) -> std::collections::BTreeSet<String> {
+        licenses.iter().map(|l| l.to_string()).collect()
+    }
+
+    fn policy() -> LicensePolicy {
+        let mut policy = LicensePolicy {
+            allow: set(&["MIT", "Apache-2.0", "GPL-2.0-only WITH Classpath-exception-2.0"]),
+            deny: set(&["GPL-3.0-only", "GPL-2.0-only"]),
+            exceptions: Default::default(),
+        };
+        policy.exceptions.insert("ring".to_owned(), set(&["OpenSSL"]));
+        policy
+    }
+
+    fn evaluate(package: &str, expression: &str) -> LicenseVerdict {
+        policy().evaluate(package, &LicenseExpression::parse(expression).unwrap())
+    }
+
+    fn rejected(denied: &[&str], not_allowed: &[&str]) -> LicenseVerdict {
+        LicenseVerdict::Rejected {
+            denied: denied.iter().map(|l| l.to_string()).collect(),
+            not_allowed: not_allowed.iter().map(|l| l.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn choose_among_alternatives() {
+        assert_eq!(LicenseVerdict::Accepted(vec!["MIT".to_owned()]), evaluate("a", "GPL-3.0-only OR MIT"));
+        assert_eq!(LicenseVerdict::Accepted(vec!["MIT".to_owned()]), evaluate("a", "MIT OR Apache-2.0"));
+        assert_eq!(rejected(&["GPL-3.0-only"], &["Zlib"]), evaluate("a", "GPL-3.0-only OR Zlib"));
+    }
+
+    #[test]
+    fn require_every_conjunct() {
+        assert_eq!(
+            LicenseVerdict::Accepted(vec!["MIT".to_owned(), "Apache-2.0".to_owned()]),
+            evaluate("a", "MIT AND (Zlib OR Apache-2.0)")
+        );
+        assert_eq!(rejected(&["GPL-3.0-only"], &[]), evaluate("a", "MIT AND GPL-3.0-only"));
+        assert_eq!(rejected(&[], &["ISC"]), evaluate("a", "(MIT OR Apache-2.0) AND ISC"));
+    }
+
+    #[test]
+    fn apply_exceptions() {
+        assert!(evaluate("a", "GPL-2.0-only WITH Classpath-exception-2.0").is_accepted());
+        assert_eq!(rejected(&["GPL-2.0-only WITH LLVM-exception"], &[]), evaluate("a", "GPL-2.0-only WITH LLVM-exception"));
+        assert!(evaluate("a", "Apache-2.0 WITH LLVM-exception").is_accepted());
+        assert!(!evaluate("ring", "MIT AND ISC AND OpenSSL").is_accepted());
+        assert!(evaluate("ring", "MIT AND OpenSSL").is_accepted());
+        assert_eq!(rejected(&[], &["OpenSSL"]), evaluate("other", "MIT AND OpenSSL"));
+    }
+}
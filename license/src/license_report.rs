@@ -0,0 +1,353 @@
+use std::collections::{ BTreeMap };
+use std::fmt;
+
+use semver::Version;
+
+use crate::{ Error, LicenseExpression, LicensePolicy, LicenseVerdict, Result };
+
+/// The license of a package as its manifest gives it
+#[derive(Clone, Debug, PartialEq)]
+pub enum PackageLicense {
+    Expression(LicenseExpression),
+    /// A `license` which is not a valid SPDX expression, with the reason
+    Invalid(String, String),
+    /// Only `license-file` is set
+    FileOnly(String),
+    /// Neither `license` nor `license-file` is set
+    Missing,
+    /// No manifest was given for the package
+    Unknown,
+}
+
+impl PackageLicense {
+    pub fn from_metadata(metadata: &v1::Metadata) -> PackageLicense {
+        match (&metadata.license, &metadata.license_file) {
+            (Some(license), _) => match LicenseExpression::parse(license) {
+                Ok(expression) => PackageLicense::Expression(expression),
+                Err(Error::InvalidExpression(license, reason)) => PackageLicense::Invalid(license, reason),
+                Err(err) => PackageLicense::Invalid(license.to_owned(), err.to_string()),
+            },
+            (None, Some(file)) => PackageLicense::FileOnly(file.to_owned()),
+            (None, None) => PackageLicense::Missing,
+        }
+    }
+
+    /// Key packages are grouped under in a report
+    pub fn group(&self) -> String {
+        match self {
+            PackageLicense::Expression(expression) => expression.to_string(),
+            PackageLicense::Invalid(license, _) => license.to_owned(),
+            PackageLicense::FileOnly(_) => "(license file)".to_owned(),
+            PackageLicense::Missing => "(none)".to_owned(),
+            PackageLicense::Unknown => "(unknown)".to_owned(),
+        }
+    }
+}
+
+/// A package of the dependency graph with its license
+#[derive(Clone, Debug, PartialEq)]
+pub struct LicensedPackage {
+    pub name: String,
+    pub version: Version,
+    pub license: PackageLicense,
+    /// Shortest chain of dependencies from the root package to this one,
+    /// both included
+    pub path: Vec<(String, Version)>,
+}
+
+/// Why a package fails a license policy
+#[derive(Clone, Debug, PartialEq)]
+pub enum LicenseIssue {
+    /// No choice of licenses out of `expression` satisfies the policy
+    Rejected { expression: String, denied: Vec<String>, not_allowed: Vec<String> },
+    Invalid(String, String),
+    /// Only a license file, which cannot be checked, is given
+    FileOnly(String),
+    Missing,
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LicenseViolation {
+    pub name: String,
+    pub version: Version,
+    pub issue: LicenseIssue,
+    /// How the package is brought in, from the root package
+    pub path: Vec<(String, Version)>,
+}
+
+fn quoted(licenses: &[String]) -> String {
+    licenses.iter().map(|l| format!("`{}`", l)).collect::<Vec<_>>().join(", ")
+}
+
+impl fmt::Display for LicenseViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{} {}` ", self.name, self.version)?;
+        match &self.issue {
+            LicenseIssue::Rejected { expression, denied, not_allowed } => {
+                write!(f, "is licensed under `{}`, which the policy does not accept", expression)?;
+                let mut reasons = vec![];
+                if !denied.is_empty() {
+                    reasons.push(format!("{} denied", quoted(denied)));
+                }
+                if !not_allowed.is_empty() {
+                    reasons.push(format!("{} not allowed", quoted(not_allowed)));
+                }
+                write!(f, " ({})", reasons.join("; "))?;
+            },
+            LicenseIssue::Invalid(license, reason) => {
+                write!(f, "has an invalid license expression `{}`: {}", license, reason)?;
+            },
+            LicenseIssue::FileOnly(file) => write!(f, "has only a license file `{}`, which needs review", file)?,
+            LicenseIssue::Missing => write!(f, "has no license")?,
+            LicenseIssue::Unknown => write!(f, "has an unknown license as its manifest was not given")?,
+        }
+        let path: Vec<_> = self.path.iter().map(|(name, version)| format!("{} {}", name, version)).collect();
+        write!(f, "\n  brought in by {}", path.join(" -> "))
+    }
+}
+
+/// The licenses of every package in the dependency graph of a package
+/// according to its lockfile.
+///
+/// `Display` lists the packages grouped by license.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LicenseReport {
+    /// The root package followed by the packages it depends on, directly
+    /// or not, in breadth-first order
+    pub packages: Vec<LicensedPackage>,
+}
+
+impl LicenseReport {
+    /// Collects the licenses of the packages `root` depends on out of
+    /// `manifests`, which holds the manifests of the locked packages.
+    ///
+    /// Packages without a manifest in `manifests` have an unknown license.
+    pub fn new(root: &v1::Project, lockfile: &lockfile::Lockfile, manifests: &[v1::Project]) -> Result<LicenseReport> {
+        let locked_root = lockfile.path_package(&root.name, &root.version).map_err(Error::Lockfile)?;
+        let packages = lockfile.shortest_paths(&[locked_root])
+            .into_iter()
+            .map(|path| {
+                let package = path[path.len() - 1];
+                let manifest = if path.len() == 1 {
+                    Some(root)
+                } else {
+                    manifests.iter().find(|m| m.name == package.name && m.version == package.version)
+                };
+                LicensedPackage {
+                    name: package.name.to_owned(),
+                    version: package.version.to_owned(),
+                    license: manifest.map_or(PackageLicense::Unknown, |m| PackageLicense::from_metadata(&m.metadata)),
+                    path: path.iter().map(|p| (p.name.to_owned(), p.version.to_owned())).collect(),
+                }
+            })
+            .collect();
+        Ok(LicenseReport { packages })
+    }
+
+    /// Packages keyed by their license expression
+    pub fn groups(&self) -> BTreeMap<String, Vec<&LicensedPackage>> {
+        let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for package in &self.packages {
+            groups.entry(package.license.group()).or_default().push(package);
+        }
+        groups
+    }
+
+    /// Dependencies of the root package whose license `policy` does not
+    /// accept, along with those without a license which can be checked
+    pub fn violations(&self, policy: &LicensePolicy) -> Vec<LicenseViolation> {
+        self.packages.iter()
+            .skip(1)
+            .filter_map(|package| {
+                let issue = match &package.license {
+                    PackageLicense::Expression(expression) => match policy.evaluate(&package.name, expression) {
+                        LicenseVerdict::Accepted(_) => return None,
+                        LicenseVerdict::Rejected { denied, not_allowed } => {
+                            LicenseIssue::Rejected { expression: expression.to_string(), denied, not_allowed }
+                        },
+                    },
+                    PackageLicense::Invalid(license, reason) => LicenseIssue::Invalid(license.to_owned(), reason.to_owned()),
+                    PackageLicense::FileOnly(file) => LicenseIssue::FileOnly(file.to_owned()),
+                    PackageLicense::Missing => LicenseIssue::Missing,
+                    PackageLicense::Unknown => LicenseIssue::Unknown,
+                };
+                Some(LicenseViolation {
+                    name: package.name.to_owned(),
+                    version: package.version.to_owned(),
+                    issue,
+                    path: package.path.to_owned(),
+                })
+            })
+            .collect()
+    }
+
+    /// Fails with every violation of `policy`
+    pub fn check(&self, policy: &LicensePolicy) -> Result<()> {
+        let violations = self.violations(policy);
+        if violations.is_empty() { Ok(()) } else { Err(Error::Violations(violations)) }
+    }
+}
+
+impl fmt::Display for LicenseReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (license, packages) in self.groups() {
+            writeln!(f, "{} ({})", license, packages.len())?;
+            for package in packages {
+                writeln!(f, "    {} {}", package.name, package.version)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use semver::{ Version };
+
+    use super::{ LicenseIssue, LicenseReport };
+    use crate::{ Error, LicensePolicy };
+
+    fn project(name: &str, version: &str, license: Option<&str>, license_file: Option<&str>) -> v1::Project {
+        v1::Project {
+            metadata: v1::Metadata {
+                license: license.map(|l| l.to_owned()),
+                license_file: license_file.map(|l| l.to_owned()),
+                .. Default::default()
+            },
+            .. v1::Project::new(name, Version::parse(version).unwrap())
+        }
+    }
+
+    const LOCKFILE: &str = r#"
+        version = 3
+
+        [[package]]
+        name = "app"
+        version = "0.1.0"
+        dependencies = ["middle", "serde"]
+
+        [[package]]
+        name = "middle"
+        version = "1.2.0"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        dependencies = ["copyleft", "custom", "serde"]
+
+        [[package]]
+        name = "copyleft"
+        version = "2.0.0"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        dependencies = ["unlisted"]
+
+        [[package]]
+        name = "custom"
+        version = "0.3.0"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "serde"
+        version = "1.0.190"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "unlisted"
+        version = "0.1.0"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+    "#;
+
+    fn report() -> LicenseReport {
+        let lockfile = lockfile::parse_lockfile(LOCKFILE).unwrap();
+        let manifests = vec![
+            project("middle", "1.2.0", Some("MIT/Apache-2.0"), None),
+            project("copyleft", "2.0.0", Some("GPL-3.0-only"), None),
+            project("custom", "0.3.0", None, Some("LICENSE.txt")),
+            project("serde", "1.0.190", Some("MIT OR Apache-2.0"), None),
+        ];
+        match LicenseReport::new(&project("app", "0.1.0", None, None), &lockfile, &manifests) {
+            Ok (report) => report,
+            Err (err) => { assert!(false, "unexpected error: {}", err); unreachable!() },
+        }
+    }
+
+    fn policy() -> LicensePolicy {
+        LicensePolicy {
+            allow: vec!["MIT".to_owned(), "Apache-2.0".to_owned()].into_iter().collect(),
+            deny: vec!["GPL-3.0-only".to_owned()].into_iter().collect(),
+            exceptions: Default::default(),
+        }
+    }
+
+    #[test]
+    fn group_packages_by_license() {
+        assert_eq!("\
+(license file) (1)
+    custom 0.3.0
+(none) (1)
+    app 0.1.0
+(unknown) (1)
+    unlisted 0.1.0
+GPL-3.0-only (1)
+    copyleft 2.0.0
+MIT OR Apache-2.0 (2)
+    middle 1.2.0
+    serde 1.0.190
+", report().to_string());
+    }
+
+    #[test]
+    fn report_violations_with_paths() {
+        let violations = report().violations(&policy());
+        let summary: Vec<_> = violations.iter()
+            .map(|v| (v.name.as_str(), v.path.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(vec![
+            ("copyleft", vec!["app", "middle", "copyleft"]),
+            ("custom", vec!["app", "middle", "custom"]),
+            ("unlisted", vec!["app", "middle", "copyleft", "unlisted"]),
+        ], summary);
+        assert_eq!(LicenseIssue::FileOnly("LICENSE.txt".to_owned()), violations[1].issue);
+        assert_eq!(LicenseIssue::Unknown, violations[2].issue);
+    }
+
+    #[test]
+    fn explain_failed_check() {
+        match report().check(&policy()) {
+            Ok (()) => assert!(false, "should fail the policy"),
+            Err (err) => assert_eq!("\
+3 package(s) fail the license policy
+`copyleft 2.0.0` is licensed under `GPL-3.0-only`, which the policy does not accept (`GPL-3.0-only` denied)
+  brought in by app 0.1.0 -> middle 1.2.0 -> copyleft 2.0.0
+`custom 0.3.0` has only a license file `LICENSE.txt`, which needs review
+  brought in by app 0.1.0 -> middle 1.2.0 -> custom 0.3.0
+`unlisted 0.1.0` has an unknown license as its manifest was not given
+  brought in by app 0.1.0 -> middle 1.2.0 -> copyleft 2.0.0 -> unlisted 0.1.0", err.to_string()),
+        }
+    }
+
+    #[test]
+    fn pass_accepted_licenses() {
+        let lockfile = lockfile::parse_lockfile(r#"
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = ["serde"]
+
+            [[package]]
+            name = "serde"
+            version = "1.0.190"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+        "#).unwrap();
+        let manifests = vec![project("serde", "1.0.190", Some("MIT OR Apache-2.0"), None)];
+        let report = LicenseReport::new(&project("app", "0.1.0", None, None), &lockfile, &manifests).unwrap();
+        assert_eq!(Ok(()), report.check(&policy()));
+
+        match LicenseReport::new(&project("other", "0.1.0", None, None), &lockfile, &manifests) {
+            Ok (_) => assert!(false, "should require the root package in the lockfile"),
+            Err (err) => assert_eq!(
+                Error::Lockfile(lockfile::Error::NotLocked("other".to_owned(), Version::parse("0.1.0").unwrap())),
+                err,
+            ),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+extern crate semver;
+#[macro_use]
+extern crate serde;
+extern crate toml;
+
+mod locked_dependency;
+mod locked_package;
+mod lockfile;
+
+pub use self::locked_dependency::*;
+pub use self::locked_package::*;
+pub use self::lockfile::*;
+
+use std::collections::{ BTreeMap };
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    DeserializerError(toml::de::Error),
+    InvalidDependency(String, String),
+    /// The lockfile has no entry for a package of the workspace
+    NotLocked(String, semver::Version),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DeserializerError(err) => {
+                write!(f, "Failed to Deserialize TOML into Lockfile: {}", err)
+            },
+            Error::InvalidDependency(package, dep) => {
+                write!(f, "package `{}` has an invalid dependency entry `{}`", package, dep)
+            },
+            Error::NotLocked(name, version) => {
+                write!(f, "package `{} {}` is not in the lockfile", name, version)
+            },
+            Error::UnsupportedVersion(version) => {
+                write!(f, "lockfile version `{}` is not supported", version)
+            },
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Newest lockfile format understood
+pub const LATEST_VERSION: u32 = 4;
+
+#[derive(Deserialize)]
+struct RawLockfile {
+    version: Option<u32>,
+    #[serde(default)]
+    package: Vec<RawPackage>,
+    /// Checksums of the oldest format, keyed by
+    /// `checksum <name> <version> (<source>)`
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct RawPackage {
+    name: String,
+    version: semver::Version,
+    source: Option<String>,
+    checksum: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+fn metadata_checksum(metadata: &BTreeMap<String, String>, package: &RawPackage) -> Option<String> {
+    let source = package.source.as_ref()?;
+    let key = format!("checksum {} {} ({})", package.name, package.version, source);
+    metadata.get(&key).filter(|c| c.as_str() != "<none>").cloned()
+}
+
+/// Parses a `Cargo.lock` of any format from the original unversioned one
+/// up to `LATEST_VERSION`.
+pub fn parse_lockfile(data: &str) -> Result<Lockfile> {
+    let raw: RawLockfile = toml::from_str(data).map_err(Error::DeserializerError)?;
+    if let Some(version) = raw.version.filter(|v| *v > LATEST_VERSION) {
+        return Err(Error::UnsupportedVersion(version));
+    }
+    let packages = raw.package.iter()
+        .map(|package| {
+            let dependencies = package.dependencies.iter()
+                .map(|dep| LockedDependency::parse(dep)
+                    .ok_or_else(|| Error::InvalidDependency(package.name.to_owned(), dep.to_owned())))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(LockedPackage {
+                name: package.name.to_owned(),
+                version: package.version.to_owned(),
+                source: package.source.to_owned(),
+                checksum: package.checksum.to_owned().or_else(|| metadata_checksum(&raw.metadata, package)),
+                dependencies,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Lockfile { version: raw.version, packages })
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use semver::Version;
+
+    use super::{ parse_lockfile, Error, LockedDependency };
+
+    const CRATES_IO: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+    #[test]
+    fn parse_current_format() {
+        let lockfile = parse_lockfile(r#"
+            version = 3
+
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = [
+             "log",
+             "serde 1.0.190",
+            ]
+
+            [[package]]
+            name = "log"
+            version = "0.4.20"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            checksum = "b5e6163cb8c49088c2c36f57875e58ccd8c87c7427f7fbd50ea6710b2f3f2e8f"
+
+            [[package]]
+            name = "serde"
+            version = "1.0.190"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            checksum = "91d3c334ca1ee894a2c6f6ad698fe8c435b76d504b13d436f0685d648d6d96f7"
+        "#).unwrap();
+        assert_eq!(Some(3), lockfile.version);
+        let app = &lockfile.packages[0];
+        assert!(app.is_path());
+        let deps: Vec<_> = lockfile.dependencies_of(app).map(|p| p.name.as_str()).collect();
+        assert_eq!(vec!["log", "serde"], deps);
+        assert_eq!(
+            Some("b5e6163cb8c49088c2c36f57875e58ccd8c87c7427f7fbd50ea6710b2f3f2e8f"),
+            lockfile.checksum("log", &Version::parse("0.4.20").unwrap())
+        );
+        assert_eq!(Ok(app), lockfile.path_package("app", &Version::parse("0.1.0").unwrap()));
+        assert_eq!(
+            Err(Error::NotLocked("log".to_owned(), Version::parse("0.4.20").unwrap())),
+            lockfile.path_package("log", &Version::parse("0.4.20").unwrap())
+        );
+    }
+
+    #[test]
+    fn parse_original_format_with_metadata_checksums() {
+        let lockfile = parse_lockfile(&format!(r#"
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = [
+             "log 0.4.8 ({source})",
+            ]
+
+            [[package]]
+            name = "log"
+            version = "0.4.8"
+            source = "{source}"
+
+            [metadata]
+            "checksum log 0.4.8 ({source})" = "14b6052be84e6b71ab17edffc2eeabf5c2c3ae1fdb464aae35ac50c67a44e1f7"
+        "#, source = CRATES_IO)).unwrap();
+        assert_eq!(None, lockfile.version);
+        let log = &lockfile.packages[1];
+        assert!(log.is_registry());
+        assert_eq!(Some("14b6052be84e6b71ab17edffc2eeabf5c2c3ae1fdb464aae35ac50c67a44e1f7"), log.checksum.as_deref());
+        assert_eq!(Some(log), lockfile.dependencies_of(&lockfile.packages[0]).next());
+    }
+
+    #[test]
+    fn parse_dependency_entries() {
+        assert_eq!(Some(LockedDependency { name: "log".to_owned(), version: None, source: None }), LockedDependency::parse("log"));
+        assert_eq!(
+            Some(LockedDependency {
+                name: "log".to_owned(),
+                version: Some(Version::parse("0.4.8").unwrap()),
+                source: Some(CRATES_IO.to_owned()),
+            }),
+            LockedDependency::parse(&format!("log 0.4.8 ({})", CRATES_IO))
+        );
+        assert_eq!(None, LockedDependency::parse("log not-a-version"));
+    }
+
+    #[test]
+    fn not_resolve_ambiguous_dependency() {
+        let lockfile = parse_lockfile(r#"
+            version = 4
+
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = ["rand"]
+
+            [[package]]
+            name = "rand"
+            version = "0.7.3"
+
+            [[package]]
+            name = "rand"
+            version = "0.8.5"
+        "#).unwrap();
+        assert_eq!(None, lockfile.dependencies_of(&lockfile.packages[0]).next());
+    }
+
+    #[test]
+    fn find_shortest_paths() {
+        let lockfile = parse_lockfile(r#"
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = ["log", "tokio"]
+
+            [[package]]
+            name = "cli"
+            version = "0.1.0"
+            dependencies = ["app", "mio"]
+
+            [[package]]
+            name = "log"
+            version = "0.4.20"
+
+            [[package]]
+            name = "mio"
+            version = "0.8.9"
+            dependencies = ["log"]
+
+            [[package]]
+            name = "tokio"
+            version = "1.33.0"
+            dependencies = ["mio"]
+        "#).unwrap();
+        let roots: Vec<_> = lockfile.packages.iter().filter(|p| p.name == "app" || p.name == "cli").collect();
+        let paths: Vec<_> = lockfile.shortest_paths(&roots).iter()
+            .map(|path| path.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(" -> "))
+            .collect();
+        assert_eq!(vec!["app", "cli", "app -> log", "app -> tokio", "cli -> mio"], paths);
+    }
+
+    #[test]
+    fn fail_on_newer_format() {
+        match parse_lockfile("version = 5") {
+            Ok (_) => assert!(false, "should not parse an unknown lockfile version"),
+            Err (Error::UnsupportedVersion(5)) => {},
+            Err (err) => assert!(false, "expected UnsupportedVersion: {:?}", err),
+        }
+    }
+}
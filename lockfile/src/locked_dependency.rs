@@ -0,0 +1,47 @@
+use std::fmt;
+
+use semver::Version;
+
+/// An entry of a locked package's `dependencies` list.
+///
+/// Newer lockfiles only write the version and source when they are needed
+/// to tell packages with the same name apart, e.g. `"log"`,
+/// `"log 0.4.20"` or `"log 0.4.20 (registry+https://...)"`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockedDependency {
+    pub name: String,
+    pub version: Option<Version>,
+    pub source: Option<String>,
+}
+
+impl LockedDependency {
+    pub fn parse(value: &str) -> Option<LockedDependency> {
+        let mut parts = value.splitn(3, ' ');
+        let name = parts.next().filter(|n| !n.is_empty())?.to_owned();
+        let version = match parts.next() {
+            Some(version) => Some(Version::parse(version).ok()?),
+            None => None,
+        };
+        let source = match parts.next() {
+            Some(source) if source.starts_with('(') && source.ends_with(')') => {
+                Some(source[1..source.len() - 1].to_owned())
+            },
+            Some(_) => return None,
+            None => None,
+        };
+        Some(LockedDependency { name, version, source })
+    }
+}
+
+impl fmt::Display for LockedDependency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, " {}", version)?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, " ({})", source)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use semver::Version;
+
+use crate::{ LockedDependency };
+
+/// A `[[package]]` entry of a lockfile
+#[derive(Clone, Debug, PartialEq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: Version,
+    /// `registry+<url>`, `git+<url>#<rev>` and so on, or `None` for path
+    /// packages
+    pub source: Option<String>,
+    /// sha256 of the `.crate` file for registry packages
+    pub checksum: Option<String>,
+    pub dependencies: Vec<LockedDependency>,
+}
+
+impl LockedPackage {
+    pub fn is_registry(&self) -> bool {
+        self.source.as_ref().is_some_and(|s| s.starts_with("registry+") || s.starts_with("sparse+"))
+    }
+
+    pub fn is_git(&self) -> bool {
+        self.source.as_ref().is_some_and(|s| s.starts_with("git+"))
+    }
+
+    /// Path packages, which include the members of the workspace
+    pub fn is_path(&self) -> bool {
+        self.source.is_none()
+    }
+}
@@ -0,0 +1,87 @@
+use semver::Version;
+
+use crate::{ Error, LockedDependency, LockedPackage, Result };
+
+/// A parsed `Cargo.lock`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lockfile {
+    /// The `version` of the lockfile format, which is not written by the
+    /// oldest format
+    pub version: Option<u32>,
+    pub packages: Vec<LockedPackage>,
+}
+
+impl Lockfile {
+    pub fn packages_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a LockedPackage> + 'a {
+        self.packages.iter().filter(move |p| p.name == name)
+    }
+
+    pub fn package(&self, name: &str, version: &Version) -> Option<&LockedPackage> {
+        self.packages.iter().find(|p| p.name == name && p.version == *version)
+    }
+
+    /// The entry of a package of the workspace, which has no `source`
+    pub fn path_package(&self, name: &str, version: &Version) -> Result<&LockedPackage> {
+        self.packages.iter()
+            .find(|p| p.is_path() && p.name == name && p.version == *version)
+            .ok_or_else(|| Error::NotLocked(name.to_owned(), version.to_owned()))
+    }
+
+    /// The checksum locked for a registry package
+    pub fn checksum(&self, name: &str, version: &Version) -> Option<&str> {
+        self.package(name, version).and_then(|p| p.checksum.as_ref()).map(|c| c.as_str())
+    }
+
+    /// Finds the package a dependency entry refers to
+    pub fn resolve(&self, dep: &LockedDependency) -> Option<&LockedPackage> {
+        let mut candidates = self.packages.iter()
+            .filter(|p| p.name == dep.name)
+            .filter(|p| dep.version.as_ref().is_none_or(|v| *v == p.version))
+            .filter(|p| dep.source.is_none() || dep.source == p.source);
+        let found = candidates.next();
+        match candidates.next() {
+            Some(_) => None,
+            None => found,
+        }
+    }
+
+    /// The packages `package` depends on
+    pub fn dependencies_of<'a>(&'a self, package: &'a LockedPackage) -> impl Iterator<Item = &'a LockedPackage> + 'a {
+        package.dependencies.iter().filter_map(move |dep| self.resolve(dep))
+    }
+
+    /// Every package reachable from `roots` with the shortest chain of
+    /// dependencies leading to it from one of them, both ends included,
+    /// in breadth-first order starting with the roots
+    pub fn shortest_paths<'a>(&'a self, roots: &[&'a LockedPackage]) -> Vec<Vec<&'a LockedPackage>> {
+        let mut packages: Vec<&LockedPackage> = vec![];
+        let mut parents = vec![];
+        for root in roots {
+            if !packages.contains(root) {
+                packages.push(root);
+                parents.push(None);
+            }
+        }
+        let mut next = 0;
+        while next < packages.len() {
+            for dep in self.dependencies_of(packages[next]) {
+                if !packages.contains(&dep) {
+                    packages.push(dep);
+                    parents.push(Some(next));
+                }
+            }
+            next += 1;
+        }
+        (0..packages.len())
+            .map(|index| {
+                let mut path = vec![];
+                let mut current = Some(index);
+                while let Some(at) = current {
+                    path.insert(0, packages[at]);
+                    current = parents[at];
+                }
+                path
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::{ BTreeMap };
+use std::fs;
+use std::io::{ Read, Write };
+use std::path::{ Path };
+
+use flate2::{ Compression, GzBuilder };
+use flate2::read::{ GzDecoder };
+use sha2::{ Digest, Sha256 };
+use tar::{ Archive, Builder, Header, HeaderMode };
+
+use crate::{
+    inherit_from_workspace, list_files, normalize_manifest, to_normalized_toml, Error, Result,
+    MANIFEST_FILE, ORIGINAL_MANIFEST_FILE,
+};
+
+/// Modification time given to every entry so archives are reproducible,
+/// which is the one the `tar` crate and cargo use
+pub const DETERMINISTIC_MTIME: u64 = 1_153_704_088;
+
+/// Hex encoded sha256 of `data`, as written to lockfiles
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// A `.crate` package: a gzipped tarball with every file under a single
+/// `<name>-<version>/` directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrateArchive {
+    root: String,
+    files: BTreeMap<String, Vec<u8>>,
+    checksum: String,
+}
+
+impl CrateArchive {
+    pub fn open(path: &Path) -> Result<CrateArchive> {
+        let data = fs::read(path).map_err(|err| Error::Io(path.to_owned(), err))?;
+        CrateArchive::from_bytes(&data).map_err(|err| match err {
+            Error::InvalidArchive(_, reason) => Error::InvalidArchive(Some(path.to_owned()), reason),
+            err => err,
+        })
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<CrateArchive> {
+        let invalid = |reason: String| Error::InvalidArchive(None, reason);
+        let mut archive = Archive::new(GzDecoder::new(data));
+        let mut root: Option<String> = None;
+        let mut files = BTreeMap::new();
+        for entry in archive.entries().map_err(|err| invalid(err.to_string()))? {
+            let mut entry = entry.map_err(|err| invalid(err.to_string()))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry.path().map_err(|err| invalid(err.to_string()))?.into_owned();
+            let mut parts = path.iter().map(|p| p.to_string_lossy().into_owned());
+            let top = parts.next().unwrap_or_default();
+            let relative = parts.collect::<Vec<_>>().join("/");
+            if relative.is_empty() || relative.split('/').any(|p| p == "..") {
+                return Err(invalid(format!("unexpected entry `{}`", path.display())));
+            }
+            match &root {
+                Some(root) if *root != top => {
+                    return Err(invalid(format!("entry `{}` is outside of `{}`", path.display(), root)))
+                },
+                Some(_) => {},
+                None => root = Some(top),
+            }
+            let mut contents = vec![];
+            entry.read_to_end(&mut contents).map_err(|err| invalid(err.to_string()))?;
+            files.insert(relative, contents);
+        }
+        match root {
+            Some(root) => Ok(CrateArchive { root, files, checksum: sha256_hex(data) }),
+            None => Err(invalid("the archive is empty".to_owned())),
+        }
+    }
+
+    /// The `<name>-<version>` directory holding the package
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Hex encoded sha256 of the compressed archive
+    pub fn checksum(&self) -> &str {
+        &self.checksum
+    }
+
+    /// Paths of the packaged files relative to the package root
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(|k| k.as_str())
+    }
+
+    pub fn read(&self, path: &str) -> Option<&[u8]> {
+        self.files.get(path).map(|f| f.as_slice())
+    }
+
+    /// Parses the packaged, normalized `Cargo.toml`
+    pub fn manifest(&self) -> Result<schema_v1::Manifest> {
+        let path = Path::new(&self.root).join(MANIFEST_FILE);
+        let data = self.read(MANIFEST_FILE)
+            .ok_or_else(|| Error::InvalidArchive(None, format!("missing `{}`", path.display())))?;
+        let data = String::from_utf8_lossy(data);
+        parser::parse_schema_v1(&data).map_err(|err| Error::Parser(path, Box::new(err)))
+    }
+
+    pub fn verify_checksum(&self, expected: &str) -> Result<()> {
+        if self.checksum.eq_ignore_ascii_case(expected) {
+            Ok(())
+        } else {
+            Err(Error::ChecksumMismatch {
+                package: self.root.to_owned(),
+                expected: expected.to_owned(),
+                actual: self.checksum.to_owned(),
+            })
+        }
+    }
+
+    /// Checks the archive against the checksum `lockfile` holds for the
+    /// package named by its manifest
+    pub fn verify_locked(&self, lockfile: &lockfile::Lockfile) -> Result<()> {
+        let manifest = self.manifest()?;
+        let package = manifest.package.as_ref().or(manifest.project.as_ref()).ok_or(Error::NotAPackage)?;
+        let version = package.version.as_defined()
+            .ok_or_else(|| Error::UnresolvedWorkspaceField("version".to_owned()))?;
+        match lockfile.checksum(&package.name, version) {
+            Some(expected) => self.verify_checksum(expected),
+            None => Err(Error::NotLocked(format!("{} {}", package.name, version))),
+        }
+    }
+}
+
+fn append(builder: &mut Builder<Vec<u8>>, path: &str, data: &[u8], mode: u32) -> Result<()> {
+    let mut header = Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(mode);
+    header.set_mtime(DETERMINISTIC_MTIME);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_entry_type(tar::EntryType::Regular);
+    builder.append_data(&mut header, path, data)
+        .map_err(|err| Error::Io(path.into(), err))
+}
+
+/// Builds the `.crate` archive `cargo package` creates for the package in
+/// `package_root`, returning the compressed bytes.
+///
+/// The files come from `list_files`, with the normalized manifest written
+/// as `Cargo.toml` and the original kept as `Cargo.toml.orig`.
+pub fn build_crate(package_root: &Path, workspace_root: &Path) -> Result<Vec<u8>> {
+    let manifest_path = package_root.join(MANIFEST_FILE);
+    let original = fs::read_to_string(&manifest_path).map_err(|err| Error::Io(manifest_path.to_owned(), err))?;
+    let manifest = parser::parse_schema_v1(&original)
+        .map_err(|err| Error::Parser(manifest_path.to_owned(), Box::new(err)))?;
+
+    let inherited = inherit_from_workspace(&manifest, package_root, workspace_root)?;
+    let package = inherited.package.as_ref().or(inherited.project.as_ref()).ok_or(Error::NotAPackage)?;
+    let files = list_files(package_root, package)?;
+    let normalized = normalize_manifest(&manifest, package_root, workspace_root)?;
+    let version = package.version.as_defined()
+        .ok_or_else(|| Error::UnresolvedWorkspaceField("version".to_owned()))?;
+    let root = format!("{}-{}", package.name, version);
+
+    let mut builder = Builder::new(vec![]);
+    builder.mode(HeaderMode::Deterministic);
+    for file in &files {
+        let path = format!("{}/{}", root, file.path);
+        if file.path == MANIFEST_FILE {
+            append(&mut builder, &path, to_normalized_toml(&normalized)?.as_bytes(), 0o644)?;
+            let orig = format!("{}/{}", root, ORIGINAL_MANIFEST_FILE);
+            append(&mut builder, &orig, original.as_bytes(), 0o644)?;
+            continue;
+        }
+        let data = fs::read(&file.source).map_err(|err| Error::Io(file.source.to_owned(), err))?;
+        let metadata = fs::metadata(&file.source).map_err(|err| Error::Io(file.source.to_owned(), err))?;
+        let mut header = Header::new_gnu();
+        header.set_metadata_in_mode(&metadata, HeaderMode::Deterministic);
+        header.set_size(data.len() as u64);
+        builder.append_data(&mut header, &path, data.as_slice())
+            .map_err(|err| Error::Io(file.source.to_owned(), err))?;
+    }
+    let tar = builder.into_inner().map_err(|err| Error::Io(package_root.to_owned(), err))?;
+
+    let mut encoder = GzBuilder::new()
+        .filename(format!("{}.crate", root))
+        .mtime(DETERMINISTIC_MTIME as u32)
+        .write(vec![], Compression::best());
+    encoder.write_all(&tar).map_err(|err| Error::Io(package_root.to_owned(), err))?;
+    encoder.finish().map_err(|err| Error::Io(package_root.to_owned(), err))
+}
+
+/// Writes the `.crate` archive of the package in `package_root` to `dest`,
+/// returning its checksum
+pub fn write_crate(package_root: &Path, workspace_root: &Path, dest: &Path) -> Result<String> {
+    let data = build_crate(package_root, workspace_root)?;
+    fs::write(dest, &data).map_err(|err| Error::Io(dest.to_owned(), err))?;
+    Ok(sha256_hex(&data))
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    extern crate tempfile;
+
+    use std::fs;
+    use std::path::{ Path, PathBuf };
+
+    use super::{ build_crate, sha256_hex, write_crate, CrateArchive };
+    use crate::{ write, Error };
+
+    const FIXTURE_CHECKSUM: &str = "28b1308dcfc6b63e5f047761a3d25d7d75f013b5c19ee35fd57027860f04f481";
+
+    fn fixture() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/fixture-0.1.0.crate")
+    }
+
+    #[test]
+    fn open_fixture_archive() {
+        let archive = CrateArchive::open(&fixture()).unwrap();
+        assert_eq!("fixture-0.1.0", archive.root());
+        assert_eq!(
+            vec!["Cargo.toml", "Cargo.toml.orig", "README.md", "src/lib.rs"],
+            archive.files().collect::<Vec<_>>()
+        );
+        let manifest = archive.manifest().unwrap();
+        assert_eq!("fixture", manifest.package.unwrap().name);
+        assert_eq!(FIXTURE_CHECKSUM, archive.checksum());
+        assert!(archive.verify_checksum(FIXTURE_CHECKSUM).is_ok());
+    }
+
+    #[test]
+    fn verify_against_lockfile() {
+        let archive = CrateArchive::open(&fixture()).unwrap();
+        let lock = |checksum: &str| lockfile::parse_lockfile(&format!(r#"
+            version = 3
+
+            [[package]]
+            name = "fixture"
+            version = "0.1.0"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            checksum = "{}"
+        "#, checksum)).unwrap();
+
+        assert!(archive.verify_locked(&lock(FIXTURE_CHECKSUM)).is_ok());
+        match archive.verify_locked(&lock(&"0".repeat(64))) {
+            Ok (_) => assert!(false, "should not verify against another checksum"),
+            Err (Error::ChecksumMismatch { actual, .. }) => assert_eq!(FIXTURE_CHECKSUM, actual),
+            Err (err) => assert!(false, "expected ChecksumMismatch: {:?}", err),
+        }
+        match archive.verify_locked(&lockfile::Lockfile::default()) {
+            Ok (_) => assert!(false, "should not verify a package missing from the lockfile"),
+            Err (Error::NotLocked(package)) => assert_eq!("fixture 0.1.0", package),
+            Err (err) => assert!(false, "expected NotLocked: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn reject_invalid_archive() {
+        match CrateArchive::from_bytes(b"not a crate") {
+            Ok (_) => assert!(false, "should not open a file which is not a gzipped tarball"),
+            Err (Error::InvalidArchive(None, _)) => {},
+            Err (err) => assert!(false, "expected InvalidArchive: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn build_reproducible_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "1.2.0"
+            exclude = ["notes.txt"]
+
+            [dev-dependencies]
+            helper = { path = "../helper" }
+        "#);
+        write(root, "src/lib.rs", "pub fn foo() {}\n");
+        write(root, "notes.txt", "");
+
+        let data = build_crate(root, root).unwrap();
+        assert_eq!(data, build_crate(root, root).unwrap());
+
+        let archive = CrateArchive::from_bytes(&data).unwrap();
+        assert_eq!("foo-1.2.0", archive.root());
+        assert_eq!(
+            vec!["Cargo.toml", "Cargo.toml.orig", "src/lib.rs"],
+            archive.files().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&b"pub fn foo() {}\n"[..]), archive.read("src/lib.rs"));
+        let manifest = archive.manifest().unwrap();
+        assert!(manifest.dev_dependencies.is_none_or(|deps| deps.is_empty()));
+
+        let dest = root.join("foo-1.2.0.crate");
+        let checksum = write_crate(root, root, &dest).unwrap();
+        assert_eq!(sha256_hex(&fs::read(&dest).unwrap()), checksum);
+        assert!(CrateArchive::open(&dest).unwrap().verify_checksum(&checksum).is_ok());
+    }
+}
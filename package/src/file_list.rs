@@ -0,0 +1,281 @@
+use std::fs;
+use std::path::{ Component, Path };
+
+use ignore::gitignore::{ Gitignore, GitignoreBuilder };
+
+use crate::{ Error, PackagedFile, Result };
+
+pub const MANIFEST_FILE: &str = "Cargo.toml";
+pub const LOCK_FILE: &str = "Cargo.lock";
+pub const TARGET_DIR: &str = "target";
+
+/// Readme files cargo uses when `readme` is not set, in order of preference
+pub const DEFAULT_READMES: &[&str] = &["README.md", "README.txt", "README"];
+
+/// The value of a field, which must already be inherited from the workspace
+fn defined<'a, T>(field: &str, value: &'a Option<schema_v1::MaybeWorkspace<T>>) -> Result<Option<&'a T>> {
+    match value {
+        Some(schema_v1::MaybeWorkspace::Defined(value)) => Ok(Some(value)),
+        Some(schema_v1::MaybeWorkspace::Workspace(_)) => Err(Error::UnresolvedWorkspaceField(field.to_owned())),
+        None => Ok(None),
+    }
+}
+
+fn build_patterns(root: &Path, patterns: &[String]) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(root);
+    for pattern in patterns {
+        builder.add_line(None, pattern)
+            .map_err(|err| Error::InvalidPattern(pattern.to_owned(), err))?;
+    }
+    builder.build().map_err(|err| Error::InvalidPattern(patterns.join(", "), err))
+}
+
+/// Which paths are packaged according to `include` and `exclude`.
+///
+/// A non-empty `include` list replaces `exclude` entirely, as cargo does.
+enum Filter {
+    Include(Gitignore),
+    Exclude(Gitignore),
+}
+
+impl Filter {
+    fn new(root: &Path, package: &schema_v1::Project) -> Result<Filter> {
+        match defined("include", &package.include)? {
+            Some(include) if !include.is_empty() => build_patterns(root, include).map(Filter::Include),
+            _ => {
+                let exclude = defined("exclude", &package.exclude)?.map_or(&[][..], |e| e.as_slice());
+                build_patterns(root, exclude).map(Filter::Exclude)
+            },
+        }
+    }
+
+    fn is_packaged(&self, relative: &Path, is_dir: bool) -> bool {
+        match self {
+            // Directories are always walked, since a pattern may match a file within them
+            Filter::Include(_) if is_dir => true,
+            Filter::Include(include) => include.matched_path_or_any_parents(relative, false).is_ignore(),
+            Filter::Exclude(exclude) => !exclude.matched_path_or_any_parents(relative, is_dir).is_ignore(),
+        }
+    }
+}
+
+/// Joins the normal components of `path` with `/`, dropping `.` and
+/// resolving `..` lexically
+fn archive_path(path: &Path) -> String {
+    let mut parts: Vec<String> = vec![];
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::ParentDir => { parts.pop(); },
+            _ => {},
+        }
+    }
+    parts.join("/")
+}
+
+fn walk(root: &Path, dir: &Path, filter: &Filter, files: &mut Vec<PackagedFile>) -> Result<()> {
+    let entries = fs::read_dir(dir).map_err(|err| Error::Io(dir.to_owned(), err))?;
+    for entry in entries {
+        let path = entry.map_err(|err| Error::Io(dir.to_owned(), err))?.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_owned();
+        let is_dir = path.is_dir();
+        if !filter.is_packaged(&relative, is_dir) {
+            continue;
+        }
+        if is_dir {
+            let name = relative.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            // Skip build artifacts, hidden directories such as `.git` and nested packages
+            let is_target = dir == root && name == TARGET_DIR;
+            if is_target || name.starts_with('.') || path.join(MANIFEST_FILE).exists() {
+                continue;
+            }
+            walk(root, &path, filter, files)?;
+        } else {
+            files.push(PackagedFile { path: archive_path(&relative), source: path });
+        }
+    }
+    Ok(())
+}
+
+/// Adds a file which is packaged regardless of `include` and `exclude`.
+///
+/// Files outside of the package directory are added at the root of the
+/// package under their file name.
+fn add_file(root: &Path, file: &Path, files: &mut Vec<PackagedFile>) {
+    let source = root.join(file);
+    if !source.is_file() {
+        return;
+    }
+    let relative = archive_path(file);
+    let path = if file.components().any(|c| c == Component::ParentDir) || file.is_absolute() {
+        source.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or(relative)
+    } else {
+        relative
+    };
+    if !files.iter().any(|f| f.path == path) {
+        files.push(PackagedFile { path, source });
+    }
+}
+
+/// Lists the files `cargo package` includes for the package in `root`,
+/// sorted by their path in the package.
+///
+/// Patterns in `include` and `exclude` use gitignore syntax relative to
+/// `root`. The `target` directory, hidden directories and any directory
+/// containing another `Cargo.toml` are never walked, while `Cargo.toml`,
+/// `Cargo.lock`, the `license-file` and the readme are always included.
+pub fn list_files(root: &Path, package: &schema_v1::Project) -> Result<Vec<PackagedFile>> {
+    if !root.join(MANIFEST_FILE).is_file() {
+        return Err(Error::MissingManifest(root.to_owned()));
+    }
+    let filter = Filter::new(root, package)?;
+    let mut files = vec![];
+    walk(root, root, &filter, &mut files)?;
+
+    add_file(root, Path::new(MANIFEST_FILE), &mut files);
+    add_file(root, Path::new(LOCK_FILE), &mut files);
+    if let Some(license_file) = defined("license-file", &package.license_file)? {
+        add_file(root, Path::new(license_file), &mut files);
+    }
+    match defined("readme", &package.readme)? {
+        Some(readme) => add_file(root, Path::new(readme), &mut files),
+        None => {
+            if let Some(readme) = DEFAULT_READMES.iter().find(|r| root.join(r).is_file()) {
+                add_file(root, Path::new(readme), &mut files);
+            }
+        },
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Total size in bytes of the files on disk
+pub fn packaged_size(files: &[PackagedFile]) -> Result<u64> {
+    files.iter().try_fold(0, |total, file| {
+        fs::metadata(&file.source)
+            .map(|m| total + m.len())
+            .map_err(|err| Error::Io(file.source.to_owned(), err))
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    extern crate tempfile;
+
+    use std::path::{ Path };
+
+    use super::{ list_files, packaged_size, Error };
+    use crate::write;
+
+    fn package(extra: &str) -> schema_v1::Project {
+        toml::from_str(&format!("name = \"foo\"\nversion = \"1.0.0\"\n{}", extra)).unwrap()
+    }
+
+    fn paths(root: &Path, package: &schema_v1::Project) -> Vec<String> {
+        match list_files(root, package) {
+            Ok (files) => files.into_iter().map(|f| f.path).collect(),
+            Err (err) => { assert!(false, "unexpected error: {}", err); vec![] },
+        }
+    }
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "Cargo.toml", "");
+        write(root, "Cargo.lock", "");
+        write(root, "src/lib.rs", "");
+        write(root, "src/bin/tool.rs", "");
+        write(root, "tests/it.rs", "");
+        write(root, "benches/data/big.bin", "0123456789");
+        write(root, "target/debug/foo", "");
+        write(root, ".git/HEAD", "");
+        write(root, "nested/Cargo.toml", "");
+        write(root, "nested/src/lib.rs", "");
+        write(root, "README.md", "");
+        write(root, "LICENSE", "");
+        dir
+    }
+
+    #[test]
+    fn list_default_files() {
+        let dir = fixture();
+        assert_eq!(vec![
+            "Cargo.lock", "Cargo.toml", "LICENSE", "README.md", "benches/data/big.bin",
+            "src/bin/tool.rs", "src/lib.rs", "tests/it.rs",
+        ], paths(dir.path(), &package("")));
+    }
+
+    #[test]
+    fn exclude_matching_files_and_directories() {
+        let dir = fixture();
+        let package = package(r#"exclude = ["benches/", "*.rs", "!src/lib.rs"]"#);
+        assert_eq!(vec![
+            "Cargo.lock", "Cargo.toml", "LICENSE", "README.md", "src/lib.rs",
+        ], paths(dir.path(), &package));
+    }
+
+    #[test]
+    fn include_overrides_exclude() {
+        let dir = fixture();
+        let package = package(r#"
+            include = ["src/**/*.rs"]
+            exclude = ["src/"]
+        "#);
+        assert_eq!(vec![
+            "Cargo.lock", "Cargo.toml", "README.md", "src/bin/tool.rs", "src/lib.rs",
+        ], paths(dir.path(), &package));
+    }
+
+    #[test]
+    fn include_license_and_readme_when_excluded() {
+        let dir = fixture();
+        write(dir.path(), "docs/INTRO.md", "");
+        let package = package(r#"
+            license-file = "LICENSE"
+            readme = "docs/INTRO.md"
+            include = ["/src/lib.rs"]
+        "#);
+        assert_eq!(vec![
+            "Cargo.lock", "Cargo.toml", "LICENSE", "docs/INTRO.md", "src/lib.rs",
+        ], paths(dir.path(), &package));
+    }
+
+    #[test]
+    fn include_readme_outside_package_at_root() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "README.md", "");
+        write(dir.path(), "foo/Cargo.toml", "");
+        let package = package(r#"readme = "../README.md""#);
+        assert_eq!(vec!["Cargo.toml", "README.md"], paths(&dir.path().join("foo"), &package));
+    }
+
+    #[test]
+    fn anchored_patterns_only_match_at_root() {
+        let dir = fixture();
+        write(dir.path(), "src/target/keep.rs", "");
+        let package = package(r#"exclude = ["/tests", "/benches"]"#);
+        assert_eq!(vec![
+            "Cargo.lock", "Cargo.toml", "LICENSE", "README.md",
+            "src/bin/tool.rs", "src/lib.rs", "src/target/keep.rs",
+        ], paths(dir.path(), &package));
+    }
+
+    #[test]
+    fn sum_packaged_size() {
+        let dir = fixture();
+        let files = list_files(dir.path(), &package(r#"include = ["benches"]"#)).unwrap();
+        assert_eq!(10, packaged_size(&files).unwrap());
+    }
+
+    #[test]
+    fn fail_without_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        match list_files(dir.path(), &package("")) {
+            Ok (_) => assert!(false, "should require a Cargo.toml"),
+            Err (Error::MissingManifest(_)) => {},
+            Err (err) => assert!(false, "expected MissingManifest: {:?}", err),
+        }
+    }
+}
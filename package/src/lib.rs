@@ -0,0 +1,92 @@
+extern crate flate2;
+extern crate ignore;
+extern crate omni_manifest_lockfile as lockfile;
+extern crate sha2;
+extern crate tar;
+extern crate toml;
+extern crate omni_manifest_toml_parser as parser;
+extern crate omni_manifest_toml_schema_v1 as schema_v1;
+
+mod crate_archive;
+mod file_list;
+mod normalize;
+mod packaged_file;
+
+pub use self::crate_archive::*;
+pub use self::file_list::*;
+pub use self::normalize::*;
+pub use self::packaged_file::*;
+
+use std::fmt;
+use std::io;
+use std::path::{ PathBuf };
+
+#[derive(Debug)]
+pub enum Error {
+    ChecksumMismatch { package: String, expected: String, actual: String },
+    DependencyWithoutVersion(String),
+    Inherit(schema_v1::InheritError),
+    InvalidArchive(Option<PathBuf>, String),
+    InvalidPattern(String, ignore::Error),
+    Io(PathBuf, io::Error),
+    MissingManifest(PathBuf),
+    MissingWorkspace(PathBuf),
+    NotAPackage,
+    NotLocked(String),
+    Parser(PathBuf, Box<parser::Error>),
+    Serialize(toml::ser::Error),
+    UnresolvedWorkspaceField(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ChecksumMismatch { package, expected, actual } => write!(
+                f,
+                "checksum of `{}` does not match, expected `{}` but found `{}`",
+                package, expected, actual
+            ),
+            Error::DependencyWithoutVersion(name) => write!(
+                f,
+                "all dependencies must have a version specified when packaging, `{}` does not",
+                name
+            ),
+            Error::Inherit(err) => err.fmt(f),
+            Error::InvalidArchive(Some(path), reason) => {
+                write!(f, "`{}` is not a valid package archive: {}", path.display(), reason)
+            },
+            Error::InvalidArchive(None, reason) => write!(f, "invalid package archive: {}", reason),
+            Error::InvalidPattern(pattern, err) => {
+                write!(f, "invalid include/exclude pattern `{}`: {}", pattern, err)
+            },
+            Error::Io(path, err) => {
+                write!(f, "failed to read `{}`: {}", path.display(), err)
+            },
+            Error::MissingManifest(root) => {
+                write!(f, "no `Cargo.toml` found in package directory `{}`", root.display())
+            },
+            Error::MissingWorkspace(path) => {
+                write!(f, "`{}` does not declare a `[workspace]`", path.display())
+            },
+            Error::NotAPackage => write!(f, "the manifest is a virtual manifest and has no package"),
+            Error::NotLocked(package) => write!(f, "`{}` has no checksum in the lockfile", package),
+            Error::Parser(path, err) => {
+                write!(f, "failed to parse `{}`: {}", path.display(), err)
+            },
+            Error::Serialize(err) => write!(f, "failed to serialize manifest: {}", err),
+            Error::UnresolvedWorkspaceField(field) => {
+                write!(f, "`{}` inherits from the workspace, which must be resolved first", field)
+            },
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Writes a test fixture file, creating the directories leading up to it
+#[cfg(test)]
+fn write(root: &std::path::Path, file: &str, data: &str) {
+    let path = root.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, data).unwrap();
+}
@@ -0,0 +1,308 @@
+use std::fs;
+use std::path::{ Component, Path };
+
+use crate::{ Error, Result, MANIFEST_FILE };
+
+/// Name the original manifest is kept under in a package
+pub const ORIGINAL_MANIFEST_FILE: &str = "Cargo.toml.orig";
+
+/// Header cargo writes at the top of a normalized manifest
+pub const NORMALIZED_MANIFEST_HEADER: &str = "\
+# THIS FILE IS AUTOMATICALLY GENERATED BY CARGO
+#
+# When uploading crates to the registry Cargo will automatically
+# \"normalize\" Cargo.toml files for maximal compatibility
+# with all versions of Cargo and also rewrite `path` dependencies
+# to registry (e.g., crates.io) dependencies.
+#
+# If you are reading this file be aware that the original Cargo.toml
+# will likely look very different (and much more reasonable).
+# See Cargo.toml.orig for the original contents.
+";
+
+/// Reads the `[workspace]` a package inherits from, which is its own when
+/// it is the workspace root
+fn load_workspace(
+    manifest: &schema_v1::Manifest,
+    package_root: &Path,
+    workspace_root: &Path,
+) -> Result<schema_v1::Workspace> {
+    if let Some(workspace) = &manifest.workspace {
+        return Ok(workspace.to_owned());
+    }
+    if package_root == workspace_root {
+        return Ok(schema_v1::Workspace::default());
+    }
+    let path = workspace_root.join(MANIFEST_FILE);
+    let data = fs::read_to_string(&path).map_err(|err| Error::Io(path.to_owned(), err))?;
+    let root = parser::parse_schema_v1(&data).map_err(|err| Error::Parser(path.to_owned(), Box::new(err)))?;
+    root.workspace.ok_or(Error::MissingWorkspace(path))
+}
+
+/// Files outside of the package are packaged at its root, so paths to them
+/// are replaced by their file name
+fn package_relative(path: &schema_v1::MaybeWorkspace<String>) -> schema_v1::MaybeWorkspace<String> {
+    match path {
+        schema_v1::MaybeWorkspace::Defined(value) => {
+            let value_path = Path::new(value);
+            let outside = value_path.is_absolute() || value_path.components().any(|c| c == Component::ParentDir);
+            match value_path.file_name() {
+                Some(name) if outside => schema_v1::MaybeWorkspace::Defined(name.to_string_lossy().into_owned()),
+                _ => path.to_owned(),
+            }
+        },
+        schema_v1::MaybeWorkspace::Workspace(_) => path.to_owned(),
+    }
+}
+
+/// Strips the local sources of dependencies which have a version, which is
+/// what a registry resolves them with.
+///
+/// Development dependencies without a version are dropped, while any other
+/// dependency without one cannot be published.
+fn normalize_dependencies(
+    deps: &Option<schema_v1::DependencyMap>,
+    is_dev: bool,
+) -> Result<Option<schema_v1::DependencyMap>> {
+    let deps = match deps {
+        Some(deps) => deps,
+        None => return Ok(None),
+    };
+    let mut normalized = schema_v1::DependencyMap::default();
+    for (name, dep) in deps.iter() {
+        let mut detailed = match dep {
+            schema_v1::Dependency::Simple(_) => {
+                normalized.0.insert(name.to_owned(), dep.to_owned());
+                continue;
+            },
+            schema_v1::Dependency::Detailed(detailed) => detailed.to_owned(),
+        };
+        let is_local = detailed.path.is_some() || detailed.git.is_some();
+        if is_local && detailed.version.is_none() {
+            if is_dev {
+                continue;
+            }
+            return Err(Error::DependencyWithoutVersion(name.to_owned()));
+        }
+        detailed.path = None;
+        detailed.git = None;
+        detailed.branch = None;
+        detailed.tag = None;
+        detailed.rev = None;
+        normalized.0.insert(name.to_owned(), schema_v1::Dependency::Detailed(detailed));
+    }
+    Ok(Some(normalized))
+}
+
+/// Resolves the `workspace = true` values of a package from the workspace
+/// at `workspace_root`, which may be `package_root` itself
+pub fn inherit_from_workspace(
+    manifest: &schema_v1::Manifest,
+    package_root: &Path,
+    workspace_root: &Path,
+) -> Result<schema_v1::Manifest> {
+    let workspace = load_workspace(manifest, package_root, workspace_root)?;
+    schema_v1::inherit_workspace(manifest, &workspace, workspace_root, package_root)
+        .map_err(Error::Inherit)
+}
+
+/// Produces the manifest `cargo package` writes as the packaged
+/// `Cargo.toml`.
+///
+/// Values are inherited from the workspace at `workspace_root`, which may
+/// be `package_root` itself, and the `[workspace]`, `[patch]` and
+/// `[replace]` sections are dropped along with local dependency sources.
+pub fn normalize_manifest(
+    manifest: &schema_v1::Manifest,
+    package_root: &Path,
+    workspace_root: &Path,
+) -> Result<schema_v1::Manifest> {
+    let manifest = inherit_from_workspace(manifest, package_root, workspace_root)?;
+
+    let mut package = match manifest.package.as_ref().or(manifest.project.as_ref()) {
+        Some(package) => package.to_owned(),
+        None => return Err(Error::NotAPackage),
+    };
+    package.workspace = None;
+    package.readme = package.readme.as_ref().map(package_relative);
+    package.license_file = package.license_file.as_ref().map(package_relative);
+
+    let mut target = manifest.target.to_owned();
+    for platform in target.iter_mut().flat_map(|t| t.values_mut()) {
+        platform.dependencies = normalize_dependencies(&platform.dependencies, false)?;
+        platform.dev_dependencies = normalize_dependencies(&platform.dev_dependencies, true)?;
+        platform.build_dependencies = normalize_dependencies(&platform.build_dependencies, false)?;
+    }
+
+    Ok(schema_v1::Manifest {
+        package: Some(package),
+        project: None,
+        dependencies: normalize_dependencies(&manifest.dependencies, false)?,
+        dev_dependencies: normalize_dependencies(&manifest.dev_dependencies, true)?,
+        build_dependencies: normalize_dependencies(&manifest.build_dependencies, false)?,
+        target,
+        workspace: None,
+        patch: None,
+        replace: None,
+        .. manifest
+    })
+}
+
+/// Serializes a normalized manifest with cargo's header
+pub fn to_normalized_toml(manifest: &schema_v1::Manifest) -> Result<String> {
+    // Serializing through a `Value` places tables after plain values
+    let value = toml::Value::try_from(manifest).map_err(Error::Serialize)?;
+    let body = toml::to_string(&value).map_err(Error::Serialize)?;
+    Ok(format!("{}\n{}", NORMALIZED_MANIFEST_HEADER, body))
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    extern crate tempfile;
+
+    use super::{ normalize_manifest, to_normalized_toml, NORMALIZED_MANIFEST_HEADER };
+    use crate::{ write, Error };
+
+    fn manifest(data: &str) -> schema_v1::Manifest {
+        parser::parse_schema_v1(data).unwrap()
+    }
+
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Cargo.toml", r#"
+            [workspace]
+            members = ["crates/*"]
+
+            [workspace.package]
+            version = "2.1.0"
+            edition = "2021"
+            license = "MIT OR Apache-2.0"
+            readme = "README.md"
+
+            [workspace.dependencies]
+            serde = { version = "1.0", features = ["derive"] }
+            bar = { path = "crates/bar", version = "0.3" }
+        "#);
+        dir
+    }
+
+    const MEMBER: &str = r#"
+        [package]
+        name = "foo"
+        version.workspace = true
+        edition.workspace = true
+        license.workspace = true
+        readme.workspace = true
+        description = "Foo"
+
+        [dependencies]
+        serde = { workspace = true, features = ["rc"] }
+        bar = { workspace = true }
+        log = { git = "https://github.com/rust-lang/log", version = "0.4" }
+
+        [dev-dependencies]
+        helper = { path = "../helper" }
+        quickcheck = "1"
+
+        [patch.crates-io]
+        log = { path = "../log" }
+    "#;
+
+    #[test]
+    fn normalize_workspace_member() {
+        let dir = workspace();
+        let package_root = dir.path().join("crates/foo");
+        let normalized = normalize_manifest(&manifest(MEMBER), &package_root, dir.path()).unwrap();
+
+        let package = normalized.package.as_ref().unwrap();
+        assert_eq!("2.1.0", package.version.as_defined().unwrap().to_string());
+        assert_eq!(Some("README.md"), package.readme.as_ref().and_then(|r| r.as_defined()).map(|r| r.as_str()));
+        assert_eq!(None, normalized.patch);
+
+        let expected = manifest(r#"
+            [package]
+            name = "foo"
+            version = "2.1.0"
+            edition = "2021"
+            license = "MIT OR Apache-2.0"
+            readme = "README.md"
+            description = "Foo"
+
+            [dependencies]
+            serde = { version = "1.0", features = ["derive", "rc"] }
+            bar = { version = "0.3" }
+            log = { version = "0.4" }
+
+            [dev-dependencies]
+            quickcheck = "1"
+        "#);
+        assert_eq!(expected, normalized);
+    }
+
+    #[test]
+    fn serialize_with_header() {
+        let dir = workspace();
+        let package_root = dir.path().join("crates/foo");
+        let normalized = normalize_manifest(&manifest(MEMBER), &package_root, dir.path()).unwrap();
+        let data = to_normalized_toml(&normalized).unwrap();
+        assert!(data.starts_with(NORMALIZED_MANIFEST_HEADER));
+        assert_eq!(normalized, manifest(&data));
+    }
+
+    #[test]
+    fn normalize_workspace_root_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = manifest(r#"
+            [package]
+            name = "foo"
+            version.workspace = true
+
+            [workspace]
+            members = ["bar"]
+
+            [workspace.package]
+            version = "0.5.0"
+        "#);
+        match normalize_manifest(&root, dir.path(), dir.path()) {
+            Ok (normalized) => {
+                assert_eq!(None, normalized.workspace);
+                assert_eq!("0.5.0", normalized.package.unwrap().version.as_defined().unwrap().to_string());
+            },
+            Err (err) => assert!(false, "unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn fail_on_path_dependency_without_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = manifest(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [build-dependencies]
+            gen = { path = "../gen" }
+        "#);
+        match normalize_manifest(&package, dir.path(), dir.path()) {
+            Ok (_) => assert!(false, "a build dependency without a version cannot be published"),
+            Err (Error::DependencyWithoutVersion(name)) => assert_eq!("gen", name),
+            Err (err) => assert!(false, "expected DependencyWithoutVersion: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn fail_without_workspace_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = manifest(r#"
+            [package]
+            name = "foo"
+            version.workspace = true
+        "#);
+        match normalize_manifest(&package, dir.path(), dir.path()) {
+            Ok (_) => assert!(false, "should not inherit without a workspace"),
+            Err (Error::Inherit(schema_v1::InheritError::MissingWorkspaceField(field))) => assert_eq!("version", field),
+            Err (err) => assert!(false, "expected MissingWorkspaceField: {:?}", err),
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use std::path::{ PathBuf };
+
+/// A file to be written to a package archive.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct PackagedFile {
+    /// Path within the package, using `/` separators
+    pub path: String,
+    /// Location of the file on disk
+    pub source: PathBuf,
+}
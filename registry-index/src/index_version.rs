@@ -0,0 +1,60 @@
+use std::collections::{ BTreeMap };
+
+use semver::{ Version };
+
+/// A dependency of a published version as listed in the index
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct IndexDependency {
+    /// Name the dependency is used under, which is the renamed one when
+    /// `package` is set
+    pub name: String,
+    pub req: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default = "default_true")]
+    pub default_features: bool,
+    /// `cfg(..)` expression or target triple the dependency applies to
+    pub target: Option<String>,
+    /// `normal`, `dev` or `build`, where a missing kind is `normal`
+    pub kind: Option<String>,
+    /// Registry index url of a dependency from another registry
+    pub registry: Option<String>,
+    pub package: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A published version, which is one line of a package's index file
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct IndexVersion {
+    pub name: String,
+    #[serde(rename = "vers")]
+    pub version: Version,
+    #[serde(default)]
+    pub deps: Vec<IndexDependency>,
+    /// sha256 of the `.crate` file
+    #[serde(rename = "cksum")]
+    pub checksum: String,
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+    /// Features using `dep:` or `?` syntax, kept apart for older cargo
+    #[serde(default)]
+    pub features2: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub yanked: bool,
+    pub links: Option<String>,
+    pub rust_version: Option<String>,
+}
+
+impl IndexVersion {
+    /// `features` and `features2` together
+    pub fn all_features(&self) -> BTreeMap<String, Vec<String>> {
+        let mut features = self.features.to_owned();
+        features.extend(self.features2.to_owned());
+        features
+    }
+}
@@ -0,0 +1,39 @@
+extern crate omni_manifest_lockfile as lockfile;
+extern crate omni_manifest_v1 as v1;
+extern crate semver;
+#[macro_use]
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+mod index_version;
+mod outdated;
+mod registry_index;
+
+pub use self::index_version::*;
+pub use self::outdated::*;
+pub use self::registry_index::*;
+
+use std::fmt;
+use std::io;
+use std::path::{ PathBuf };
+
+#[derive(Debug)]
+pub enum Error {
+    /// A line of an index file, counted from one, is not a valid entry
+    InvalidEntry(PathBuf, usize, serde_json::Error),
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidEntry(path, line, err) => {
+                write!(f, "{}:{}: invalid index entry: {}", path.display(), line, err)
+            },
+            Error::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
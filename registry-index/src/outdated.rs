@@ -0,0 +1,291 @@
+use std::fmt;
+
+use semver::{ Version, VersionReq };
+
+use crate::{ IndexVersion, RegistryIndex, Result };
+
+/// What the index knows about a registry dependency of a package
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutdatedDependency {
+    /// Name of the package declaring the dependency
+    pub member: String,
+    /// Key the dependency is declared under
+    pub name: String,
+    /// Name of the package depended on, taking any rename into account
+    pub package: String,
+    pub kind: v1::DependencyKind,
+    pub platform: Option<String>,
+    pub requirement: VersionReq,
+    /// Newest version in the lockfile which matches the requirement
+    pub locked: Option<Version>,
+    /// Whether the locked version has been yanked from the registry
+    pub locked_yanked: bool,
+    /// Newest release the requirement accepts
+    pub latest_compatible: Option<Version>,
+    /// Newest release overall
+    pub latest: Option<Version>,
+}
+
+impl OutdatedDependency {
+    /// Whether the newest release is outside of the requirement, so
+    /// updating to it means changing the manifest
+    pub fn is_breaking_update(&self) -> bool {
+        self.latest.as_ref().is_some_and(|latest| !self.requirement.matches(latest))
+    }
+
+    /// Whether a newer release is available than the locked version, or
+    /// than the requirement allows
+    pub fn is_outdated(&self) -> bool {
+        let newer_compatible = match (&self.locked, &self.latest_compatible) {
+            (Some(locked), Some(compatible)) => locked < compatible,
+            _ => false,
+        };
+        newer_compatible || self.is_breaking_update()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let version = |v: &Option<Version>| v.as_ref().map(|v| v.to_string());
+        json!({
+            "member": self.member,
+            "name": self.name,
+            "package": self.package,
+            "kind": self.kind.to_string(),
+            "platform": self.platform,
+            "requirement": self.requirement.to_string(),
+            "locked": version(&self.locked),
+            "locked_yanked": self.locked_yanked,
+            "latest_compatible": version(&self.latest_compatible),
+            "latest": version(&self.latest),
+            "breaking": self.is_breaking_update(),
+        })
+    }
+}
+
+/// Every registry dependency of a set of packages with the versions
+/// available for it.
+///
+/// `Display` renders an aligned table, while `to_json` serves tools.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OutdatedReport {
+    pub dependencies: Vec<OutdatedDependency>,
+}
+
+impl OutdatedReport {
+    pub fn outdated(&self) -> impl Iterator<Item = &OutdatedDependency> {
+        self.dependencies.iter().filter(|dep| dep.is_outdated())
+    }
+
+    /// Dependencies locked to a yanked version
+    pub fn yanked(&self) -> impl Iterator<Item = &OutdatedDependency> {
+        self.dependencies.iter().filter(|dep| dep.locked_yanked)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({ "dependencies": self.dependencies.iter().map(OutdatedDependency::to_json).collect::<Vec<_>>() })
+    }
+
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string_pretty(&self.to_json()).unwrap_or_default()
+    }
+}
+
+const TABLE_HEADER: [&str; 7] = ["Member", "Dependency", "Kind", "Requirement", "Locked", "Compatible", "Latest"];
+
+impl fmt::Display for OutdatedReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let version = |v: &Option<Version>| v.as_ref().map_or_else(|| "-".to_owned(), |v| v.to_string());
+        let mut rows = vec![TABLE_HEADER.iter().map(|h| h.to_string()).collect::<Vec<_>>()];
+        for dep in &self.dependencies {
+            let name = if dep.name == dep.package { dep.name.to_owned() } else { format!("{} ({})", dep.name, dep.package) };
+            let kind = match &dep.platform {
+                Some(platform) => format!("{} ({})", dep.kind, platform),
+                None => dep.kind.to_string(),
+            };
+            let locked = if dep.locked_yanked { format!("{} (yanked)", version(&dep.locked)) } else { version(&dep.locked) };
+            let latest = if dep.is_breaking_update() { format!("{} (breaking)", version(&dep.latest)) } else { version(&dep.latest) };
+            rows.push(vec![
+                dep.member.to_owned(), name, kind, dep.requirement.to_string(), locked, version(&dep.latest_compatible), latest,
+            ]);
+        }
+        let widths: Vec<_> = (0..TABLE_HEADER.len())
+            .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+            .collect();
+        for row in rows {
+            let cells: Vec<_> = row.iter().zip(&widths).map(|(cell, width)| format!("{:1$}", cell, width)).collect();
+            writeln!(f, "{}", cells.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Newest release among `versions` accepted by `filter`, leaving out
+/// yanked versions and pre-releases
+fn newest<F>(versions: &[IndexVersion], filter: F) -> Option<&Version>
+where
+    F: Fn(&Version) -> bool,
+{
+    versions.iter()
+        .filter(|v| !v.yanked && !v.version.is_prerelease() && filter(&v.version))
+        .map(|v| &v.version)
+        .max()
+}
+
+/// Newest registry package in the lockfile which matches `requirement`
+fn locked_version(lockfile: &lockfile::Lockfile, package: &str, requirement: &VersionReq) -> Option<Version> {
+    lockfile.packages_named(package)
+        .filter(|p| p.is_registry() && requirement.matches(&p.version))
+        .map(|p| p.version.to_owned())
+        .max()
+}
+
+/// Looks up every crates.io dependency of `projects` in `index`, such as
+/// the members of a workspace sharing `lockfile`.
+///
+/// Dependencies from other sources are left out, while those missing from
+/// the index are reported without any available versions.
+pub fn outdated(
+    projects: &[v1::Project],
+    lockfile: Option<&lockfile::Lockfile>,
+    index: &RegistryIndex,
+) -> Result<OutdatedReport> {
+    let mut report = OutdatedReport::default();
+    for project in projects {
+        for dep in &project.dependencies {
+            let requirement = match &dep.source {
+                v1::Dependency::DefaultRegistry(requirement) => requirement,
+                _ => continue,
+            };
+            let package = dep.package_name();
+            let versions = index.versions(package)?.unwrap_or_default();
+            let locked = lockfile.and_then(|lockfile| locked_version(lockfile, package, requirement));
+            let locked_yanked = locked.as_ref()
+                .is_some_and(|locked| versions.iter().any(|v| v.version == *locked && v.yanked));
+            report.dependencies.push(OutdatedDependency {
+                member: project.name.to_owned(),
+                name: dep.name.to_owned(),
+                package: package.to_owned(),
+                kind: dep.kind,
+                platform: dep.platform.to_owned(),
+                requirement: requirement.to_owned(),
+                locked,
+                locked_yanked,
+                latest_compatible: newest(&versions, |v| requirement.matches(v)).cloned(),
+                latest: newest(&versions, |_| true).cloned(),
+            });
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path };
+
+    use semver::{ Version, VersionReq };
+
+    use super::{ outdated, OutdatedReport };
+    use crate::{ RegistryIndex };
+
+    fn dependency(name: &str, kind: v1::DependencyKind, req: &str) -> v1::DeclaredDependency {
+        v1::DeclaredDependency::registry(name, kind, VersionReq::parse(req).unwrap())
+    }
+
+    fn project(dependencies: Vec<v1::DeclaredDependency>) -> v1::Project {
+        v1::Project { dependencies, .. v1::Project::new("app", Version::parse("0.1.0").unwrap()) }
+    }
+
+    const LOCKFILE: &str = r#"
+        version = 3
+
+        [[package]]
+        name = "log"
+        version = "0.4.20"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "rand"
+        version = "0.7.3"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "serde"
+        version = "1.0.190"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+    "#;
+
+    fn report() -> OutdatedReport {
+        let index = RegistryIndex::open(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/index")).unwrap();
+        let lockfile = lockfile::parse_lockfile(LOCKFILE).unwrap();
+        let mut dependencies = vec![
+            dependency("serde", v1::DependencyKind::Normal, "1.0"),
+            dependency("log", v1::DependencyKind::Normal, "0.4"),
+            dependency("rand", v1::DependencyKind::Development, "0.7"),
+            dependency("unknown", v1::DependencyKind::Normal, "1"),
+        ];
+        dependencies.push(v1::DeclaredDependency {
+            source: v1::Dependency::LocalPath("../local".into()),
+            .. dependency("local", v1::DependencyKind::Normal, "1")
+        });
+        match outdated(&[project(dependencies)], Some(&lockfile), &index) {
+            Ok (report) => report,
+            Err (err) => { assert!(false, "unexpected error: {}", err); OutdatedReport::default() },
+        }
+    }
+
+    #[test]
+    fn find_compatible_and_latest_versions() {
+        let report = report();
+        let summary: Vec<_> = report.dependencies.iter()
+            .map(|dep| (
+                dep.name.as_str(),
+                dep.locked.as_ref().map(|v| v.to_string()),
+                dep.latest_compatible.as_ref().map(|v| v.to_string()),
+                dep.latest.as_ref().map(|v| v.to_string()),
+            ))
+            .collect();
+        let some = |v: &str| Some(v.to_owned());
+        assert_eq!(vec![
+            ("serde", some("1.0.190"), some("1.0.195"), some("1.0.195")),
+            ("log", some("0.4.20"), some("0.4.21"), some("0.4.21")),
+            ("rand", some("0.7.3"), some("0.7.3"), some("0.8.5")),
+            ("unknown", None, None, None),
+        ], summary);
+
+        let outdated: Vec<_> = report.outdated().map(|dep| dep.name.as_str()).collect();
+        assert_eq!(vec!["serde", "log", "rand"], outdated);
+        let yanked: Vec<_> = report.yanked().map(|dep| dep.name.as_str()).collect();
+        assert_eq!(vec!["log"], yanked);
+        assert!(report.dependencies[2].is_breaking_update());
+        assert!(!report.dependencies[0].is_breaking_update());
+    }
+
+    #[test]
+    fn render_table() {
+        assert_eq!("\
+Member  Dependency  Kind              Requirement  Locked           Compatible  Latest
+app     serde       dependencies      ^1.0         1.0.190          1.0.195     1.0.195
+app     log         dependencies      ^0.4         0.4.20 (yanked)  0.4.21      0.4.21
+app     rand        dev-dependencies  ^0.7         0.7.3            0.7.3       0.8.5 (breaking)
+app     unknown     dependencies      ^1           -                -           -
+", report().to_string());
+    }
+
+    #[test]
+    fn render_json() {
+        let json = report().to_json();
+        assert_eq!(json!({
+            "member": "app",
+            "name": "log",
+            "package": "log",
+            "kind": "dependencies",
+            "platform": null,
+            "requirement": "^0.4",
+            "locked": "0.4.20",
+            "locked_yanked": true,
+            "latest_compatible": "0.4.21",
+            "latest": "0.4.21",
+            "breaking": false,
+        }), json["dependencies"][1]);
+    }
+}
@@ -0,0 +1,100 @@
+use std::fs;
+use std::io;
+use std::path::{ Path, PathBuf };
+
+use crate::{ Error, IndexVersion, Result };
+
+/// Path of a package's file relative to the root of an index, which is
+/// sharded by the length and leading characters of its lowercased name
+pub fn index_path(name: &str) -> PathBuf {
+    let name = name.to_lowercase();
+    match name.len() {
+        1 => Path::new("1").join(&name),
+        2 => Path::new("2").join(&name),
+        3 => Path::new("3").join(&name[..1]).join(&name),
+        _ => Path::new(&name[..2]).join(&name[2..4]).join(&name),
+    }
+}
+
+/// A registry index checked out in a local directory, such as a clone of
+/// the crates.io index or a mirror of it
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegistryIndex {
+    root: PathBuf,
+}
+
+impl RegistryIndex {
+    pub fn open(root: &Path) -> Result<RegistryIndex> {
+        if !root.is_dir() {
+            return Err(Error::Io(root.to_owned(), io::Error::new(io::ErrorKind::NotFound, "index directory not found")));
+        }
+        Ok(RegistryIndex { root: root.to_owned() })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Every published version of a package in the order they were
+    /// published, yanked ones included, or `None` when the index has no
+    /// such package
+    pub fn versions(&self, name: &str) -> Result<Option<Vec<IndexVersion>>> {
+        let path = self.root.join(index_path(name));
+        let data = match fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(Error::Io(path, err)),
+        };
+        data.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(number, line)| {
+                serde_json::from_str(line).map_err(|err| Error::InvalidEntry(path.to_owned(), number + 1, err))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path, PathBuf };
+
+    use super::{ index_path, RegistryIndex };
+
+    fn fixture_index() -> RegistryIndex {
+        RegistryIndex::open(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/index")).unwrap()
+    }
+
+    #[test]
+    fn shard_paths_by_name() {
+        assert_eq!(PathBuf::from("1/a"), index_path("a"));
+        assert_eq!(PathBuf::from("2/io"), index_path("io"));
+        assert_eq!(PathBuf::from("3/l/log"), index_path("log"));
+        assert_eq!(PathBuf::from("se/rd/serde"), index_path("Serde"));
+    }
+
+    #[test]
+    fn read_versions() {
+        match fixture_index().versions("serde") {
+            Ok (Some(versions)) => {
+                let numbers: Vec<_> = versions.iter().map(|v| v.version.to_string()).collect();
+                assert_eq!(vec!["1.0.190", "1.0.195", "2.0.0-alpha.1"], numbers);
+                assert_eq!(vec!["dep:serde_derive".to_owned()], versions[1].all_features()["derive"]);
+                assert_eq!(Some("1.31"), versions[1].rust_version.as_deref());
+                assert_eq!("=1.0.190", versions[0].deps[0].req);
+            },
+            Ok (None) => assert!(false, "serde should be in the index"),
+            Err (err) => assert!(false, "unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn not_find_missing_package() {
+        match fixture_index().versions("tokio") {
+            Ok (versions) => assert_eq!(None, versions),
+            Err (err) => assert!(false, "unexpected error: {}", err),
+        }
+    }
+}
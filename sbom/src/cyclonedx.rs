@@ -0,0 +1,83 @@
+use serde_json::{ Map, Value };
+
+use crate::{ ComponentSource, Sbom, SbomComponent, SbomOptions };
+
+pub const CYCLONEDX_SPEC_VERSION: &str = "1.5";
+
+fn component(component: &SbomComponent, is_root: bool) -> Value {
+    let mut object = Map::new();
+    object.insert("type".to_owned(), json!(if is_root { "application" } else { "library" }));
+    object.insert("bom-ref".to_owned(), json!(component.purl()));
+    object.insert("name".to_owned(), json!(component.name));
+    object.insert("version".to_owned(), json!(component.version.to_string()));
+    if !is_root {
+        // Development dependencies are not part of what is shipped
+        let scope = match component.scope {
+            v1::DependencyKind::Development => "excluded",
+            _ => "required",
+        };
+        object.insert("scope".to_owned(), json!(scope));
+    }
+    if let Some(checksum) = &component.checksum {
+        object.insert("hashes".to_owned(), json!([{ "alg": "SHA-256", "content": checksum }]));
+    }
+    if let Some(license) = &component.license {
+        object.insert("licenses".to_owned(), json!([{ "expression": license }]));
+    }
+    object.insert("purl".to_owned(), json!(component.purl()));
+    let mut references = vec![];
+    match &component.source {
+        ComponentSource::Git(repository) => references.push(json!({ "type": "vcs", "url": repository.repo.to_string() })),
+        ComponentSource::Registry(_) => {
+            if let Some(location) = component.download_location() {
+                references.push(json!({ "type": "distribution", "url": location }));
+            }
+        },
+        ComponentSource::Path(_) => {},
+    }
+    if !references.is_empty() {
+        object.insert("externalReferences".to_owned(), json!(references));
+    }
+    let mut properties = vec![json!({ "name": "cargo:dependency_kind", "value": component.scope.to_string() })];
+    if let ComponentSource::Path(Some(path)) = &component.source {
+        properties.push(json!({ "name": "cargo:path", "value": path.to_string_lossy() }));
+    }
+    if let ComponentSource::Git(repository) = &component.source {
+        let reference = match &repository.reference {
+            v1::GitReference::Tag(tag) => format!("tag={}", tag),
+            v1::GitReference::Branch(branch) => format!("branch={}", branch),
+            v1::GitReference::Rev(rev) => format!("rev={}", rev),
+        };
+        properties.push(json!({ "name": "cargo:git_reference", "value": reference }));
+    }
+    object.insert("properties".to_owned(), json!(properties));
+    Value::Object(object)
+}
+
+impl Sbom {
+    /// A CycloneDX 1.5 JSON document, where development-only components
+    /// have the `excluded` scope and every component records its
+    /// dependency kind in a `cargo:dependency_kind` property
+    pub fn to_cyclonedx(&self, options: &SbomOptions) -> Value {
+        let dependencies: Vec<_> = self.components.iter()
+            .enumerate()
+            .map(|(index, c)| json!({
+                "ref": c.purl(),
+                "dependsOn": self.dependencies_of(index).map(|(to, _)| self.components[to].purl()).collect::<Vec<_>>(),
+            }))
+            .collect();
+        json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": CYCLONEDX_SPEC_VERSION,
+            "serialNumber": format!("urn:uuid:{}", options.uuid),
+            "version": 1,
+            "metadata": {
+                "timestamp": options.created,
+                "tools": { "components": [{ "type": "application", "name": options.tool }] },
+                "component": component(self.root(), true),
+            },
+            "components": self.components[1..].iter().map(|c| component(c, false)).collect::<Vec<_>>(),
+            "dependencies": dependencies,
+        })
+    }
+}
@@ -0,0 +1,256 @@
+extern crate omni_manifest_lockfile as lockfile;
+extern crate omni_manifest_v1 as v1;
+extern crate semver;
+#[macro_use]
+extern crate serde_json;
+extern crate url;
+
+mod cyclonedx;
+mod sbom;
+mod spdx;
+
+pub use self::cyclonedx::*;
+pub use self::sbom::*;
+pub use self::spdx::*;
+
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// A lockfile `source` which cannot be understood
+    InvalidSource(String, v1::Error),
+    Lockfile(lockfile::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidSource(source, err) => write!(f, "invalid package source `{}`: {}", source, err),
+            Error::Lockfile(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ PathBuf };
+
+    use semver::{ Version, VersionReq };
+
+    use super::{ ComponentSource, Error, Sbom, SbomOptions };
+
+    fn registry(name: &str, kind: v1::DependencyKind, req: &str) -> v1::DeclaredDependency {
+        v1::DeclaredDependency::registry(name, kind, VersionReq::parse(req).unwrap())
+    }
+
+    fn project() -> v1::Project {
+        let git = v1::GitRepository::from_url_string("https://github.com/example/mygit?branch=main".to_owned()).unwrap();
+        v1::Project {
+            metadata: v1::Metadata {
+                license: Some("MIT OR Apache-2.0".to_owned()),
+                .. Default::default()
+            },
+            dependencies: vec![
+                registry("serde", v1::DependencyKind::Normal, "1.0"),
+                v1::DeclaredDependency::new("mygit", v1::DependencyKind::Normal, v1::Dependency::Git(git)),
+                registry("cc", v1::DependencyKind::Build, "1.0"),
+                registry("rand", v1::DependencyKind::Development, "0.8"),
+                v1::DeclaredDependency::new("helper", v1::DependencyKind::Development, v1::Dependency::LocalPath(PathBuf::from("../helper"))),
+            ],
+            .. v1::Project::new("app", Version::parse("0.1.0").unwrap())
+        }
+    }
+
+    const LOCKFILE: &str = r#"
+        version = 3
+
+        [[package]]
+        name = "app"
+        version = "0.1.0"
+        dependencies = ["cc", "helper", "mygit", "rand", "serde"]
+
+        [[package]]
+        name = "cc"
+        version = "1.0.83"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        checksum = "f1174fb0b6ec23863f8b971027804a42614e347eafb0a95bf0b12cdae21fc4d0"
+        dependencies = ["jobserver"]
+
+        [[package]]
+        name = "helper"
+        version = "0.2.0"
+
+        [[package]]
+        name = "jobserver"
+        version = "0.1.27"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        checksum = "8c37f63953c4c63420ed5fd3d6d398c719489b9f872b9fa683262f8edd363c7d"
+
+        [[package]]
+        name = "libc"
+        version = "0.2.150"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        checksum = "89d92a4743f9a61002fae18374ed11e7973f530cb3a3255fb354818118b2203c"
+
+        [[package]]
+        name = "mygit"
+        version = "0.3.0"
+        source = "git+https://github.com/example/mygit?branch=main#0123456789abcdef0123456789abcdef01234567"
+        dependencies = ["libc"]
+
+        [[package]]
+        name = "rand"
+        version = "0.8.5"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        checksum = "34af8d1a0e25924bc5b7c43c079c942339d8f0a8b57c39049bef581b46327404"
+        dependencies = ["libc"]
+
+        [[package]]
+        name = "serde"
+        version = "1.0.190"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        checksum = "91d3c334ca1ee894a2c6f6ad698fe8c435b76d504b13d436f0685d648d6d96f7"
+    "#;
+
+    fn options() -> SbomOptions {
+        SbomOptions {
+            created: "2024-05-01T12:00:00Z".to_owned(),
+            uuid: "3e671687-395b-41f5-a30f-a58921a69b79".to_owned(),
+            tool: "omni-manifest-sbom-1.0.0".to_owned(),
+        }
+    }
+
+    fn sbom() -> Sbom {
+        let lockfile = lockfile::parse_lockfile(LOCKFILE).unwrap();
+        match Sbom::new(&project(), &lockfile) {
+            Ok (sbom) => sbom,
+            Err (err) => { assert!(false, "unexpected error: {}", err); unreachable!() },
+        }
+    }
+
+    #[test]
+    fn collect_components_with_scopes() {
+        let sbom = sbom();
+        let components: Vec<_> = sbom.components.iter()
+            .map(|c| (c.name.as_str(), c.scope))
+            .collect();
+        assert_eq!(vec![
+            ("app", v1::DependencyKind::Normal),
+            ("cc", v1::DependencyKind::Build),
+            ("helper", v1::DependencyKind::Development),
+            ("mygit", v1::DependencyKind::Normal),
+            ("rand", v1::DependencyKind::Development),
+            ("serde", v1::DependencyKind::Normal),
+            ("jobserver", v1::DependencyKind::Build),
+            ("libc", v1::DependencyKind::Normal),
+        ], components);
+        assert_eq!(Some("MIT OR Apache-2.0"), sbom.root().license.as_deref());
+        assert_eq!(ComponentSource::Path(Some(PathBuf::from("../helper"))), sbom.components[2].source);
+        match &sbom.components[3].source {
+            ComponentSource::Git(repository) => {
+                assert_eq!(Some("0123456789abcdef0123456789abcdef01234567"), repository.precise.as_deref());
+                assert_eq!(v1::GitReference::Branch("main".to_owned()), repository.reference);
+            },
+            source => assert!(false, "expected a git source: {:?}", source),
+        }
+    }
+
+    #[test]
+    fn build_package_urls() {
+        let sbom = sbom();
+        assert_eq!("pkg:cargo/serde@1.0.190", sbom.components[5].purl());
+        assert_eq!(
+            "pkg:cargo/mygit@0.3.0?vcs_url=git%2Bhttps%3A%2F%2Fgithub.com%2Fexample%2Fmygit%400123456789abcdef0123456789abcdef01234567",
+            sbom.components[3].purl()
+        );
+    }
+
+    #[test]
+    fn fail_without_locked_root() {
+        let mut project = project();
+        project.version = Version::parse("0.2.0").unwrap();
+        match Sbom::new(&project, &lockfile::Lockfile::default()) {
+            Ok (_) => assert!(false, "should require the root package in the lockfile"),
+            Err (Error::Lockfile(lockfile::Error::NotLocked(name, _))) => assert_eq!("app", name),
+            Err (err) => assert!(false, "expected NotLocked: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn render_cyclonedx() {
+        let mut sbom = sbom();
+        sbom.set_license("serde", &Version::parse("1.0.190").unwrap(), "MIT OR Apache-2.0");
+        let bom = sbom.to_cyclonedx(&options());
+        assert_eq!("CycloneDX", bom["bomFormat"]);
+        assert_eq!("1.5", bom["specVersion"]);
+        assert_eq!("urn:uuid:3e671687-395b-41f5-a30f-a58921a69b79", bom["serialNumber"]);
+        assert_eq!("pkg:cargo/app@0.1.0", bom["metadata"]["component"]["bom-ref"]);
+        assert_eq!(json!({
+            "type": "library",
+            "bom-ref": "pkg:cargo/serde@1.0.190",
+            "name": "serde",
+            "version": "1.0.190",
+            "scope": "required",
+            "hashes": [{ "alg": "SHA-256", "content": "91d3c334ca1ee894a2c6f6ad698fe8c435b76d504b13d436f0685d648d6d96f7" }],
+            "licenses": [{ "expression": "MIT OR Apache-2.0" }],
+            "purl": "pkg:cargo/serde@1.0.190",
+            "externalReferences": [{ "type": "distribution", "url": "https://crates.io/api/v1/crates/serde/1.0.190/download" }],
+            "properties": [{ "name": "cargo:dependency_kind", "value": "dependencies" }],
+        }), bom["components"][4]);
+        assert_eq!("excluded", bom["components"][3]["scope"]);
+        assert_eq!(json!({
+            "ref": "pkg:cargo/rand@0.8.5",
+            "dependsOn": ["pkg:cargo/libc@0.2.150"],
+        }), bom["dependencies"][4]);
+    }
+
+    #[test]
+    fn render_spdx_json() {
+        let document = sbom().to_spdx_json(&options());
+        assert_eq!("SPDX-2.3", document["spdxVersion"]);
+        assert_eq!(
+            "https://spdx.org/spdxdocs/app-0.1.0-3e671687-395b-41f5-a30f-a58921a69b79",
+            document["documentNamespace"]
+        );
+        assert_eq!("MIT OR Apache-2.0", document["packages"][0]["licenseDeclared"]);
+        assert_eq!("NOASSERTION", document["packages"][2]["downloadLocation"]);
+        let relationships: Vec<_> = document["relationships"].as_array().unwrap().iter()
+            .take(6)
+            .map(|r| format!(
+                "{} {} {}",
+                r["spdxElementId"].as_str().unwrap(),
+                r["relationshipType"].as_str().unwrap(),
+                r["relatedSpdxElement"].as_str().unwrap()
+            ))
+            .collect();
+        assert_eq!(vec![
+            "SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-app-0.1.0",
+            "SPDXRef-Package-cc-1.0.83 BUILD_DEPENDENCY_OF SPDXRef-Package-app-0.1.0",
+            "SPDXRef-Package-helper-0.2.0 DEV_DEPENDENCY_OF SPDXRef-Package-app-0.1.0",
+            "SPDXRef-Package-app-0.1.0 DEPENDS_ON SPDXRef-Package-mygit-0.3.0",
+            "SPDXRef-Package-rand-0.8.5 DEV_DEPENDENCY_OF SPDXRef-Package-app-0.1.0",
+            "SPDXRef-Package-app-0.1.0 DEPENDS_ON SPDXRef-Package-serde-1.0.190",
+        ], relationships);
+    }
+
+    #[test]
+    fn render_spdx_tag_value() {
+        let document = sbom().to_spdx_tag_value(&options());
+        assert!(document.starts_with("SPDXVersion: SPDX-2.3\nDataLicense: CC0-1.0\nSPDXID: SPDXRef-DOCUMENT\n"));
+        assert!(document.contains("\
+PackageName: mygit
+SPDXID: SPDXRef-Package-mygit-0.3.0
+PackageVersion: 0.3.0
+PackageDownloadLocation: git+https://github.com/example/mygit@0123456789abcdef0123456789abcdef01234567
+FilesAnalyzed: false
+PackageLicenseConcluded: NOASSERTION
+PackageLicenseDeclared: NOASSERTION
+PackageCopyrightText: NOASSERTION
+"), "{}", document);
+        assert!(document.contains("PackageChecksum: SHA256: 91d3c334ca1ee894a2c6f6ad698fe8c435b76d504b13d436f0685d648d6d96f7\n"));
+        assert!(document.contains("Relationship: SPDXRef-Package-mygit-0.3.0 DEPENDS_ON SPDXRef-Package-libc-0.2.150\n"));
+    }
+}
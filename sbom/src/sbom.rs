@@ -0,0 +1,236 @@
+use std::path::{ PathBuf };
+
+use semver::Version;
+
+use crate::{ Error, Result };
+
+/// Registry index url of crates.io as written in lockfiles
+pub const CRATES_IO_INDEX: &str = "https://github.com/rust-lang/crates.io-index";
+
+/// Where a component comes from
+#[derive(Clone, Debug, PartialEq)]
+pub enum ComponentSource {
+    /// A registry with this index url
+    Registry(String),
+    /// A git repository, where `precise` holds the locked revision
+    Git(v1::GitRepository),
+    /// A local path, which is only known for dependencies declared by the
+    /// root package
+    Path(Option<PathBuf>),
+}
+
+/// A package in the bill of materials
+#[derive(Clone, Debug, PartialEq)]
+pub struct SbomComponent {
+    pub name: String,
+    pub version: Version,
+    pub source: ComponentSource,
+    /// SPDX license expression
+    pub license: Option<String>,
+    /// sha256 of the `.crate` file of registry packages
+    pub checksum: Option<String>,
+    /// `Development` or `Build` when the component is only reached through
+    /// development or build dependencies of the root package
+    pub scope: v1::DependencyKind,
+}
+
+impl SbomComponent {
+    /// Package URL such as `pkg:cargo/serde@1.0.190`, qualified with the
+    /// repository of git sources
+    pub fn purl(&self) -> String {
+        let purl = format!("pkg:cargo/{}@{}", self.name, self.version);
+        match &self.source {
+            ComponentSource::Git(repository) => {
+                let vcs = match &repository.precise {
+                    Some(rev) => format!("git+{}@{}", repository.repo, rev),
+                    None => format!("git+{}", repository.repo),
+                };
+                let vcs: String = url::form_urlencoded::byte_serialize(vcs.as_bytes()).collect();
+                format!("{}?vcs_url={}", purl, vcs)
+            },
+            ComponentSource::Registry(index) if index != CRATES_IO_INDEX => {
+                let index: String = url::form_urlencoded::byte_serialize(index.as_bytes()).collect();
+                format!("{}?repository_url={}", purl, index)
+            },
+            _ => purl,
+        }
+    }
+
+    /// Where the component can be downloaded from, if anywhere
+    pub fn download_location(&self) -> Option<String> {
+        match &self.source {
+            ComponentSource::Registry(index) if index == CRATES_IO_INDEX => Some(format!(
+                "https://crates.io/api/v1/crates/{}/{}/download", self.name, self.version
+            )),
+            ComponentSource::Registry(_) | ComponentSource::Path(_) => None,
+            ComponentSource::Git(repository) => Some(match &repository.precise {
+                Some(rev) => format!("git+{}@{}", repository.repo, rev),
+                None => format!("git+{}", repository.repo),
+            }),
+        }
+    }
+}
+
+/// A dependency of one component on another, by their index in
+/// `Sbom::components`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SbomRelationship {
+    pub from: usize,
+    pub to: usize,
+    /// How the root package declares the dependency, which is `Normal` for
+    /// dependencies between other components since lockfiles do not record
+    /// their kind
+    pub kind: v1::DependencyKind,
+}
+
+/// Values which make a document unique, passed in so documents can be
+/// generated reproducibly
+#[derive(Clone, Debug, PartialEq)]
+pub struct SbomOptions {
+    /// RFC 3339 time of creation, e.g. `2024-05-01T12:00:00Z`
+    pub created: String,
+    /// UUID of the CycloneDX serial number and SPDX document namespace
+    pub uuid: String,
+    /// Tool recorded as the creator of the document
+    pub tool: String,
+}
+
+/// The packages a package is built from according to its lockfile, which
+/// renders as CycloneDX or SPDX.
+///
+/// The root package comes first and is followed by the packages it depends
+/// on, directly or not, in breadth-first order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sbom {
+    pub components: Vec<SbomComponent>,
+    pub relationships: Vec<SbomRelationship>,
+}
+
+fn component_source(package: &lockfile::LockedPackage) -> Result<ComponentSource> {
+    let source = match &package.source {
+        Some(source) => source,
+        None => return Ok(ComponentSource::Path(None)),
+    };
+    if let Some(url) = source.strip_prefix("git+") {
+        return v1::GitRepository::from_url_string(url.to_owned())
+            .map(ComponentSource::Git)
+            .map_err(|err| Error::InvalidSource(source.to_owned(), err));
+    }
+    let index = source.strip_prefix("registry+").or_else(|| source.strip_prefix("sparse+")).unwrap_or(source);
+    Ok(ComponentSource::Registry(index.to_owned()))
+}
+
+/// Orders kinds from the most to the least significant for a build
+fn rank(kind: v1::DependencyKind) -> u8 {
+    match kind {
+        v1::DependencyKind::Normal => 0,
+        v1::DependencyKind::Build => 1,
+        v1::DependencyKind::Development => 2,
+    }
+}
+
+/// The declarations of `project` which `package` satisfies
+fn declarations<'a>(project: &'a v1::Project, package: &'a lockfile::LockedPackage) -> impl Iterator<Item = &'a v1::DeclaredDependency> {
+    project.dependencies.iter().filter(move |dep| {
+        dep.package_name() == package.name && dep.version.as_ref().is_none_or(|req| req.matches(&package.version))
+    })
+}
+
+impl Sbom {
+    /// Collects the packages `project` is built from out of `lockfile`.
+    ///
+    /// Only the license of the root package is known from its manifest,
+    /// others can be filled in with `set_license`.
+    pub fn new(project: &v1::Project, lockfile: &lockfile::Lockfile) -> Result<Sbom> {
+        let root = lockfile.path_package(&project.name, &project.version).map_err(Error::Lockfile)?;
+
+        // Walk the lockfile breadth-first from the root package
+        let mut packages = vec![root];
+        let mut relationships = vec![];
+        let mut next = 0;
+        while next < packages.len() {
+            let from = next;
+            next += 1;
+            for dep in lockfile.dependencies_of(packages[from]) {
+                let to = match packages.iter().position(|p| p == &dep) {
+                    Some(to) => to,
+                    None => {
+                        packages.push(dep);
+                        packages.len() - 1
+                    },
+                };
+                let kind = if from == 0 {
+                    declarations(project, dep)
+                        .map(|d| d.kind)
+                        .min_by_key(|kind| rank(*kind))
+                        .unwrap_or(v1::DependencyKind::Normal)
+                } else {
+                    v1::DependencyKind::Normal
+                };
+                relationships.push(SbomRelationship { from, to, kind });
+            }
+        }
+
+        // A component has the most significant scope of any path to it,
+        // where the scope of a path is its least significant dependency
+        let mut scopes = vec![None; packages.len()];
+        scopes[0] = Some(v1::DependencyKind::Normal);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for relationship in &relationships {
+                let parent = match scopes[relationship.from] {
+                    Some(parent) => parent,
+                    None => continue,
+                };
+                let scope = if rank(parent) > rank(relationship.kind) { parent } else { relationship.kind };
+                if scopes[relationship.to].is_none_or(|current| rank(scope) < rank(current)) {
+                    scopes[relationship.to] = Some(scope);
+                    changed = true;
+                }
+            }
+        }
+
+        let components = packages.iter()
+            .zip(scopes)
+            .map(|(package, scope)| {
+                let source = match component_source(package)? {
+                    ComponentSource::Path(None) => ComponentSource::Path(
+                        declarations(project, package).find_map(|dep| match &dep.source {
+                            v1::Dependency::LocalPath(path) => Some(path.to_owned()),
+                            _ => None,
+                        })
+                    ),
+                    source => source,
+                };
+                Ok(SbomComponent {
+                    name: package.name.to_owned(),
+                    version: package.version.to_owned(),
+                    source,
+                    license: None,
+                    checksum: package.checksum.to_owned(),
+                    scope: scope.unwrap_or(v1::DependencyKind::Normal),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut sbom = Sbom { components, relationships };
+        sbom.components[0].license = project.metadata.license.to_owned();
+        Ok(sbom)
+    }
+
+    pub fn root(&self) -> &SbomComponent {
+        &self.components[0]
+    }
+
+    /// Sets the license of a component, e.g. from its published manifest
+    pub fn set_license(&mut self, name: &str, version: &Version, license: &str) {
+        for component in self.components.iter_mut().filter(|c| c.name == name && c.version == *version) {
+            component.license = Some(license.to_owned());
+        }
+    }
+
+    /// Components `index` depends on with the kind of each dependency
+    pub fn dependencies_of(&self, index: usize) -> impl Iterator<Item = (usize, v1::DependencyKind)> + '_ {
+        self.relationships.iter().filter(move |r| r.from == index).map(|r| (r.to, r.kind))
+    }
+}
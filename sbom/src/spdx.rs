@@ -0,0 +1,144 @@
+use serde_json::{ Value };
+
+use crate::{ Sbom, SbomComponent, SbomOptions };
+
+pub const SPDX_VERSION: &str = "SPDX-2.3";
+
+const NOASSERTION: &str = "NOASSERTION";
+
+/// An SPDX identifier, which may only contain letters, digits, `.` and `-`
+fn spdx_id(component: &SbomComponent) -> String {
+    let id = format!("SPDXRef-Package-{}-{}", component.name, component.version);
+    id.chars().map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '-' }).collect()
+}
+
+/// Relationship as `(element, type, related element)`
+type Relationship = (String, &'static str, String);
+
+impl Sbom {
+    /// Identifiers of the packages, made unique when several components
+    /// share a name and version
+    fn spdx_ids(&self) -> Vec<String> {
+        let ids: Vec<_> = self.components.iter().map(spdx_id).collect();
+        ids.iter()
+            .enumerate()
+            .map(|(index, id)| {
+                if ids.iter().filter(|other| *other == id).count() > 1 {
+                    format!("{}-{}", id, index)
+                } else {
+                    id.to_owned()
+                }
+            })
+            .collect()
+    }
+
+    fn spdx_relationships(&self, ids: &[String]) -> Vec<Relationship> {
+        let mut relationships = vec![("SPDXRef-DOCUMENT".to_owned(), "DESCRIBES", ids[0].to_owned())];
+        for relationship in &self.relationships {
+            let (from, to) = (ids[relationship.from].to_owned(), ids[relationship.to].to_owned());
+            relationships.push(match relationship.kind {
+                v1::DependencyKind::Normal => (from, "DEPENDS_ON", to),
+                v1::DependencyKind::Development => (to, "DEV_DEPENDENCY_OF", from),
+                v1::DependencyKind::Build => (to, "BUILD_DEPENDENCY_OF", from),
+            });
+        }
+        relationships
+    }
+
+    fn spdx_name(&self) -> String {
+        format!("{}-{}", self.root().name, self.root().version)
+    }
+
+    fn spdx_namespace(&self, options: &SbomOptions) -> String {
+        format!("https://spdx.org/spdxdocs/{}-{}", self.spdx_name(), options.uuid)
+    }
+
+    /// An SPDX 2.3 JSON document, where dependencies of the root package
+    /// are related by `DEPENDS_ON`, `DEV_DEPENDENCY_OF` or
+    /// `BUILD_DEPENDENCY_OF` according to how they are declared
+    pub fn to_spdx_json(&self, options: &SbomOptions) -> Value {
+        let ids = self.spdx_ids();
+        let packages: Vec<_> = self.components.iter()
+            .zip(&ids)
+            .map(|(component, id)| {
+                let checksums: Vec<_> = component.checksum.iter()
+                    .map(|checksum| json!({ "algorithm": "SHA256", "checksumValue": checksum }))
+                    .collect();
+                json!({
+                    "name": component.name,
+                    "SPDXID": id,
+                    "versionInfo": component.version.to_string(),
+                    "downloadLocation": component.download_location().unwrap_or_else(|| NOASSERTION.to_owned()),
+                    "filesAnalyzed": false,
+                    "checksums": checksums,
+                    "licenseConcluded": NOASSERTION,
+                    "licenseDeclared": component.license.as_deref().unwrap_or(NOASSERTION),
+                    "copyrightText": NOASSERTION,
+                    "externalRefs": [{
+                        "referenceCategory": "PACKAGE-MANAGER",
+                        "referenceType": "purl",
+                        "referenceLocator": component.purl(),
+                    }],
+                })
+            })
+            .collect();
+        let relationships: Vec<_> = self.spdx_relationships(&ids).into_iter()
+            .map(|(element, kind, related)| json!({
+                "spdxElementId": element,
+                "relationshipType": kind,
+                "relatedSpdxElement": related,
+            }))
+            .collect();
+        json!({
+            "spdxVersion": SPDX_VERSION,
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": self.spdx_name(),
+            "documentNamespace": self.spdx_namespace(options),
+            "creationInfo": {
+                "created": options.created,
+                "creators": [format!("Tool: {}", options.tool)],
+            },
+            "packages": packages,
+            "relationships": relationships,
+        })
+    }
+
+    /// The same document as `to_spdx_json` in the tag-value format
+    pub fn to_spdx_tag_value(&self, options: &SbomOptions) -> String {
+        let ids = self.spdx_ids();
+        let mut lines = vec![
+            format!("SPDXVersion: {}", SPDX_VERSION),
+            "DataLicense: CC0-1.0".to_owned(),
+            "SPDXID: SPDXRef-DOCUMENT".to_owned(),
+            format!("DocumentName: {}", self.spdx_name()),
+            format!("DocumentNamespace: {}", self.spdx_namespace(options)),
+            format!("Creator: Tool: {}", options.tool),
+            format!("Created: {}", options.created),
+        ];
+        for (component, id) in self.components.iter().zip(&ids) {
+            lines.push(String::new());
+            lines.push(format!("PackageName: {}", component.name));
+            lines.push(format!("SPDXID: {}", id));
+            lines.push(format!("PackageVersion: {}", component.version));
+            lines.push(format!(
+                "PackageDownloadLocation: {}",
+                component.download_location().unwrap_or_else(|| NOASSERTION.to_owned())
+            ));
+            lines.push("FilesAnalyzed: false".to_owned());
+            if let Some(checksum) = &component.checksum {
+                lines.push(format!("PackageChecksum: SHA256: {}", checksum));
+            }
+            lines.push(format!("PackageLicenseConcluded: {}", NOASSERTION));
+            lines.push(format!("PackageLicenseDeclared: {}", component.license.as_deref().unwrap_or(NOASSERTION)));
+            lines.push(format!("PackageCopyrightText: {}", NOASSERTION));
+            lines.push(format!("ExternalRef: PACKAGE-MANAGER purl {}", component.purl()));
+        }
+        lines.push(String::new());
+        for (element, kind, related) in self.spdx_relationships(&ids) {
+            lines.push(format!("Relationship: {} {} {}", element, kind, related));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
@@ -9,9 +9,14 @@ extern crate omni_manifest_v1 as v1;
 use serde::de::Deserialize;
 use std::fmt;
 
+mod tool_metadata;
+
+pub use self::tool_metadata::*;
+
 // #[derive(Debug, Fail)]
 #[derive(Debug)]
 pub enum Error {
+    ConversionError(schema_v1_to_v1::Error),
     DeserializerError(toml::de::Error),
     FailedToParseToml(String),
     UnusedKeys(Vec<String>),
@@ -20,6 +25,9 @@ pub enum Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Error::ConversionError(err) => {
+                write!(f, "Failed to convert Manifest: {}", err)
+            },
             Error::DeserializerError(err) => {
                 write!(f, "Failed to Deserialize TOML into Manifest: {}", err)
             }
@@ -43,7 +51,7 @@ fn parse_err<S: Into<String>>(value: S) -> Error {
     Error::FailedToParseToml(value.into())
 }
 
-pub const TOML_WITHOUT_NEWLINES: &'static str =
+pub const TOML_WITHOUT_NEWLINES: &str =
 r#"The TOML spec requires newlines after table definitions (e.g., `[a] b = 1` is
 invalid), but this file has a table header which does not have a newline after
 it. A newline needs to be added in order to parse this file.
@@ -52,6 +60,7 @@ it. A newline needs to be added in order to parse this file.
 /// Deserializer which allows a less restrictive TOML parser option; Used
 /// to determine if previously allowed syntax "newlines after a table" is
 /// represented in the provided manifest
+#[allow(deprecated)]
 fn deserialize_toml_without_newlines(data: &str) -> Option<Error> {
     let mut parser = toml::de::Deserializer::new(data);
     parser.set_require_newline_after_table(false);
@@ -76,7 +85,8 @@ pub fn parse_cargo_toml(data: &str) -> Result<toml::Value> {
         .map_err(|e| deserialize_toml_without_newlines(data).unwrap_or(e))
 }
 
-fn stringify_serde_ignored_path(dst: &mut String, path: &serde_ignored::Path<'_>) {
+/// Renders a `serde_ignored` path as the dotted key used in error messages
+pub fn stringify_serde_ignored_path(dst: &mut String, path: &serde_ignored::Path<'_>) {
     use serde_ignored::Path;
 
     match *path {
@@ -112,7 +122,7 @@ pub fn parse_schema_v1(data: &str) -> Result<schema_v1::Manifest> {
             })
             .map_err(Error::DeserializerError)
             .and_then(|m| {
-                if ignored.len() == 0 {
+                if ignored.is_empty() {
                     Ok(m)
                 } else {
                     Err(Error::UnusedKeys(ignored))
@@ -121,24 +131,25 @@ pub fn parse_schema_v1(data: &str) -> Result<schema_v1::Manifest> {
         })
 }
 
-pub fn parse(data: &str) -> Result<v1::Manifest> {
+/// Parses and validates a manifest, returning any warnings raised while
+/// converting it
+pub fn parse(data: &str) -> Result<(v1::Manifest, schema_v1_to_v1::OptionVec<schema_v1_to_v1::Warning>)> {
     parse_schema_v1(data)
         .and_then(|schema_v1| {
-            
-            println!("Convert schema: {:?}", schema_v1);
-            // schema_v1_to_v1::convert(schema_v1)
-            Ok(v1::Manifest::Project)
+            schema_v1_to_v1::convert(&schema_v1)
+                .map_err(Error::ConversionError)
         })
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
 mod omni_toml_parser {
     mod tests {
         extern crate semver;
         extern crate omni_manifest_toml_schema_v1 as schema_v1;
 
         use crate::{ Error, TOML_WITHOUT_NEWLINES };
-        use crate::{ parse_cargo_toml, parse_toml, parse_schema_v1 };
+        use crate::{ parse, parse_cargo_toml, parse_toml, parse_schema_v1 };
 
         #[test]
         fn parse_valid_minimal_manifest() {
@@ -200,6 +211,100 @@ mod omni_toml_parser {
             }
         }
 
+        #[test]
+        fn parse_lints_without_unused_keys() {
+            match parse(r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+
+                [lints.rust]
+                unsafe_code = "forbid"
+
+                [lints.clippy]
+                all = { level = "deny", priority = -1 }
+            "#) {
+                Ok((v1::Manifest::Project(project), _)) => assert_eq!(2, project.lints.len()),
+                Ok(result) => assert!(false, "should have been a project: {:?}", result),
+                Err(err) => assert!(false, "unexpected error: {}", err),
+            }
+        }
+
+        #[test]
+        fn fail_to_parse_unknown_workspace_inherit_keys() {
+            match parse_schema_v1(r#"
+                [package]
+                name = "foo"
+                version = { workspace = true, typo = 1 }
+            "#) {
+                Ok(_) => assert!(false, "should have failed to parse the unknown `typo` key"),
+                Err(err) => assert!(err.to_string().contains("unknown field `typo`"), "unexpected error: {}", err),
+            }
+        }
+
+        #[test]
+        fn fail_to_parse_unknown_lint_table_keys() {
+            let manifests = [
+                r#"
+                    [lints.clippy]
+                    all = { level = "deny", priorty = -1 }
+                "#,
+                r#"
+                    [workspace.lints.clippy]
+                    all = { level = "deny", priorty = -1 }
+                "#,
+            ];
+            for manifest in manifests {
+                match parse_schema_v1(manifest) {
+                    Ok(_) => assert!(false, "should have failed to parse the misspelt `priority`"),
+                    Err(err) => assert!(
+                        err.to_string().contains("unknown field `priorty`"),
+                        "unexpected error: {}", err
+                    ),
+                }
+            }
+        }
+
+        #[test]
+        fn parse_legacy_aliases_with_warnings() {
+            match parse(r#"
+                [project]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2021"
+
+                [lib]
+                proc_macro = true
+
+                [dev_dependencies]
+                serde = { version = "1.0", default_features = false }
+            "#) {
+                Ok((v1::Manifest::Project(project), Some(warnings))) => {
+                    assert_eq!(Some(true), project.targets[0].proc_macro);
+                    assert_eq!(v1::DependencyKind::Development, project.dependencies[0].kind);
+                    assert!(!project.dependencies[0].default_features);
+                    assert_eq!(4, warnings.len(), "unexpected warnings: {:?}", warnings);
+                },
+                Ok(result) => assert!(false, "should have been a project with warnings: {:?}", result),
+                Err(err) => assert!(false, "unexpected error: {}", err),
+            }
+        }
+
+        #[test]
+        fn fail_to_parse_invalid_lint_level() {
+            match parse_schema_v1(r#"
+                [lints.rust]
+                unsafe_code = "error"
+            "#) {
+                Ok(_) => assert!(false, "should have failed to parse an invalid lint level"),
+                Err(err) => assert!(
+                    err.to_string().contains("lint level `error` is not valid"),
+                    "unexpected error: {}", err
+                ),
+            }
+        }
+
         #[test]
         fn parse_empty_toml_to_all_none_manifest_struct() {
             match parse_schema_v1(r#"
@@ -270,7 +375,7 @@ mod omni_toml_parser {
                         None => assert!(false, "should have a value for manifest package field but is:\n{:?}", m),
                         Some (package) => {
                             assert_eq!("foo", package.name);
-                            assert_eq!(semver::Version::parse("1.0.0").unwrap(), package.version);
+                            assert_eq!(schema_v1::MaybeWorkspace::Defined(semver::Version::parse("1.0.0").unwrap()), package.version);
                         }
                     }
                 },
@@ -322,7 +427,7 @@ mod omni_toml_parser {
                                 version: Some("1.0.0".to_owned()),
                                 .. Default::default()
                             };
-                            assert_eq!(&schema_v1::Dependency::Detailed(expected), dep1);
+                            assert_eq!(&schema_v1::Dependency::Detailed(Box::new(expected)), dep1);
                         },
                     }
                 },
@@ -350,7 +455,7 @@ mod omni_toml_parser {
                                 version: Some("1.0.0".to_owned()),
                                 .. Default::default()
                             };
-                            assert_eq!(&schema_v1::Dependency::Detailed(expected), dep1);
+                            assert_eq!(&schema_v1::Dependency::Detailed(Box::new(expected)), dep1);
                         },
                     }
                 },
@@ -409,5 +514,97 @@ mod omni_toml_parser {
                 Err(err) => assert!(false, "should have parsed successfully but instead:\n{}", err),
             }
         }
+
+        #[test]
+        fn parse_typed_edition_rust_version_and_resolver() {
+            match parse_schema_v1(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+            rust-version = "1.70"
+            resolver = "2"
+            "#) {
+                Ok(m) => {
+                    let package = m.package.unwrap();
+                    assert_eq!(Some(schema_v1::MaybeWorkspace::Defined(schema_v1::Edition::Edition2021)), package.edition);
+                    assert_eq!(Some(schema_v1::Resolver::V2), package.resolver);
+                    let rust_version = *package.rust_version.unwrap().as_defined().unwrap();
+                    assert_eq!((1, Some(70), None), (rust_version.major, rust_version.minor, rust_version.patch));
+                },
+                Err(err) => assert!(false, "should have parsed successfully but instead:\n{}", err),
+            }
+        }
+
+        #[test]
+        fn fail_to_parse_unknown_edition() {
+            match parse_schema_v1(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2030"
+            "#) {
+                Ok(_) => assert!(false, "should not have parsed unknown edition"),
+                Err(Error::DeserializerError(err)) => {
+                    assert!(format!("{}", err).contains("supported edition values"), "unexpected message: {}", err);
+                },
+                Err(err) => assert!(false, "should have gotten DeserializerError but was:\n{}", err),
+            }
+        }
+
+        #[test]
+        fn fail_to_parse_rust_version_with_pre_release() {
+            match parse_schema_v1(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            rust-version = "1.70.0-nightly"
+            "#) {
+                Ok(_) => assert!(false, "should not have parsed rust-version with pre-release"),
+                Err(Error::DeserializerError(_)) => {},
+                Err(err) => assert!(false, "should have gotten DeserializerError but was:\n{}", err),
+            }
+        }
+
+        #[test]
+        fn fail_to_parse_unknown_workspace_resolver() {
+            match parse_schema_v1(r#"
+            [workspace]
+            resolver = "4"
+            "#) {
+                Ok(_) => assert!(false, "should not have parsed unknown resolver"),
+                Err(Error::DeserializerError(_)) => {},
+                Err(err) => assert!(false, "should have gotten DeserializerError but was:\n{}", err),
+            }
+        }
+
+        #[test]
+        fn parse_and_convert_project() {
+            match parse(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2024"
+            "#) {
+                Ok((v1::Manifest::Project(project), None)) => assert_eq!("foo", project.name),
+                Ok(result) => assert!(false, "should have converted to a project without warnings: {:?}", result),
+                Err(err) => assert!(false, "should have parsed successfully but instead:\n{}", err),
+            }
+        }
+
+        #[test]
+        fn fail_to_parse_rust_version_older_than_edition() {
+            match parse(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2024"
+            rust-version = "1.80"
+            "#) {
+                Ok(_) => assert!(false, "should not have converted rust-version older than edition"),
+                Err(Error::ConversionError(_)) => {},
+                Err(err) => assert!(false, "should have gotten ConversionError but was:\n{}", err),
+            }
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,195 @@
+use serde::de::{ DeserializeOwned };
+
+use crate::{ stringify_serde_ignored_path, Error, Result };
+
+/// Settings an external tool keeps under `metadata.<tool>`, along with the
+/// keys of that table the settings have no field for
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToolMetadata<T> {
+    pub value: T,
+    /// Dotted paths of the unknown keys, e.g. `metadata.release.sign-tags`
+    pub unused_keys: Vec<String>,
+}
+
+/// Merges `overlay` into `base`, where tables are merged key by key and any
+/// other value of `overlay`, arrays included, replaces the one of `base`
+fn merge(base: toml::Value, overlay: toml::Value) -> toml::Value {
+    match (base, overlay) {
+        (toml::Value::Table(mut base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                let merged = match base.remove(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => value,
+                };
+                base.insert(key, merged);
+            }
+            toml::Value::Table(base)
+        },
+        (_, overlay) => overlay,
+    }
+}
+
+fn tool_table(metadata: Option<&toml::Value>, tool: &str) -> Option<toml::Value> {
+    metadata.and_then(|metadata| metadata.get(tool)).cloned()
+}
+
+/// Deserializes the `metadata.<tool>` tables of the package and of the
+/// workspace it belongs to into `T`.
+///
+/// The package's table is merged over the workspace's, so the package wins
+/// for any key both set while tables nested in both are merged key by key.
+/// Returns `None` when neither has a table for the tool.
+pub fn merge_tool_metadata<T: DeserializeOwned>(
+    workspace: Option<&toml::Value>,
+    package: Option<&toml::Value>,
+    tool: &str,
+) -> Result<Option<ToolMetadata<T>>> {
+    let table = match (tool_table(workspace, tool), tool_table(package, tool)) {
+        (Some(workspace), Some(package)) => merge(workspace, package),
+        (Some(table), None) | (None, Some(table)) => table,
+        (None, None) => return Ok(None),
+    };
+    let mut unused_keys = vec![];
+    let value = serde_ignored::deserialize(table, |path| {
+        let mut path_str = String::new();
+        stringify_serde_ignored_path(&mut path_str, &path);
+        unused_keys.push(format!("metadata.{}.{}", tool, path_str));
+    })
+    .map_err(Error::DeserializerError)?;
+    Ok(Some(ToolMetadata { value, unused_keys }))
+}
+
+/// Typed access to `[package.metadata.<tool>]` and `[workspace.metadata.<tool>]`
+pub trait ToolMetadataExt {
+    /// Settings of `tool` from the package and workspace tables of this
+    /// manifest, as merged by [`merge_tool_metadata`]
+    fn tool_metadata<T: DeserializeOwned>(&self, tool: &str) -> Result<Option<ToolMetadata<T>>>;
+
+    /// Settings of `tool` for a workspace member, with the workspace table
+    /// taken from the manifest of the workspace `root`
+    fn member_tool_metadata<T: DeserializeOwned>(
+        &self,
+        root: &schema_v1::Manifest,
+        tool: &str,
+    ) -> Result<Option<ToolMetadata<T>>>;
+}
+
+fn package_metadata(manifest: &schema_v1::Manifest) -> Option<&toml::Value> {
+    manifest.package.as_ref().or(manifest.project.as_ref()).and_then(|package| package.metadata.as_ref())
+}
+
+fn workspace_metadata(manifest: &schema_v1::Manifest) -> Option<&toml::Value> {
+    manifest.workspace.as_ref().and_then(|workspace| workspace.metadata.as_ref())
+}
+
+impl ToolMetadataExt for schema_v1::Manifest {
+    fn tool_metadata<T: DeserializeOwned>(&self, tool: &str) -> Result<Option<ToolMetadata<T>>> {
+        merge_tool_metadata(workspace_metadata(self), package_metadata(self), tool)
+    }
+
+    fn member_tool_metadata<T: DeserializeOwned>(
+        &self,
+        root: &schema_v1::Manifest,
+        tool: &str,
+    ) -> Result<Option<ToolMetadata<T>>> {
+        merge_tool_metadata(workspace_metadata(root), package_metadata(self), tool)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use serde::{ Deserialize };
+
+    use crate::{ parse_schema_v1, Error };
+    use super::{ ToolMetadata, ToolMetadataExt };
+
+    #[derive(Debug, Default, Deserialize, PartialEq)]
+    #[serde(rename_all = "kebab-case")]
+    struct Release {
+        sign_tag: Option<bool>,
+        #[serde(default)]
+        pre_release_hook: Vec<String>,
+        #[serde(default)]
+        replacements: std::collections::BTreeMap<String, String>,
+    }
+
+    #[test]
+    fn merge_package_over_workspace_metadata() {
+        let manifest = parse_schema_v1(r#"
+            [workspace]
+
+            [workspace.metadata.release]
+            sign-tag = true
+            pre-release-hook = ["./check.sh", "--all"]
+            replacements = { readme = "README.md", changelog = "CHANGELOG.md" }
+
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [package.metadata.release]
+            pre-release-hook = ["./check.sh"]
+            replacements = { changelog = "CHANGES.md" }
+            sign-tags = false
+        "#).unwrap();
+        match manifest.tool_metadata::<Release>("release") {
+            Ok (Some(ToolMetadata { value, unused_keys })) => {
+                assert_eq!(Some(true), value.sign_tag);
+                assert_eq!(vec!["./check.sh".to_owned()], value.pre_release_hook);
+                assert_eq!(Some("README.md"), value.replacements.get("readme").map(String::as_str));
+                assert_eq!(Some("CHANGES.md"), value.replacements.get("changelog").map(String::as_str));
+                assert_eq!(vec!["metadata.release.sign-tags".to_owned()], unused_keys);
+            },
+            Ok (None) => assert!(false, "should have found the release metadata"),
+            Err (err) => assert!(false, "unexpected error: {}", err),
+        }
+        match manifest.tool_metadata::<Release>("docs") {
+            Ok (None) => {},
+            result => assert!(false, "should not have found metadata for docs: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn read_member_metadata_from_workspace_root() {
+        let root = parse_schema_v1(r#"
+            [workspace]
+            members = ["crates/*"]
+
+            [workspace.metadata.release]
+            sign-tag = true
+        "#).unwrap();
+        let member = parse_schema_v1(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+        "#).unwrap();
+        match member.member_tool_metadata::<Release>(&root, "release") {
+            Ok (Some(metadata)) => assert_eq!(
+                ToolMetadata { value: Release { sign_tag: Some(true), .. Default::default() }, unused_keys: vec![] },
+                metadata,
+            ),
+            result => assert!(false, "should have inherited the workspace metadata: {:?}", result),
+        }
+        match member.tool_metadata::<Release>("release") {
+            Ok (None) => {},
+            result => assert!(false, "should not see the root's metadata by itself: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn fail_on_invalid_tool_metadata() {
+        let manifest = parse_schema_v1(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [package.metadata.release]
+            sign-tag = "yes"
+        "#).unwrap();
+        match manifest.tool_metadata::<Release>("release") {
+            Err (Error::DeserializerError(_)) => {},
+            result => assert!(false, "should have failed to deserialize `sign-tag`: {:?}", result),
+        }
+    }
+}
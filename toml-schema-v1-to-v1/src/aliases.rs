@@ -0,0 +1,129 @@
+use crate::{ Error, Warning };
+
+/// Moves the value of a deprecated `alias` into the field spelled `key`,
+/// warning about it, where both being set is a conflict.
+///
+/// `table` is the dotted path of the table holding both keys.
+fn merge_alias<T>(
+    table: &str,
+    key: &str,
+    alias: &str,
+    value: &mut Option<T>,
+    aliased: &mut Option<T>,
+    warnings: &mut Vec<Warning>,
+) -> Result<(), Error> {
+    let path = |key: &str| if table.is_empty() { key.to_owned() } else { format!("{}.{}", table, key) };
+    match (value.is_some(), aliased.take()) {
+        (true, Some(_)) => Err(Error::ConflictingAlias(path(alias), path(key))),
+        (false, Some(aliased)) => {
+            warnings.push(Warning::DeprecatedAlias(path(alias), key.to_owned()));
+            *value = Some(aliased);
+            Ok(())
+        },
+        (_, None) => Ok(()),
+    }
+}
+
+fn normalize_dependencies(
+    table: &str,
+    deps: &mut Option<schema_v1::DependencyMap>,
+    warnings: &mut Vec<Warning>,
+) -> Result<(), Error> {
+    for (name, dep) in deps.iter_mut().flat_map(|deps| deps.0.iter_mut()) {
+        if let schema_v1::Dependency::Detailed(detail) = dep {
+            merge_alias(
+                &format!("{}.{}", table, name),
+                "default-features",
+                "default_features",
+                &mut detail.default_features,
+                &mut detail.default_features2,
+                warnings,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Normalizes the `dependencies`, `dev-dependencies` and `build-dependencies`
+/// of the root or of a `[target.*]` table, which `prefix` leads to
+fn normalize_dependency_tables(
+    prefix: &str,
+    normal: &mut Option<schema_v1::DependencyMap>,
+    dev: (&mut Option<schema_v1::DependencyMap>, &mut Option<schema_v1::DependencyMap>),
+    build: (&mut Option<schema_v1::DependencyMap>, &mut Option<schema_v1::DependencyMap>),
+    warnings: &mut Vec<Warning>,
+) -> Result<(), Error> {
+    merge_alias(prefix, "dev-dependencies", "dev_dependencies", dev.0, dev.1, warnings)?;
+    merge_alias(prefix, "build-dependencies", "build_dependencies", build.0, build.1, warnings)?;
+    let path = |table: &str| if prefix.is_empty() { table.to_owned() } else { format!("{}.{}", prefix, table) };
+    normalize_dependencies(&path("dependencies"), normal, warnings)?;
+    normalize_dependencies(&path("dev-dependencies"), dev.0, warnings)?;
+    normalize_dependencies(&path("build-dependencies"), build.0, warnings)
+}
+
+fn normalize_target(table: &str, target: &mut schema_v1::Target, warnings: &mut Vec<Warning>) -> Result<(), Error> {
+    merge_alias(table, "crate-type", "crate_type", &mut target.crate_type, &mut target.crate_type2, warnings)?;
+    merge_alias(table, "proc-macro", "proc_macro", &mut target.proc_macro, &mut target.proc_macro2, warnings)
+}
+
+fn normalize_targets(src: &mut schema_v1::Manifest, warnings: &mut Vec<Warning>) -> Result<(), Error> {
+    if let Some(lib) = &mut src.lib {
+        normalize_target("lib", lib, warnings)?;
+    }
+    let tables = vec![
+        ("bin", &mut src.bin),
+        ("example", &mut src.example),
+        ("test", &mut src.test),
+        ("bench", &mut src.bench),
+    ];
+    for (kind, targets) in tables {
+        for (index, target) in targets.iter_mut().flatten().enumerate() {
+            let table = match &target.name {
+                Some(name) => format!("{}.{}", kind, name),
+                None => format!("{}.{}", kind, index),
+            };
+            normalize_target(&table, target, warnings)?;
+        }
+    }
+    Ok(())
+}
+
+/// Replaces cargo's legacy spellings of `src` with the current ones,
+/// warning about each: `[project]` for `[package]`, along with
+/// `dev_dependencies`, `build_dependencies`, `default_features`,
+/// `crate_type` and `proc_macro` for their kebab-case names.
+///
+/// Setting both spellings of a key is an error, as is having both a
+/// `[package]` and a `[project]` table.
+pub fn normalize_aliases(src: &schema_v1::Manifest, warnings: &mut Vec<Warning>) -> Result<schema_v1::Manifest, Error> {
+    let mut src = src.to_owned();
+    match (&src.package, src.project.take()) {
+        (Some(_), Some(_)) => return Err(Error::PackageAndProject),
+        (None, Some(project)) => {
+            warnings.push(Warning::DeprecatedProjectTable);
+            src.package = Some(project);
+        },
+        (_, None) => {},
+    }
+    normalize_dependency_tables(
+        "",
+        &mut src.dependencies,
+        (&mut src.dev_dependencies, &mut src.dev_dependencies2),
+        (&mut src.build_dependencies, &mut src.build_dependencies2),
+        warnings,
+    )?;
+    for (name, platform) in src.target.iter_mut().flatten() {
+        normalize_dependency_tables(
+            &format!("target.{}", name),
+            &mut platform.dependencies,
+            (&mut platform.dev_dependencies, &mut platform.dev_dependencies2),
+            (&mut platform.build_dependencies, &mut platform.build_dependencies2),
+            warnings,
+        )?;
+    }
+    if let Some(workspace) = &mut src.workspace {
+        normalize_dependencies("workspace.dependencies", &mut workspace.dependencies, warnings)?;
+    }
+    normalize_targets(&mut src, warnings)?;
+    Ok(src)
+}
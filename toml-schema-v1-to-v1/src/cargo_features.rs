@@ -0,0 +1,148 @@
+use crate::project::{ dependency_maps };
+use crate::{ Error, Warning };
+
+/// A manifest field along with the `cargo-features` entry it needs
+type Gated = (&'static str, String);
+
+fn package_fields(package: &schema_v1::Project, gated: &mut Vec<Gated>) {
+    if let Some(schema_v1::MaybeWorkspace::Defined(edition)) = &package.edition {
+        gated.push(("edition", "package.edition".to_owned()));
+        match edition {
+            schema_v1::Edition::Edition2021 => gated.push(("edition2021", "package.edition".to_owned())),
+            schema_v1::Edition::Edition2024 => gated.push(("edition2024", "package.edition".to_owned())),
+            schema_v1::Edition::Edition2015 | schema_v1::Edition::Edition2018 => {},
+        }
+    }
+    let fields = [
+        ("rust-version", "package.rust-version", package.rust_version.is_some()),
+        ("resolver", "package.resolver", package.resolver.is_some()),
+        ("default-run", "package.default-run", package.default_run.is_some()),
+        ("metabuild", "package.metabuild", package.metabuild.is_some()),
+        ("per-package-target", "package.forced-target", package.forced_target.is_some()),
+        ("per-package-target", "package.default-target", package.default_target.is_some()),
+    ];
+    gated.extend(fields.iter().filter(|(_, _, set)| *set).map(|(feature, field, _)| (*feature, field.to_string())));
+}
+
+fn profile_fields(src: &schema_v1::Manifest, gated: &mut Vec<Gated>) {
+    let profiles = match &src.profile {
+        Some(profiles) => profiles,
+        None => return,
+    };
+    let named = [
+        ("test", &profiles.test),
+        ("doc", &profiles.doc),
+        ("bench", &profiles.bench),
+        ("dev", &profiles.dev),
+        ("release", &profiles.release),
+    ];
+    let custom = profiles.custom.iter().map(|(name, profile)| (name.as_str(), Some(profile)));
+    for (name, profile) in named.iter().map(|(name, profile)| (*name, profile.as_ref())).chain(custom) {
+        let profile = match profile {
+            Some(profile) => profile,
+            None => continue,
+        };
+        if profiles.custom.contains_key(name) {
+            gated.push(("named-profiles", format!("profile.{}", name)));
+        }
+        if profile.overrides.is_some() {
+            gated.push(("profile-overrides", format!("profile.{}.package", name)));
+        }
+        if profile.build_override.is_some() {
+            gated.push(("profile-overrides", format!("profile.{}.build-override", name)));
+        }
+    }
+}
+
+fn dependency_fields(src: &schema_v1::Manifest, gated: &mut Vec<Gated>) {
+    let workspace = src.workspace.iter()
+        .flat_map(|workspace| &workspace.dependencies)
+        .map(|map| ("workspace.dependencies".to_owned(), map));
+    let declared = dependency_maps(src).into_iter().map(|(kind, platform, map)| {
+        let table = match platform {
+            Some(platform) => format!("target.{}.{}", platform, kind),
+            None => kind.to_string(),
+        };
+        (table, map)
+    });
+    for (table, map) in declared.chain(workspace) {
+        for (name, dep) in map.iter() {
+            let detail = match dep {
+                schema_v1::Dependency::Detailed(detail) => detail,
+                schema_v1::Dependency::Simple(_) => continue,
+            };
+            let fields = [
+                ("bindeps", "artifact", detail.artifact.is_some()),
+                ("rename-dependency", "package", detail.package.is_some()),
+                ("alternative-registries", "registry", detail.registry.is_some()),
+                ("public-dependency", "public", detail.public.is_some()),
+                ("workspace-inheritance", "workspace", detail.workspace.is_some()),
+            ];
+            for (feature, key, _) in fields.iter().filter(|(_, _, set)| *set) {
+                gated.push((feature, format!("{}.{}.{}", table, name, key)));
+            }
+        }
+    }
+}
+
+/// Fields of the manifest which needed a `cargo-features` entry at some
+/// point, with the feature they need
+fn gated_fields(src: &schema_v1::Manifest) -> Vec<Gated> {
+    let mut gated = vec![];
+    if let Some(package) = src.package.as_ref().or(src.project.as_ref()) {
+        package_fields(package, &mut gated);
+    }
+    if let Some(workspace) = &src.workspace {
+        if workspace.resolver.is_some() {
+            gated.push(("resolver", "workspace.resolver".to_owned()));
+        }
+        if workspace.package.is_some() {
+            gated.push(("workspace-inheritance", "workspace.package".to_owned()));
+        }
+        if workspace.lints.is_some() {
+            gated.push(("lints", "workspace.lints".to_owned()));
+        }
+    }
+    if src.lints.is_some() {
+        gated.push(("lints", "lints".to_owned()));
+    }
+    profile_fields(src, &mut gated);
+    dependency_fields(src, &mut gated);
+    gated
+}
+
+/// Checks the manifest's `cargo-features` against the fields it uses as
+/// cargo of the `toolchain` version would, where `None` stands for the
+/// latest one.
+///
+/// A field which needs a feature that is unstable on the toolchain and not
+/// listed fails, while listing an unknown feature or one which has since
+/// been stabilized or removed only warns.
+pub fn check_cargo_features(
+    src: &schema_v1::Manifest,
+    toolchain: Option<v1::RustVersion>,
+    warnings: &mut Vec<Warning>,
+) -> Result<(), Error> {
+    let listed = src.cargo_features.to_owned().unwrap_or_default();
+    for name in &listed {
+        match v1::CargoFeature::find(name) {
+            Some(feature) => match feature.status {
+                v1::FeatureStatus::Stabilized(version) if feature.is_stable_on(toolchain) => {
+                    warnings.push(Warning::StabilizedCargoFeature(name.to_owned(), version))
+                },
+                v1::FeatureStatus::Removed(version) if feature.is_removed_on(toolchain) => {
+                    warnings.push(Warning::RemovedCargoFeature(name.to_owned(), version))
+                },
+                _ => {},
+            },
+            None => warnings.push(Warning::UnknownCargoFeature(name.to_owned())),
+        }
+    }
+    for (feature, field) in gated_fields(src) {
+        let stable = v1::CargoFeature::find(feature).is_some_and(|f| f.is_stable_on(toolchain));
+        if !stable && !listed.iter().any(|name| name == feature) {
+            return Err(Error::CargoFeatureRequired(feature.to_owned(), field));
+        }
+    }
+    Ok(())
+}
@@ -1,15 +1,15 @@
 use semver::VersionReq;
 use std::fmt;
 
-pub const GIT_KEY_BRANCH: &'static str = "branch";
-pub const GIT_KEY_TAG: &'static str = "tag";
-pub const GIT_KEY_REV: &'static str = "rev";
+pub const GIT_KEY_BRANCH: &str = "branch";
+pub const GIT_KEY_TAG: &str = "tag";
+pub const GIT_KEY_REV: &str = "rev";
 
 #[derive(Debug, PartialEq)]
 pub enum Warning {
     GitKeysIgnored(Vec<String>),
     IgnoredMetadata(String),
-    NoValidSources(schema_v1::DetailedDependency),
+    NoValidSources(Box<schema_v1::DetailedDependency>),
     RegistryIgnored(String),
 }
 
@@ -31,15 +31,17 @@ pub enum Constraint {
     OneOfGitOrRegistry,
     OneOfGitOrPath,
     OneOfBranchTagOrRev,
+    LibOrTargetWithoutArtifact,
 }
 
 impl fmt::Display for Constraint{
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", match self {
-            Constraint::DependencyNameIsRequired => "Dependeny name is required",
+            Constraint::DependencyNameIsRequired => "Dependency name is required",
             Constraint::OneOfGitOrRegistry => "Only one of 'git' or 'registry' is allowed.",
             Constraint::OneOfGitOrPath => "Only one of 'git' or 'path' is allowed.",
             Constraint::OneOfBranchTagOrRev => "Only one of 'branch', 'tag' or 'rev' allowed.",
+            Constraint::LibOrTargetWithoutArtifact => "'lib' and 'target' are only allowed along with 'artifact'.",
         })
     }
 }
@@ -47,6 +49,7 @@ impl fmt::Display for Constraint{
 #[derive(Debug, PartialEq)]
 pub enum Error {
     Constraint(Constraint),
+    UnresolvedWorkspace,
     V1(v1::Error),
     VersionReq(semver::ReqParseError),
 }
@@ -55,6 +58,9 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::Constraint(c) => write!(f, "Constraint violated: {}", c),
+            Error::UnresolvedWorkspace => {
+                write!(f, "inherits from `[workspace.dependencies]`, which must be resolved before conversion")
+            },
             Error::V1(err) => err.fmt(f),
             Error::VersionReq(err) => write!(f, "Version error: {}", err),
         }
@@ -70,9 +76,12 @@ impl From<v1::Error> for Error {
 pub type Result<T> = std::result::Result<(T, Option<Vec<Warning>>), Error>;
 
 fn convert_detailed_dependency(src: schema_v1::DetailedDependency) -> Result<v1::Dependency> {
+    if src.workspace == Some(true) {
+        return Err(Error::UnresolvedWorkspace);
+    }
     let mut warnings = vec![];
     if src.version.is_none() && src.path.is_none() && src.git.is_none() {
-        warnings.push(Warning::NoValidSources(src.to_owned()));
+        warnings.push(Warning::NoValidSources(Box::new(src.to_owned())));
     }
     if let Some(v) = &src.version {
         if v.contains('+') {
@@ -84,8 +93,8 @@ fn convert_detailed_dependency(src: schema_v1::DetailedDependency) -> Result<v1:
             src.branch.as_ref().map(|_| GIT_KEY_BRANCH),
             src.tag.as_ref().map(|_| GIT_KEY_TAG),
             src.rev.as_ref().map(|_| GIT_KEY_REV)
-        ].into_iter().filter_map(|k| k.map(|v|v.to_owned())).collect();
-        if keys.len() > 0 {
+        ].iter().filter_map(|k| k.map(|v|v.to_owned())).collect();
+        if !keys.is_empty() {
             warnings.push(Warning::GitKeysIgnored(keys))
         }
     }
@@ -134,28 +143,16 @@ fn convert_detailed_dependency(src: schema_v1::DetailedDependency) -> Result<v1:
             .map(v1::Dependency::Git)
         },
     }.map(|d| {
-        (d, if warnings.len() == 0 { None } else { Some(warnings) })
+        (d, if warnings.is_empty() { None } else { Some(warnings) })
     })
 }
 
-fn validate_dependency((d, w): (v1::Dependency, Option<Vec<Warning>>)) -> Result<v1::Dependency> {
-    // let mut warnings = w.unwrap_or_default();
-    let warnings = w.unwrap_or_default();
-    // match d {
-    //     v1::Dependency::Git(_) => {}
-    //     v1::Dependency::LocalPath(path) | v1::Dependency::Directory(path) => {
-
-    //     },
-    //     v1::Dependency::CustomRegistry(v) => {
-
-    //     },
-    //     v1::Dependency::
-    // }
-    // if d.version.len() == 0 {
-    //     return Err(Error::Constraint(Constraint::DependencyNameIsRequired))
-    // }
-    let warnings = if warnings.len() == 0 { None } else { Some(warnings) };
-    Ok((d, warnings))
+fn validate_dependency_name(name: &str) -> std::result::Result<(), Error> {
+    match v1::validate_name(name) {
+        Ok(()) => Ok(()),
+        Err(v1::NameError::Empty) => Err(Error::Constraint(Constraint::DependencyNameIsRequired)),
+        Err(err) => Err(Error::V1(v1::Error::InvalidDependencyName(name.to_owned(), err))),
+    }
 }
 
 pub fn convert_dependency(src: schema_v1::Dependency) -> Result<v1::Dependency> {
@@ -166,12 +163,74 @@ pub fn convert_dependency(src: schema_v1::Dependency) -> Result<v1::Dependency>
                 .map(|v| (v, None))
                 .map_err(Error::VersionReq)
         },
-        schema_v1::Dependency::Detailed(details) => convert_detailed_dependency(details),
+        schema_v1::Dependency::Detailed(details) => convert_detailed_dependency(*details),
+    }
+}
+
+/// Converts the dependency declared under the key `name`, validating both
+/// the key and any `package` rename against cargo's naming rules
+pub fn convert_named_dependency(name: &str, src: schema_v1::Dependency) -> Result<v1::Dependency> {
+    validate_dependency_name(name)?;
+    if let schema_v1::Dependency::Detailed(details) = &src {
+        if let Some(package) = &details.package {
+            validate_dependency_name(package)?;
+        }
     }
-    .and_then(validate_dependency)
+    convert_dependency(src)
+}
+
+/// Converts the `artifact`, `lib` and `target` keys of an artifact
+/// dependency, which the latter two are only allowed with
+fn convert_artifact(details: &schema_v1::DetailedDependency) -> std::result::Result<Option<v1::Artifact>, Error> {
+    let kinds = match &details.artifact {
+        Some(kinds) => kinds.0.iter().map(|kind| v1::ArtifactKind::parse(kind)).collect::<v1::Result<Vec<_>>>()?,
+        None if details.lib.is_some() || details.target.is_some() => {
+            return Err(Error::Constraint(Constraint::LibOrTargetWithoutArtifact));
+        },
+        None => return Ok(None),
+    };
+    Ok(Some(v1::Artifact {
+        kinds,
+        lib: details.lib.unwrap_or(false),
+        target: details.target.to_owned(),
+    }))
+}
+
+/// Converts a dependency declared under the key `name` in a `kind` table,
+/// keeping the declared `version` alongside the source it resolves from
+pub fn convert_declared_dependency(
+    name: &str,
+    kind: v1::DependencyKind,
+    platform: Option<&str>,
+    src: schema_v1::Dependency,
+) -> Result<v1::DeclaredDependency> {
+    let (version, details) = match &src {
+        schema_v1::Dependency::Simple(version) => (Some(version.to_owned()), Default::default()),
+        schema_v1::Dependency::Detailed(details) => (details.version.to_owned(), details.to_owned()),
+    };
+    let (source, warnings) = convert_named_dependency(name, src)?;
+    let artifact = convert_artifact(&details)?;
+    let version = match version {
+        Some(version) => Some(VersionReq::parse(&version).map_err(Error::VersionReq)?),
+        None => None,
+    };
+    let dependency = v1::DeclaredDependency {
+        name: name.to_owned(),
+        package: details.package,
+        kind,
+        platform: platform.map(str::to_owned),
+        source,
+        version,
+        optional: details.optional.unwrap_or(false),
+        default_features: details.default_features.unwrap_or(true),
+        features: details.features.unwrap_or_default(),
+        artifact,
+    };
+    Ok((dependency, warnings))
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
 mod tests {
     use std::path::PathBuf;
     use std::str::FromStr;
@@ -180,7 +239,7 @@ mod tests {
     use schema_v1;
     use semver::VersionReq;
 
-    use super::{ convert_dependency, Error, Constraint, Warning };
+    use super::{ convert_declared_dependency, convert_dependency, convert_named_dependency, Error, Constraint, Warning };
 
     #[test]
     fn convert_named_dependency_without_warnings() {
@@ -217,9 +276,9 @@ mod tests {
     #[test]
     fn warn_for_no_valid_sources() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok (result) => {
@@ -242,10 +301,10 @@ mod tests {
     #[test]
     fn warn_on_semver_in_version() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 version: Some("1.0.0+foo".to_owned()),
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok ((d, Some(w))) => {
@@ -267,11 +326,11 @@ mod tests {
         let expected_keys = ["branch".to_owned(), "rev".to_owned()];
 
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 branch: Some("foo".to_owned()),
                 rev: Some("baz".to_owned()),
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok ((d, Some(w))) => {
@@ -298,9 +357,9 @@ mod tests {
     #[test]
     fn default_detailed_dependency_default_registry_version_to_major_wildcard() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok (result) => {
@@ -321,10 +380,10 @@ mod tests {
     #[test]
     fn detailed_dependency_default_registry_with_version_parsed() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 version: Some("^3.1".to_owned()),
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok (result) => {
@@ -344,11 +403,11 @@ mod tests {
     #[test]
     fn detailed_dependency_local_path_with_registry_ignored() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 registry: Some("ignored".to_owned()),
                 path: Some(".".to_owned()),
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok (result) => {
@@ -374,10 +433,10 @@ mod tests {
     #[test]
     fn detailed_dependency_local_path_without_registry_ignored() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 path: Some(".".to_owned()),
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok (result) => {
@@ -393,10 +452,10 @@ mod tests {
     #[test]
     fn detailed_dependency_registry() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 registry: Some("foo".to_owned()),
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok (result) => {
@@ -414,11 +473,11 @@ mod tests {
     #[test]
     fn not_allow_git_and_registry() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 git: Some("bar".to_owned()),
                 registry: Some("foo".to_owned()),
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok (_) => assert!(false, "should have failed due to constraint"),
@@ -430,11 +489,11 @@ mod tests {
     #[test]
     fn not_allow_git_and_path() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 git: Some("bar".to_owned()),
                 path: Some(".".to_owned()),
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok (_) => assert!(false, "should have failed due to constraint"),
@@ -446,12 +505,12 @@ mod tests {
     #[test]
     fn not_allow_git_with_conflicting_keys() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 git: Some("http://foo".to_owned()),
                 branch: Some("bar".to_owned()),
                 tag: Some("baz".to_owned()),
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok (_) => assert!(false, "should have failed due to constraint"),
@@ -463,10 +522,10 @@ mod tests {
     #[test]
     fn detailed_dependency_git() {
         let dep = schema_v1::Dependency::Detailed (
-            schema_v1::DetailedDependency {
+            Box::new(schema_v1::DetailedDependency {
                 git: Some("http://foo".to_owned()),
                 .. Default::default()
-            }
+            })
         );
         match convert_dependency(dep) {
             Ok (result) => {
@@ -482,4 +541,165 @@ mod tests {
             Err (err) => assert!(false, "unexpected error: {}", err),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn convert_named_dependency_with_valid_key() {
+        let dep = schema_v1::Dependency::Simple("1.0.0".to_owned());
+        match convert_named_dependency("foo-bar", dep) {
+            Ok ((d, None)) => assert_eq!(v1::Dependency::DefaultRegistry(VersionReq::parse("1.0.0").unwrap()), d),
+            Ok ((_, Some(w))) => assert!(false, "should not have had warnings but was: {:?}", w),
+            Err (err) => assert!(false, "unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn not_allow_empty_dependency_name() {
+        let dep = schema_v1::Dependency::Simple("1.0.0".to_owned());
+        match convert_named_dependency("", dep) {
+            Ok (_) => assert!(false, "should have failed due to constraint"),
+            Err (Error::Constraint(Constraint::DependencyNameIsRequired)) => {},
+            Err (err) => assert!(false, "expected DependencyNameIsRequired: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn not_allow_invalid_dependency_key() {
+        let dep = schema_v1::Dependency::Simple("1.0.0".to_owned());
+        match convert_named_dependency("1foo", dep) {
+            Ok (_) => assert!(false, "should not allow a dependency key starting with a digit"),
+            Err (Error::V1(v1::Error::InvalidDependencyName(name, v1::NameError::StartsWithDigit(_)))) => {
+                assert_eq!("1foo", name);
+            },
+            Err (err) => assert!(false, "expected InvalidDependencyName: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn not_allow_invalid_package_rename() {
+        let dep = schema_v1::Dependency::Detailed (
+            Box::new(schema_v1::DetailedDependency {
+                version: Some("1.0.0".to_owned()),
+                package: Some("foo.bar".to_owned()),
+                .. Default::default()
+            })
+        );
+        match convert_named_dependency("foo", dep) {
+            Ok (_) => assert!(false, "should not allow a package rename with invalid characters"),
+            Err (Error::V1(v1::Error::InvalidDependencyName(name, v1::NameError::InvalidCharacter('.')))) => {
+                assert_eq!("foo.bar", name);
+            },
+            Err (err) => assert!(false, "expected InvalidDependencyName: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn not_allow_empty_package_rename() {
+        let dep = schema_v1::Dependency::Detailed (
+            Box::new(schema_v1::DetailedDependency {
+                version: Some("1.0.0".to_owned()),
+                package: Some("".to_owned()),
+                .. Default::default()
+            })
+        );
+        match convert_named_dependency("foo", dep) {
+            Ok (_) => assert!(false, "should have failed due to constraint"),
+            Err (Error::Constraint(Constraint::DependencyNameIsRequired)) => {},
+            Err (err) => assert!(false, "expected DependencyNameIsRequired: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_declared_path_dependency_keeps_version() {
+        let dep = schema_v1::Dependency::Detailed (
+            Box::new(schema_v1::DetailedDependency {
+                version: Some("1.2".to_owned()),
+                path: Some("../bar".to_owned()),
+                package: Some("bar-core".to_owned()),
+                optional: Some(true),
+                default_features: Some(false),
+                features: Some(vec!["std".to_owned()]),
+                .. Default::default()
+            })
+        );
+        match convert_declared_dependency("bar", v1::DependencyKind::Build, Some("cfg(unix)"), dep) {
+            Ok ((d, None)) => {
+                assert_eq!(v1::Dependency::LocalPath(PathBuf::from("../bar")), d.source);
+                assert_eq!(Some(VersionReq::parse("1.2").unwrap()), d.version);
+                assert_eq!("bar-core", d.package_name());
+                assert_eq!(v1::DependencyKind::Build, d.kind);
+                assert_eq!(Some("cfg(unix)".to_owned()), d.platform);
+                assert!(d.optional);
+                assert!(!d.default_features);
+                assert_eq!(vec!["std".to_owned()], d.features);
+            },
+            Ok ((_, Some(w))) => assert!(false, "should not have had warnings but was: {:?}", w),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_declared_dependency_without_version() {
+        let dep = schema_v1::Dependency::Detailed (
+            Box::new(schema_v1::DetailedDependency {
+                path: Some("../bar".to_owned()),
+                .. Default::default()
+            })
+        );
+        match convert_declared_dependency("bar", v1::DependencyKind::Normal, None, dep) {
+            Ok ((d, _)) => {
+                assert_eq!(None, d.version);
+                assert!(d.default_features);
+            },
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_declared_artifact_dependency() {
+        let dep = schema_v1::Dependency::Detailed (
+            Box::new(schema_v1::DetailedDependency {
+                path: Some("../tool".to_owned()),
+                artifact: Some(schema_v1::string_or_vec::StringOrVec(vec!["bin:tool".to_owned(), "cdylib".to_owned()])),
+                lib: Some(true),
+                target: Some("wasm32-unknown-unknown".to_owned()),
+                .. Default::default()
+            })
+        );
+        match convert_declared_dependency("tool", v1::DependencyKind::Build, None, dep) {
+            Ok ((d, _)) => assert_eq!(Some(v1::Artifact {
+                kinds: vec![v1::ArtifactKind::Binary("tool".to_owned()), v1::ArtifactKind::Cdylib],
+                lib: true,
+                target: Some("wasm32-unknown-unknown".to_owned()),
+            }), d.artifact),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_convert_invalid_artifact_dependency() {
+        let dep = schema_v1::Dependency::Detailed (
+            Box::new(schema_v1::DetailedDependency {
+                path: Some("../tool".to_owned()),
+                artifact: Some(schema_v1::string_or_vec::StringOrVec(vec!["dylib".to_owned()])),
+                .. Default::default()
+            })
+        );
+        match convert_declared_dependency("tool", v1::DependencyKind::Normal, None, dep) {
+            Ok (_) => assert!(false, "should have failed on the artifact kind"),
+            Err (err) => assert_eq!(Error::V1(v1::Error::InvalidArtifactKind("dylib".to_owned())), err),
+        }
+
+        let dep = schema_v1::Dependency::Detailed (
+            Box::new(schema_v1::DetailedDependency {
+                path: Some("../tool".to_owned()),
+                lib: Some(true),
+                .. Default::default()
+            })
+        );
+        match convert_declared_dependency("tool", v1::DependencyKind::Normal, None, dep) {
+            Ok (_) => assert!(false, "should have failed due to constraint"),
+            Err (Error::Constraint(Constraint::LibOrTargetWithoutArtifact)) => {},
+            Err (err) => assert!(false, "expected LibOrTargetWithoutArtifact: {:?}", err),
+        }
+    }
+}
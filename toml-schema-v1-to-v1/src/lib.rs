@@ -1,13 +1,19 @@
 // extern crate failure;
 extern crate semver;
+extern crate url;
 
 extern crate omni_manifest_v1 as v1;
 extern crate omni_manifest_toml_schema_v1 as schema_v1;
 
 use std::fmt;
 
+pub mod aliases;
+pub mod cargo_features;
 pub mod dependency;
+pub mod lints;
 pub mod profile;
+pub mod project;
+pub mod targets;
 
 // use dependency::{ convert_dependency };
 // use profile::{ convert_profile };
@@ -18,65 +24,1020 @@ pub type ConvertResult<T, W, E> = std::result::Result<(T, OptionVec<W>), E>;
 
 #[derive(Debug, PartialEq)]
 pub enum Warning {
-    None,
+    Dependency(String, Box<dependency::Warning>),
+    /// A legacy underscore key, with its path and the key replacing it
+    DeprecatedAlias(String, String),
+    /// `[project]` used instead of `[package]`
+    DeprecatedProjectTable,
+    /// Targets sharing a name, with where each is declared
+    DuplicateTargetName(String, Vec<String>),
+    /// A URL field which is not a valid URL and is dropped, with the field
+    /// and its value
+    InvalidUrl(String, String),
+    NoEditionSet(v1::Edition, v1::Edition),
+    /// A `proc-macro` library which also sets other `crate-type`s
+    ProcMacroCrateTypes(String, Vec<String>),
+    /// A listed `cargo-features` entry which the toolchain has removed
+    RemovedCargoFeature(String, v1::RustVersion),
+    RestrictedPackageName(String, v1::RestrictedName),
+    /// A listed `cargo-features` entry which the toolchain has stabilized
+    StabilizedCargoFeature(String, v1::RustVersion),
+    UnknownCargoFeature(String),
+    /// A `[lints.<tool>]` table of a tool cargo does not know of
+    UnknownLintTool(String),
 }
 
 impl fmt::Display for Warning {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Warning::None => write!(f, "none"),
+            Warning::Dependency(name, w) => write!(f, "dependency `{}`: {}", name, w),
+            Warning::DeprecatedAlias(alias, key) => {
+                write!(f, "`{}` is deprecated in favor of `{}` and will not work in the 2024 edition", alias, key)
+            },
+            Warning::DeprecatedProjectTable => {
+                write!(f, "the `[project]` section is deprecated in favor of `[package]`")
+            },
+            Warning::DuplicateTargetName(name, locations) => {
+                write!(f, "the target name `{}` is used by more than one target: {}", name, locations.join(", "))
+            },
+            Warning::InvalidUrl(field, url) => write!(f, "`{}` is not a valid URL and is ignored: `{}`", field, url),
+            Warning::NoEditionSet(default, latest) => {
+                write!(f, "no edition set: defaulting to the {} edition while the latest is {}", default, latest)
+            },
+            Warning::ProcMacroCrateTypes(name, crate_types) => write!(
+                f,
+                "library `{}` should only specify `proc-macro = true` instead of also setting `crate-type` to {}",
+                name, crate_types.join(", ")
+            ),
+            Warning::RemovedCargoFeature(name, version) => {
+                write!(f, "the cargo feature `{}` was removed in {} and has no effect", name, version)
+            },
+            Warning::RestrictedPackageName(name, reason) => {
+                write!(f, "the name `{}` should not be used as a package name, {}", name, reason)
+            },
+            Warning::StabilizedCargoFeature(name, version) => {
+                write!(f, "the cargo feature `{}` was stabilized in {} and no longer needs to be listed", name, version)
+            },
+            Warning::UnknownCargoFeature(name) => write!(f, "unknown cargo feature `{}`", name),
+            Warning::UnknownLintTool(table) => {
+                write!(f, "unrecognized lint tool `{}`, specifying unrecognized tools may break in the future", table)
+            },
         }
     }
 }
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
-    None (schema_v1::Manifest),
+    /// A field used without listing the unstable feature it needs, with the
+    /// feature and the field
+    CargoFeatureRequired(String, String),
+    /// A legacy underscore key set along with its kebab-case spelling, with
+    /// the paths of both
+    ConflictingAlias(String, String),
+    Dependency(String, dependency::Error),
+    MissingTargetName(v1::TargetKind),
+    None (Box<schema_v1::Manifest>),
+    /// Both `[package]` and `[project]` are set
+    PackageAndProject,
+    Profile(String, profile::Error),
+    RustVersionOlderThanEdition(v1::RustVersion, v1::Edition),
+    /// A `required-features` entry naming no feature or dependency, with
+    /// where the target is declared, its name and the entry
+    UnknownRequiredFeature(String, String, String),
+    UnresolvedWorkspaceField(String),
+    V1(v1::Error),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Error::CargoFeatureRequired(feature, field) => write!(
+                f,
+                "feature `{}` is required to use `{}`, consider adding `cargo-features = [\"{}\"]` to the manifest",
+                feature, field, feature
+            ),
+            Error::ConflictingAlias(alias, key) => {
+                write!(f, "`{}` is redundant with `{}`, only one of them may be set", alias, key)
+            },
+            Error::Dependency(name, err) => write!(f, "dependency `{}`: {}", name, err),
+            Error::MissingTargetName(kind) => write!(f, "a `[[{}]]` target requires a `name` or `path`", kind),
             Error::None (manifest) => write!(f, "None: {:?}", manifest),
+            Error::PackageAndProject => write!(f, "only one of `[package]` and `[project]` may be set"),
+            Error::Profile(name, err) => write!(f, "profile `{}`: {}", name, err),
+            Error::RustVersionOlderThanEdition(rust_version, edition) => write!(
+                f,
+                "rust-version {} is older than first version ({}) required by the specified edition ({})",
+                rust_version, edition.first_version(), edition
+            ),
+            Error::UnknownRequiredFeature(location, target, feature) => write!(
+                f,
+                "target `{}` ({}) requires the feature `{}`, which is neither a feature of the package nor a `dep/feature` of one of its dependencies",
+                target, location, feature
+            ),
+            Error::UnresolvedWorkspaceField(field) => write!(
+                f,
+                "`{}` inherits from the workspace, which must be resolved before conversion",
+                field
+            ),
+            Error::V1(err) => err.fmt(f),
         }
     }
 }
 
+impl From<v1::Error> for Error {
+    fn from(err: v1::Error) -> Error {
+        Error::V1(err)
+    }
+}
+
 pub type Result<T> = ConvertResult<T, Warning, Error>;
 
 pub fn convert(src: &schema_v1::Manifest) -> Result<v1::Manifest> {
-    if let Some(workspace) = &src.workspace {
+    convert_for_toolchain(src, None)
+}
+
+/// Converts the manifest as cargo of the `toolchain` version would read
+/// it, which decides the `cargo-features` its fields need, where `None`
+/// stands for the latest one
+pub fn convert_for_toolchain(src: &schema_v1::Manifest, toolchain: Option<v1::RustVersion>) -> Result<v1::Manifest> {
+    let mut warnings = vec![];
+    let src = &aliases::normalize_aliases(src, &mut warnings)?;
+    cargo_features::check_cargo_features(src, toolchain, &mut warnings)?;
+    let project = match &src.package {
+        Some(package) => {
+            Some(project::convert_project(src, package, &mut warnings)?)
+        },
+        None => None,
+    };
+    let workspace = src.workspace.as_ref().map(|workspace| {
         // TODO: validate property values
-        let workspace = v1::Workspace {
+        v1::Workspace {
             members: workspace.members.clone(),
             default_members: workspace.default_members.clone(),
             exclude: workspace.exclude.clone(),
-        };
-        return Ok((v1::Manifest::Workspace(workspace), None))
+            resolver: workspace.resolver.map(project::convert_resolver),
+            lints: workspace.lints.as_ref()
+                .map(|src| lints::convert_lints(src, "workspace.lints", &mut warnings))
+                .unwrap_or_default(),
+        }
+    });
+    let warnings = if warnings.is_empty() { None } else { Some(warnings) };
+
+    if let Some(workspace) = workspace {
+        return Ok((v1::Manifest::Workspace(workspace), warnings))
+    }
+    if let Some(project) = project {
+        return Ok((v1::Manifest::Project(project), warnings))
     }
 
-    // check for workspace in schema and return that enum if found...
-    // let src_copy: schema_v1::Manifest = src.clone();
-    Err ( Error::None(src.to_owned()) )
+    Err ( Error::None(Box::new(src.to_owned())) )
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
 mod tests {
+    extern crate toml;
+
     // use v1;
     use schema_v1;
 
-    use super::{ convert, Error, Warning };
+    use super::{ convert, convert_for_toolchain, profile, Error, Warning };
 
     #[test]
     fn convert_workspace() {
         let man = schema_v1::Manifest {
+            workspace: Some(schema_v1::Workspace {
+                members: Some(vec!["foo".to_owned()]),
+                .. Default::default()
+            }),
             .. Default::default()
         };
         match convert(&man) {
             Err (err) => assert!(false, "unepxected error: {:?}", err),
-            Ok ((v1::Manifest::Project, _)) => assert!(false, "should have been a workspace enum"),
+            Ok ((v1::Manifest::Project(_), _)) => assert!(false, "should have been a workspace enum"),
             Ok ((v1::Manifest::Workspace(workspace), _)) => {
-                    assert!(false, "foo: {:?}", workspace);
+                assert_eq!(Some(vec!["foo".to_owned()]), workspace.members);
+            }
+        }
+    }
+
+    fn manifest_with_package(package: &str) -> schema_v1::Manifest {
+        schema_v1::Manifest {
+            package: Some(toml::from_str(package).unwrap()),
+            .. Default::default()
+        }
+    }
+
+    fn package_manifest(name: &str) -> schema_v1::Manifest {
+        manifest_with_package(&format!(r#"
+            name = "{}"
+            version = "1.0.0"
+            edition = "2021"
+        "#, name))
+    }
+
+    #[test]
+    fn convert_valid_package() {
+        match convert(&package_manifest("foo")) {
+            Ok ((v1::Manifest::Project(project), None)) => {
+                assert_eq!("foo", project.name);
+                assert_eq!(v1::Edition::Edition2021, project.edition);
+                assert_eq!(None, project.rust_version);
+            },
+            Ok ((v1::Manifest::Project(_), Some(w))) => assert!(false, "should not have had warnings but was: {:?}", w),
+            Ok (result) => assert!(false, "should have been a project enum: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_convert_invalid_package_name() {
+        match convert(&package_manifest("foo bar")) {
+            Ok (_) => assert!(false, "should not convert a package name containing a space"),
+            Err (Error::V1(v1::Error::InvalidPackageName(name, v1::NameError::InvalidCharacter(' ')))) => {
+                assert_eq!("foo bar", name);
+            },
+            Err (err) => assert!(false, "expected InvalidPackageName: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_convert_empty_package_name() {
+        match convert(&package_manifest("")) {
+            Ok (_) => assert!(false, "should not convert an empty package name"),
+            Err (Error::V1(v1::Error::InvalidPackageName(_, v1::NameError::Empty))) => {},
+            Err (err) => assert!(false, "expected InvalidPackageName: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn warn_on_restricted_package_name() {
+        match convert(&package_manifest("core")) {
+            Ok ((_, Some(w))) => {
+                assert_eq!(vec![Warning::RestrictedPackageName("core".to_owned(), v1::RestrictedName::StandardLibrary)], w);
+            },
+            Ok ((_, None)) => assert!(false, "should have warned about a standard library name"),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_convert_invalid_target_dependency_key() {
+        let mut manifest = package_manifest("foo");
+        manifest.target = Some(toml::from_str(r#"
+            [unix.dependencies]
+            "bar!" = "1.0"
+        "#).unwrap());
+        match convert(&manifest) {
+            Ok (_) => assert!(false, "should not convert an invalid dependency key"),
+            Err (Error::Dependency(name, _)) => assert_eq!("bar!", name),
+            Err (err) => assert!(false, "expected Dependency error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn default_resolver_from_edition() {
+        for (edition, resolver) in &[
+            ("2018", v1::Resolver::V1),
+            ("2021", v1::Resolver::V2),
+            ("2024", v1::Resolver::V3),
+        ] {
+            let manifest = manifest_with_package(&format!(r#"
+                name = "foo"
+                version = "1.0.0"
+                edition = "{}"
+            "#, edition));
+            match convert(&manifest) {
+                Ok ((v1::Manifest::Project(project), _)) => assert_eq!(*resolver, project.resolver),
+                Ok (result) => assert!(false, "should have been a project enum: {:?}", result),
+                Err (err) => assert!(false, "unexpected error: {:?}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn convert_explicit_resolver_and_rust_version() {
+        let manifest = manifest_with_package(r#"
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+            rust-version = "1.70"
+            resolver = "1"
+        "#);
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), _)) => {
+                assert_eq!(v1::Resolver::V1, project.resolver);
+                assert_eq!(Some(v1::RustVersion { major: 1, minor: Some(70), patch: None }), project.rust_version);
+            },
+            Ok (result) => assert!(false, "should have been a project enum: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn warn_when_no_edition_set() {
+        let manifest = manifest_with_package(r#"
+            name = "foo"
+            version = "1.0.0"
+        "#);
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), Some(w))) => {
+                assert_eq!(v1::Edition::Edition2015, project.edition);
+                assert_eq!(vec![Warning::NoEditionSet(v1::Edition::Edition2015, v1::Edition::Edition2024)], w);
+            },
+            Ok (result) => assert!(false, "should have warned that no edition was set: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_convert_rust_version_older_than_edition() {
+        let manifest = manifest_with_package(r#"
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+            rust-version = "1.55"
+        "#);
+        match convert(&manifest) {
+            Ok (_) => assert!(false, "edition 2021 should require at least rust 1.56"),
+            Err (Error::RustVersionOlderThanEdition(_, v1::Edition::Edition2021)) => {},
+            Err (err) => assert!(false, "expected RustVersionOlderThanEdition: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_convert_rust_version_older_than_target_edition() {
+        let mut manifest = manifest_with_package(r#"
+            name = "foo"
+            version = "1.0.0"
+            edition = "2018"
+            rust-version = "1.56"
+        "#);
+        manifest.bin = Some(vec![schema_v1::Target {
+            name: Some("foo".to_owned()),
+            edition: Some(schema_v1::Edition::Edition2024),
+            .. Default::default()
+        }]);
+        match convert(&manifest) {
+            Ok (_) => assert!(false, "edition 2024 target should require at least rust 1.85"),
+            Err (Error::RustVersionOlderThanEdition(_, v1::Edition::Edition2024)) => {},
+            Err (err) => assert!(false, "expected RustVersionOlderThanEdition: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_workspace_resolver() {
+        let man = schema_v1::Manifest {
+            workspace: Some(schema_v1::Workspace {
+                resolver: Some(schema_v1::Resolver::V2),
+                .. Default::default()
+            }),
+            .. Default::default()
+        };
+        match convert(&man) {
+            Ok ((v1::Manifest::Workspace(workspace), _)) => assert_eq!(Some(v1::Resolver::V2), workspace.resolver),
+            Ok (result) => assert!(false, "should have been a workspace enum: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_package_metadata_and_publish() {
+        let manifest = manifest_with_package(r#"
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+            description = "A foo"
+            license = "MIT"
+            keywords = ["foo"]
+            repository = "https://github.com/foo/foo"
+            publish = ["internal"]
+        "#);
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), _)) => {
+                assert_eq!(Some("A foo".to_owned()), project.metadata.description);
+                assert_eq!(vec!["foo".to_owned()], project.metadata.keywords);
+                assert_eq!(
+                    Some("https://github.com/foo/foo"),
+                    project.metadata.repository.as_ref().map(|url| url.as_str())
+                );
+                assert_eq!(Some(vec!["internal".to_owned()]), project.publish);
+            },
+            Ok (result) => assert!(false, "should have been a project enum: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_publish_false_to_no_registries() {
+        let manifest = manifest_with_package(r#"
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+            publish = false
+        "#);
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), _)) => assert_eq!(Some(vec![]), project.publish),
+            Ok (result) => assert!(false, "should have been a project enum: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_build_script() {
+        let cases = vec![
+            ("", v1::BuildScript::Inferred),
+            ("build = \"tools/gen.rs\"", v1::BuildScript::Path("tools/gen.rs".into())),
+            ("build = true", v1::BuildScript::Path("build.rs".into())),
+            ("build = false", v1::BuildScript::Disabled),
+        ];
+        for (build, expected) in cases {
+            let manifest = manifest_with_package(&format!(r#"
+                name = "foo-sys"
+                version = "1.0.0"
+                edition = "2021"
+                links = "foo"
+                {}
+            "#, build));
+            match convert(&manifest) {
+                Ok ((v1::Manifest::Project(project), _)) => {
+                    assert_eq!(expected, project.build, "{}", build);
+                    assert_eq!(Some("foo".to_owned()), project.metadata.links);
+                },
+                Ok (result) => assert!(false, "should have been a project enum: {:?}", result),
+                Err (err) => assert!(false, "unexpected error: {:?}", err),
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn drop_invalid_urls_with_warnings() {
+        let manifest = manifest_with_package(r#"
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+            homepage = "not a url"
+            repository = "https://github.com/example/foo"
+            documentation = "docs"
+        "#);
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), Some(warnings))) => {
+                assert_eq!(None, project.metadata.homepage);
+                assert_eq!(Some("https://github.com/example/foo"), project.metadata.repository.as_ref().map(|u| u.as_str()));
+                assert_eq!(None, project.metadata.documentation);
+                assert_eq!(vec![
+                    Warning::InvalidUrl("homepage".to_owned(), "not a url".to_owned()),
+                    Warning::InvalidUrl("documentation".to_owned(), "docs".to_owned()),
+                ], warnings);
+            },
+            Ok (result) => assert!(false, "should have been a project with warnings: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_declared_dependencies() {
+        let mut manifest = package_manifest("foo");
+        manifest.dependencies = Some(schema_v1::DependencyMap(
+            toml::from_str(r#"bar = { path = "../bar", version = "1.0" }"#).unwrap()
+        ));
+        manifest.dev_dependencies = Some(schema_v1::DependencyMap(toml::from_str(r#"baz = "0.2""#).unwrap()));
+        manifest.target = Some(toml::from_str(r#"
+            [unix.build-dependencies]
+            qux = "3"
+        "#).unwrap());
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), _)) => {
+                let declared: Vec<_> = project.dependencies.iter()
+                    .map(|d| (d.name.as_str(), d.kind, d.platform.as_deref()))
+                    .collect();
+                assert_eq!(vec![
+                    ("bar", v1::DependencyKind::Normal, None),
+                    ("baz", v1::DependencyKind::Development, None),
+                    ("qux", v1::DependencyKind::Build, Some("unix")),
+                ], declared);
+                assert!(project.dependencies[0].version.is_some());
+            },
+            Ok (result) => assert!(false, "should have been a project enum: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_convert_unresolved_workspace_field() {
+        let manifest = manifest_with_package(r#"
+            name = "foo"
+            version.workspace = true
+        "#);
+        match convert(&manifest) {
+            Ok (_) => assert!(false, "should not convert a field inherited from the workspace"),
+            Err (Error::UnresolvedWorkspaceField(field)) => assert_eq!("version", field),
+            Err (err) => assert!(false, "expected UnresolvedWorkspaceField: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_features_profiles_and_targets() {
+        let manifest: schema_v1::Manifest = toml::from_str(r#"
+            [package]
+            name = "foo-bar"
+            version = "1.0.0"
+            edition = "2021"
+
+            [features]
+            default = ["std"]
+            std = []
+
+            [profile.release]
+            lto = "thin"
+            codegen-units = 1
+
+            [lib]
+            crate-type = ["rlib", "cdylib"]
+
+            [[bin]]
+            path = "src/bin/tool.rs"
+            required-features = ["std"]
+        "#).unwrap();
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), _)) => {
+                assert_eq!(vec!["std".to_owned()], project.features["default"]);
+                assert_eq!(Some("thin"), project.profiles["release"].lto.as_deref());
+                assert_eq!(Some(1), project.profiles["release"].codegen_units);
+                let targets: Vec<_> = project.targets.iter().map(|t| (t.kind, t.name.as_str())).collect();
+                assert_eq!(vec![(v1::TargetKind::Lib, "foo_bar"), (v1::TargetKind::Bin, "tool")], targets);
+                assert_eq!(vec!["std".to_owned()], project.targets[1].required_features);
+            },
+            Ok (result) => assert!(false, "should have been a project enum: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_custom_profiles() {
+        let manifest: schema_v1::Manifest = toml::from_str(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+
+            [profile.profiling]
+            inherits = "release"
+            debug = true
+        "#).unwrap();
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), _)) => {
+                assert_eq!(Some("release"), project.profiles["profiling"].inherits.as_deref());
+                assert_eq!(Some("true"), project.profiles["profiling"].debug.as_deref());
+            },
+            Ok (result) => assert!(false, "should have been a project enum: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+
+        let mut manifest = manifest;
+        manifest.profile.as_mut().unwrap().custom.get_mut("profiling").unwrap().inherits = None;
+        match convert(&manifest) {
+            Ok (_) => assert!(false, "should not convert a custom profile without `inherits`"),
+            Err (err) => assert_eq!(Error::Profile("profiling".to_owned(), profile::Error::MissingInherits), err),
+        }
+    }
+
+    fn forced_target_manifest(cargo_features: &str) -> schema_v1::Manifest {
+        toml::from_str(&format!(r#"
+            {}
+
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+            forced-target = "wasm32-unknown-unknown"
+        "#, cargo_features)).unwrap()
+    }
+
+    #[test]
+    fn require_cargo_features_for_unstable_fields() {
+        match convert(&forced_target_manifest("")) {
+            Ok (_) => assert!(false, "should not convert `forced-target` without `per-package-target`"),
+            Err (err) => {
+                assert_eq!(Error::CargoFeatureRequired("per-package-target".to_owned(), "package.forced-target".to_owned()), err);
+                assert_eq!(
+                    "feature `per-package-target` is required to use `package.forced-target`, \
+                     consider adding `cargo-features = [\"per-package-target\"]` to the manifest",
+                    err.to_string(),
+                );
+            },
+        }
+        match convert(&forced_target_manifest(r#"cargo-features = ["per-package-target"]"#)) {
+            Ok ((v1::Manifest::Project(project), None)) => assert_eq!("foo", project.name),
+            Ok (result) => assert!(false, "should have been a project without warnings: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn require_cargo_features_by_toolchain() {
+        let manifest: schema_v1::Manifest = toml::from_str(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2018"
+
+            [profile.profiling]
+            inherits = "release"
+        "#).unwrap();
+        let before = Some(v1::RustVersion::new(1, 56, 0));
+        match convert_for_toolchain(&manifest, before) {
+            Ok (_) => assert!(false, "custom profiles should need `named-profiles` before 1.57"),
+            Err (err) => assert_eq!(Error::CargoFeatureRequired("named-profiles".to_owned(), "profile.profiling".to_owned()), err),
+        }
+        let mut listed = manifest.to_owned();
+        listed.cargo_features = Some(vec!["named-profiles".to_owned()]);
+        assert!(convert_for_toolchain(&listed, before).is_ok());
+        assert!(convert_for_toolchain(&manifest, Some(v1::RustVersion::new(1, 57, 0))).is_ok());
+
+        let mut edition = manifest_with_package(r#"
+            name = "foo"
+            version = "1.0.0"
+            edition = "2024"
+        "#);
+        match convert_for_toolchain(&edition, Some(v1::RustVersion::new(1, 80, 0))) {
+            Ok (_) => assert!(false, "edition 2024 should need `edition2024` before 1.85"),
+            Err (err) => assert_eq!(Error::CargoFeatureRequired("edition2024".to_owned(), "package.edition".to_owned()), err),
+        }
+        edition.cargo_features = Some(vec!["edition2024".to_owned()]);
+        assert!(convert_for_toolchain(&edition, Some(v1::RustVersion::new(1, 80, 0))).is_ok());
+    }
+
+    #[test]
+    fn require_bindeps_for_artifact_dependencies() {
+        let mut manifest: schema_v1::Manifest = toml::from_str(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2018"
+
+            [build-dependencies]
+            tool = { path = "../tool", artifact = "bin", target = "target" }
+        "#).unwrap();
+        match convert(&manifest) {
+            Ok (_) => assert!(false, "should not convert `artifact` without `bindeps`"),
+            Err (err) => assert_eq!(Error::CargoFeatureRequired("bindeps".to_owned(), "build-dependencies.tool.artifact".to_owned()), err),
+        }
+        manifest.cargo_features = Some(vec!["bindeps".to_owned()]);
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), _)) => {
+                let artifact = project.dependencies[0].artifact.to_owned().unwrap();
+                assert_eq!(vec!["CARGO_BIN_DIR_TOOL", "CARGO_BIN_FILE_TOOL", "CARGO_BIN_FILE_TOOL_tool"], artifact.env_vars("tool", "tool", &["tool".to_owned()], "tool"));
+            },
+            Ok (result) => assert!(false, "should have been a project: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn warn_on_needless_cargo_features() {
+        let mut manifest = manifest_with_package(r#"
+            name = "foo"
+            version = "1.0.0"
+            edition = "2018"
+        "#);
+        manifest.cargo_features = Some(vec![
+            "named-profiles".to_owned(),
+            "publish-lockfile".to_owned(),
+            "im-a-teapot".to_owned(),
+        ]);
+        match convert(&manifest) {
+            Ok ((_, Some(w))) => assert_eq!(vec![
+                Warning::StabilizedCargoFeature("named-profiles".to_owned(), v1::RustVersion::new(1, 57, 0)),
+                Warning::RemovedCargoFeature("publish-lockfile".to_owned(), v1::RustVersion::new(1, 37, 0)),
+                Warning::UnknownCargoFeature("im-a-teapot".to_owned()),
+            ], w),
+            Ok (result) => assert!(false, "should have warned about the listed features: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+        // Not yet stable on the toolchain, so listing it is needed
+        match convert_for_toolchain(&manifest, Some(v1::RustVersion::new(1, 36, 0))) {
+            Ok ((_, Some(w))) => assert_eq!(vec![Warning::UnknownCargoFeature("im-a-teapot".to_owned())], w),
+            Ok (result) => assert!(false, "should have warned about the unknown feature: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn convert_lints() {
+        let manifest: schema_v1::Manifest = toml::from_str(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+
+            [lints.rust]
+            unsafe_code = "forbid"
+
+            [lints.clippy]
+            pedantic = { level = "warn", priority = -1 }
+
+            [lints.cranelift]
+            something = "allow"
+        "#).unwrap();
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), Some(w))) => {
+                assert_eq!(vec!["-W", "clippy::pedantic", "-A", "cranelift::something", "-F", "unsafe_code"], v1::lint_flags(&project.lints));
+                assert_eq!(vec![Warning::UnknownLintTool("lints.cranelift".to_owned())], w);
+            },
+            Ok (result) => assert!(false, "should have warned about the unknown tool: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+
+        let workspace: schema_v1::Manifest = toml::from_str(r#"
+            [workspace]
+            members = ["foo"]
+
+            [workspace.lints.rust]
+            missing_docs = "deny"
+        "#).unwrap();
+        match convert(&workspace) {
+            Ok ((v1::Manifest::Workspace(workspace), None)) => assert_eq!(vec!["-D", "missing_docs"], v1::lint_flags(&workspace.lints)),
+            Ok (result) => assert!(false, "should have been a workspace without warnings: {:?}", result),
+            Err (err) => assert!(false, "unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn fail_to_convert_unresolved_workspace_lints() {
+        let manifest: schema_v1::Manifest = toml::from_str(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+
+            [lints]
+            workspace = true
+        "#).unwrap();
+        match convert(&manifest) {
+            Ok (_) => assert!(false, "should not convert lints inherited from the workspace"),
+            Err (err) => assert_eq!(Error::UnresolvedWorkspaceField("lints".to_owned()), err),
+        }
+        match convert_for_toolchain(&manifest, Some(v1::RustVersion::new(1, 73, 0))) {
+            Ok (_) => assert!(false, "`[lints]` should need the `lints` feature before 1.74"),
+            Err (err) => assert_eq!(Error::CargoFeatureRequired("lints".to_owned(), "lints".to_owned()), err),
+        }
+    }
+
+    #[test]
+    fn fail_to_convert_target_without_name_or_path() {
+        let mut manifest = package_manifest("foo");
+        manifest.example = Some(vec![Default::default()]);
+        match convert(&manifest) {
+            Ok (_) => assert!(false, "should not convert an example without a name"),
+            Err (Error::MissingTargetName(kind)) => assert_eq!(v1::TargetKind::Example, kind),
+            Err (err) => assert!(false, "expected MissingTargetName: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn diff_ignores_formatting_and_order() {
+        let old: schema_v1::Manifest = toml::from_str(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            keywords = ["a", "b"]
+
+            [dependencies]
+            serde = { version = "1.2", features = ["derive", "rc"] }
+            log = "0.4"
+
+            [features]
+            default = ["std", "log"]
+            std = []
+        "#).unwrap();
+        let new: schema_v1::Manifest = toml::from_str(r#"
+            [features]
+            std = []
+            default = [ "log", "std" ]
+
+            [package]
+            keywords = ["b", "a"]
+            version = "1.0.0"
+            name = "foo"
+
+            [dependencies.log]
+            version = "0.4"
+
+            [dependencies.serde]
+            features = ["rc", "derive"]
+            version = "1.2"
+        "#).unwrap();
+        let (old, _) = convert(&old).unwrap();
+        let (new, _) = convert(&new).unwrap();
+        let diff = v1::diff(&old, &new);
+        assert!(diff.is_empty(), "unexpected changes: {}", diff);
+    }
+
+    fn manifest_with_targets(targets: &str) -> schema_v1::Manifest {
+        toml::from_str(&format!(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+
+            [dependencies]
+            serde = {{ version = "1.0", optional = true }}
+            log = {{ version = "0.4", optional = true }}
+            rand = "0.8"
+
+            [features]
+            std = []
+            logging = ["dep:log"]
+
+            {}
+        "#, targets)).unwrap()
+    }
+
+    #[test]
+    fn require_known_required_features() {
+        let manifest = manifest_with_targets(r#"
+            [[bin]]
+            name = "tool"
+            required-features = ["std", "serde", "rand/std", "serde/derive"]
+        "#);
+        match convert(&manifest) {
+            Ok ((_, None)) => {},
+            result => assert!(false, "should have converted without warnings: {:?}", result),
+        }
+        let cases = vec![
+            ("missing", "missing"),
+            ("log", "log"),
+            ("dep:serde", "dep:serde"),
+            ("serde?/derive", "serde?/derive"),
+            ("uuid/v4", "uuid/v4"),
+        ];
+        for (feature, expected) in cases {
+            let manifest = manifest_with_targets(&format!(r#"
+                [[example]]
+                name = "demo"
+
+                [[example]]
+                name = "other"
+                required-features = ["std", "{}"]
+            "#, feature));
+            match convert(&manifest) {
+                Ok (_) => assert!(false, "should not have accepted the required feature `{}`", feature),
+                Err (err) => assert_eq!(
+                    Error::UnknownRequiredFeature("[[example]] #2".to_owned(), "other".to_owned(), expected.to_owned()),
+                    err,
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn require_default_run_among_declared_binaries() {
+        let manifest: schema_v1::Manifest = toml::from_str(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+            autobins = false
+            default-run = "server"
+
+            [[bin]]
+            name = "cli"
+            path = "src/main.rs"
+        "#).unwrap();
+        match convert(&manifest) {
+            Ok (_) => assert!(false, "should not find `server` among the `[[bin]]` targets"),
+            Err (err) => assert_eq!(Error::V1(v1::Error::DefaultRunNotFound("server".to_owned(), vec!["cli".to_owned()])), err),
+        }
+
+        let mut discovered = manifest.to_owned();
+        discovered.package.as_mut().unwrap().autobins = None;
+        match convert(&discovered) {
+            Ok ((v1::Manifest::Project(project), _)) => {
+                assert!(project.autobins);
+                assert_eq!(Some("server".to_owned()), project.default_run);
+            },
+            result => assert!(false, "should leave discovered binaries to `verify_default_run`: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn warn_on_suspicious_targets() {
+        let manifest = manifest_with_targets(r#"
+            [lib]
+            name = "foo"
+            proc-macro = true
+            crate-type = ["proc-macro", "rlib"]
+
+            [[bin]]
+            name = "foo"
+            path = "src/main.rs"
+
+            [[bin]]
+            name = "demo"
+            path = "src/bin/demo.rs"
+
+            [[example]]
+            name = "demo"
+        "#);
+        match convert(&manifest) {
+            Ok ((_, Some(warnings))) => assert_eq!(vec![
+                Warning::ProcMacroCrateTypes("foo".to_owned(), vec!["rlib".to_owned()]),
+                Warning::DuplicateTargetName("demo".to_owned(), vec!["[[bin]] #2".to_owned(), "[[example]] #1".to_owned()]),
+            ], warnings),
+            result => assert!(false, "should have warned about the targets: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn convert_legacy_aliases() {
+        let manifest: schema_v1::Manifest = toml::from_str(r#"
+            [project]
+            name = "foo"
+            version = "1.0.0"
+            edition = "2021"
+
+            [lib]
+            crate_type = ["rlib", "cdylib"]
+
+            [dependencies]
+            serde = { version = "1.0", default_features = false }
+
+            [build_dependencies]
+            cc = "1.0"
+
+            [target.'cfg(unix)'.dev_dependencies]
+            libc = { version = "0.2", default_features = false }
+        "#).unwrap();
+        match convert(&manifest) {
+            Ok ((v1::Manifest::Project(project), Some(warnings))) => {
+                assert_eq!("foo", project.name);
+                assert_eq!(vec!["rlib".to_owned(), "cdylib".to_owned()], project.targets[0].crate_types);
+                let kinds: Vec<_> = project.dependencies.iter().map(|d| (d.name.as_str(), d.kind, d.default_features)).collect();
+                assert_eq!(vec![
+                    ("serde", v1::DependencyKind::Normal, false),
+                    ("cc", v1::DependencyKind::Build, true),
+                    ("libc", v1::DependencyKind::Development, false),
+                ], kinds);
+                assert_eq!(vec![
+                    Warning::DeprecatedProjectTable,
+                    Warning::DeprecatedAlias("build_dependencies".to_owned(), "build-dependencies".to_owned()),
+                    Warning::DeprecatedAlias("dependencies.serde.default_features".to_owned(), "default-features".to_owned()),
+                    Warning::DeprecatedAlias("target.cfg(unix).dev_dependencies".to_owned(), "dev-dependencies".to_owned()),
+                    Warning::DeprecatedAlias("target.cfg(unix).dev-dependencies.libc.default_features".to_owned(), "default-features".to_owned()),
+                    Warning::DeprecatedAlias("lib.crate_type".to_owned(), "crate-type".to_owned()),
+                ], warnings);
+                assert_eq!(
+                    "`lib.crate_type` is deprecated in favor of `crate-type` and will not work in the 2024 edition",
+                    warnings[5].to_string(),
+                );
+            },
+            result => assert!(false, "should have been a project with warnings: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn reject_conflicting_aliases() {
+        let cases = vec![
+            (r#"
+                [dev-dependencies]
+                log = "0.4"
+
+                [dev_dependencies]
+                log = "0.4"
+            "#, Error::ConflictingAlias("dev_dependencies".to_owned(), "dev-dependencies".to_owned())),
+            (r#"
+                [dependencies]
+                serde = { version = "1.0", default-features = false, default_features = false }
+            "#, Error::ConflictingAlias(
+                "dependencies.serde.default_features".to_owned(),
+                "dependencies.serde.default-features".to_owned(),
+            )),
+            (r#"
+                [[bin]]
+                name = "tool"
+                proc-macro = false
+                proc_macro = false
+            "#, Error::ConflictingAlias("bin.tool.proc_macro".to_owned(), "bin.tool.proc-macro".to_owned())),
+            (r#"
+                [project]
+                name = "foo"
+                version = "1.0.0"
+            "#, Error::PackageAndProject),
+        ];
+        for (tables, expected) in cases {
+            let manifest: schema_v1::Manifest = toml::from_str(&format!(r#"
+                [package]
+                name = "foo"
+                version = "1.0.0"
+                edition = "2021"
+
+                {}
+            "#, tables)).unwrap();
+            match convert(&manifest) {
+                Ok (result) => assert!(false, "should have failed with {:?} but was {:?}", expected, result),
+                Err (err) => assert_eq!(expected, err),
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{ Error, Warning };
+
+/// Tools cargo passes lints of to rustc and rustdoc
+pub const LINT_TOOLS: [&str; 3] = ["clippy", "rust", "rustdoc"];
+
+pub fn convert_level(src: schema_v1::LintLevel) -> v1::LintLevel {
+    match src {
+        schema_v1::LintLevel::Forbid => v1::LintLevel::Forbid,
+        schema_v1::LintLevel::Deny => v1::LintLevel::Deny,
+        schema_v1::LintLevel::Warn => v1::LintLevel::Warn,
+        schema_v1::LintLevel::Allow => v1::LintLevel::Allow,
+    }
+}
+
+/// Converts `[lints]` or `[workspace.lints]`, warning about tools cargo
+/// does not know of, whose lints are still kept
+pub fn convert_lints(src: &schema_v1::Lints, table: &str, warnings: &mut Vec<Warning>) -> Vec<v1::Lint> {
+    let mut lints = vec![];
+    for (tool, tool_lints) in src {
+        if !LINT_TOOLS.contains(&tool.as_str()) {
+            warnings.push(Warning::UnknownLintTool(format!("{}.{}", table, tool)));
+        }
+        lints.extend(tool_lints.iter().map(|(name, lint)| v1::Lint {
+            tool: tool.to_owned(),
+            name: name.to_owned(),
+            level: convert_level(lint.level()),
+            priority: lint.priority(),
+        }));
+    }
+    lints
+}
+
+/// Converts the `[lints]` of a package, which must no longer inherit from
+/// the workspace
+pub fn convert_package_lints(
+    src: Option<&schema_v1::PackageLints>,
+    warnings: &mut Vec<Warning>,
+) -> Result<Vec<v1::Lint>, Error> {
+    match src {
+        Some(lints) if lints.workspace == Some(true) => Err(Error::UnresolvedWorkspaceField("lints".to_owned())),
+        Some(lints) => Ok(convert_lints(&lints.tools, "lints", warnings)),
+        None => Ok(vec![]),
+    }
+}
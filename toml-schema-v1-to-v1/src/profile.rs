@@ -8,6 +8,7 @@ pub enum Error {
     LtoNotAllowedInOverride,
     RPathNotAllowedInOverride,
     InvalidPanicSetting(String),
+    MissingInherits,
 }
 
 impl fmt::Display for Error {
@@ -19,6 +20,7 @@ impl fmt::Display for Error {
             Error::LtoNotAllowedInOverride => write!(f, "`lto` may not be specified in a profile override."),
             Error::RPathNotAllowedInOverride => write!(f, "`rpath` may not be specified in a profile override."),
             Error::InvalidPanicSetting(setting) => write!(f, "`panic` setting of `{}` is not a valid setting, must be `unwind` or `abort`", setting),
+            Error::MissingInherits => write!(f, "a custom profile must set `inherits`"),
         }
     }
 }
@@ -45,11 +47,43 @@ fn validate_profile(src: &schema_v1::Profile) -> Option<Error> {
     }
 }
 
+fn convert_settings(src: schema_v1::Profile) -> v1::Profile {
+    v1::Profile {
+        opt_level: src.opt_level.map(|level| level.0),
+        lto: src.lto.map(|lto| match lto {
+            schema_v1::string_or_bool::StringOrBool::String(value) => value,
+            schema_v1::string_or_bool::StringOrBool::Bool(value) => value.to_string(),
+        }),
+        codegen_units: src.codegen_units,
+        debug: src.debug.map(|debug| match debug {
+            schema_v1::U32OrBool::U32(level) => level.to_string(),
+            schema_v1::U32OrBool::Bool(value) => value.to_string(),
+        }),
+        debug_assertions: src.debug_assertions,
+        rpath: src.rpath,
+        panic: src.panic,
+        overflow_checks: src.overflow_checks,
+        incremental: src.incremental,
+        inherits: src.inherits,
+        overrides: src.overrides.unwrap_or_default()
+            .into_iter()
+            .map(|(spec, profile)| {
+                let spec = match spec {
+                    schema_v1::ProfilePackageSpec::Spec(spec) => spec,
+                    schema_v1::ProfilePackageSpec::All => "*".to_owned(),
+                };
+                (spec, convert_settings(profile))
+            })
+            .collect(),
+        build_override: src.build_override.map(|profile| Box::new(convert_settings(*profile))),
+    }
+}
+
 pub fn convert_profile(src: schema_v1::Profile) -> Result<v1::Profile> {
-    if let Some(err) = src.build_override.and_then(|p| validate_profile(&p)) {
+    if let Some(err) = src.build_override.as_ref().and_then(|p| validate_profile(p)) {
         return Err (err)
     }
-    if let Some(err) = src.overrides.and_then(|o| {
+    if let Some(err) = src.overrides.as_ref().and_then(|o| {
         for profile in o.values() {
             if let Some(err) = validate_profile(profile) {
                 return Some(err)
@@ -59,15 +93,16 @@ pub fn convert_profile(src: schema_v1::Profile) -> Result<v1::Profile> {
     }) {
         return Err (err)
     }
-    if let Some (panic) = src.panic {
+    if let Some (panic) = &src.panic {
         if panic != "unwind" && panic != "abort" {
-            return Err(Error::InvalidPanicSetting(panic))
+            return Err(Error::InvalidPanicSetting(panic.to_owned()))
         }
     }
-    Ok(( v1::Profile {}, None ))
+    Ok(( convert_settings(src), None ))
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
 mod tests {
     use std::collections::BTreeMap;
 
@@ -172,4 +207,31 @@ mod tests {
             Err (err) => assert_eq!(Error::InvalidPanicSetting("foo".to_owned()), err),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn convert_settings_and_overrides() {
+        let mut overrides = BTreeMap::new();
+        overrides.insert(schema_v1::ProfilePackageSpec::All, schema_v1::Profile {
+            opt_level: Some(schema_v1::OptLevel("2".to_owned())),
+            ..Default::default()
+        });
+        let p = schema_v1::Profile {
+            opt_level: Some(schema_v1::OptLevel("s".to_owned())),
+            lto: Some(schema_v1::string_or_bool::StringOrBool::Bool(true)),
+            debug: Some(schema_v1::U32OrBool::U32(1)),
+            panic: Some("abort".to_owned()),
+            overrides: Some(overrides),
+            ..Default::default()
+        };
+        match convert_profile(p) {
+            Ok ((profile, _)) => {
+                assert_eq!(Some("s"), profile.opt_level.as_deref());
+                assert_eq!(Some("true"), profile.lto.as_deref());
+                assert_eq!(Some("1"), profile.debug.as_deref());
+                assert_eq!(Some("abort"), profile.panic.as_deref());
+                assert_eq!(Some("2"), profile.overrides["*"].opt_level.as_deref());
+            },
+            Err (err) => assert!(false, "unexpected error: {}", err),
+        }
+    }
+}
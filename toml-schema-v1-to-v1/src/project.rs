@@ -0,0 +1,303 @@
+use std::collections::{ BTreeMap };
+
+use v1::IntoUrl;
+
+use crate::{ dependency, lints, profile, targets, Error, Warning };
+
+pub fn convert_edition(src: schema_v1::Edition) -> v1::Edition {
+    match src {
+        schema_v1::Edition::Edition2015 => v1::Edition::Edition2015,
+        schema_v1::Edition::Edition2018 => v1::Edition::Edition2018,
+        schema_v1::Edition::Edition2021 => v1::Edition::Edition2021,
+        schema_v1::Edition::Edition2024 => v1::Edition::Edition2024,
+    }
+}
+
+pub fn convert_rust_version(src: schema_v1::RustVersion) -> v1::RustVersion {
+    v1::RustVersion {
+        major: src.major,
+        minor: src.minor,
+        patch: src.patch,
+    }
+}
+
+pub fn convert_resolver(src: schema_v1::Resolver) -> v1::Resolver {
+    match src {
+        schema_v1::Resolver::V1 => v1::Resolver::V1,
+        schema_v1::Resolver::V2 => v1::Resolver::V2,
+        schema_v1::Resolver::V3 => v1::Resolver::V3,
+    }
+}
+
+fn validate_package_name(name: &str, warnings: &mut Vec<Warning>) -> Result<(), Error> {
+    v1::validate_name(name)
+        .map_err(|err| v1::Error::InvalidPackageName(name.to_owned(), err))?;
+    warnings.extend(
+        v1::restricted_names(name)
+            .into_iter()
+            .map(|reason| Warning::RestrictedPackageName(name.to_owned(), reason))
+    );
+    Ok(())
+}
+
+/// Editions set on individual `[lib]`, `[[bin]]`, `[[example]]`, `[[test]]`
+/// and `[[bench]]` targets
+fn target_editions(src: &schema_v1::Manifest) -> Vec<schema_v1::Edition> {
+    src.lib.iter()
+        .chain(src.bin.iter().flatten())
+        .chain(src.example.iter().flatten())
+        .chain(src.test.iter().flatten())
+        .chain(src.bench.iter().flatten())
+        .filter_map(|target| target.edition)
+        .collect()
+}
+
+fn validate_rust_version(rust_version: v1::RustVersion, edition: v1::Edition) -> Result<(), Error> {
+    if rust_version < edition.first_version() {
+        Err(Error::RustVersionOlderThanEdition(rust_version, edition))
+    } else {
+        Ok(())
+    }
+}
+
+/// The value of a field, which must not still inherit from the workspace
+pub fn defined<'a, T>(field: &str, src: &'a Option<schema_v1::MaybeWorkspace<T>>) -> Result<Option<&'a T>, Error> {
+    match src {
+        Some(schema_v1::MaybeWorkspace::Defined(value)) => Ok(Some(value)),
+        Some(schema_v1::MaybeWorkspace::Workspace(_)) => Err(Error::UnresolvedWorkspaceField(field.to_owned())),
+        None => Ok(None),
+    }
+}
+
+/// A URL field, which is dropped with a warning when it is not a valid URL
+/// as cargo still accepts the manifest
+fn convert_url(
+    field: &str,
+    src: &Option<schema_v1::MaybeWorkspace<String>>,
+    warnings: &mut Vec<Warning>,
+) -> Result<Option<url::Url>, Error> {
+    match defined(field, src)? {
+        Some(url) => match url.as_str().into_url() {
+            Ok(url) => Ok(Some(url)),
+            Err(_) => {
+                warnings.push(Warning::InvalidUrl(field.to_owned(), url.to_owned()));
+                Ok(None)
+            },
+        },
+        None => Ok(None),
+    }
+}
+
+fn convert_metadata(
+    src: &schema_v1::Manifest,
+    package: &schema_v1::Project,
+    warnings: &mut Vec<Warning>,
+) -> Result<v1::Metadata, Error> {
+    Ok(v1::Metadata {
+        authors: defined("authors", &package.authors)?.cloned().unwrap_or_default(),
+        keywords: defined("keywords", &package.keywords)?.cloned().unwrap_or_default(),
+        categories: defined("categories", &package.categories)?.cloned().unwrap_or_default(),
+        license: defined("license", &package.license)?.cloned(),
+        license_file: defined("license-file", &package.license_file)?.cloned(),
+        description: defined("description", &package.description)?.cloned(),
+        readme: defined("readme", &package.readme)?.cloned(),
+        homepage: convert_url("homepage", &package.homepage, warnings)?,
+        repository: convert_url("repository", &package.repository, warnings)?,
+        documentation: convert_url("documentation", &package.documentation, warnings)?,
+        badges: src.badges.to_owned().unwrap_or_default(),
+        links: package.links.to_owned(),
+    })
+}
+
+/// Registries allowed by `publish`, where `true` or no value allows any
+pub fn convert_publish(src: Option<&schema_v1::VecStringOrBool>) -> Option<Vec<String>> {
+    match src {
+        Some(schema_v1::VecStringOrBool::VecString(registries)) => Some(registries.to_owned()),
+        Some(schema_v1::VecStringOrBool::Bool(false)) => Some(vec![]),
+        Some(schema_v1::VecStringOrBool::Bool(true)) | None => None,
+    }
+}
+
+/// The build script named by `build`, where `true` names `build.rs`
+pub fn convert_build(src: Option<&schema_v1::string_or_bool::StringOrBool>) -> v1::BuildScript {
+    match src {
+        Some(schema_v1::string_or_bool::StringOrBool::String(path)) => v1::BuildScript::Path(path.into()),
+        Some(schema_v1::string_or_bool::StringOrBool::Bool(true)) => v1::BuildScript::Path(v1::DEFAULT_BUILD_SCRIPT.into()),
+        Some(schema_v1::string_or_bool::StringOrBool::Bool(false)) => v1::BuildScript::Disabled,
+        None => v1::BuildScript::Inferred,
+    }
+}
+
+type DependencyTable<'a> = (v1::DependencyKind, Option<&'a str>, &'a schema_v1::DependencyMap);
+
+fn dependency_tables<'a>(
+    platform: Option<&'a str>,
+    normal: &'a Option<schema_v1::DependencyMap>,
+    dev: &'a Option<schema_v1::DependencyMap>,
+    build: &'a Option<schema_v1::DependencyMap>,
+) -> Vec<DependencyTable<'a>> {
+    vec![
+        (v1::DependencyKind::Normal, normal),
+        (v1::DependencyKind::Development, dev),
+        (v1::DependencyKind::Build, build),
+    ].into_iter()
+        .filter_map(|(kind, map)| map.as_ref().map(|map| (kind, platform, map)))
+        .collect()
+}
+
+/// Every dependency table of the manifest with the kind and platform its
+/// dependencies are declared for
+pub(crate) fn dependency_maps(src: &schema_v1::Manifest) -> Vec<DependencyTable<'_>> {
+    let mut maps = dependency_tables(None, &src.dependencies, &src.dev_dependencies, &src.build_dependencies);
+    for (name, platform) in src.target.iter().flatten() {
+        maps.extend(dependency_tables(
+            Some(name),
+            &platform.dependencies,
+            &platform.dev_dependencies,
+            &platform.build_dependencies,
+        ));
+    }
+    maps
+}
+
+fn convert_dependencies(
+    src: &schema_v1::Manifest,
+    warnings: &mut Vec<Warning>,
+) -> Result<Vec<v1::DeclaredDependency>, Error> {
+    let mut dependencies = vec![];
+    for (kind, platform, map) in dependency_maps(src) {
+        for (name, dep) in map.iter() {
+            let (dependency, w) = dependency::convert_declared_dependency(name, kind, platform, dep.to_owned())
+                .map_err(|err| Error::Dependency(name.to_owned(), err))?;
+            warnings.extend(
+                w.unwrap_or_default()
+                    .into_iter()
+                    .map(|w| Warning::Dependency(name.to_owned(), Box::new(w)))
+            );
+            dependencies.push(dependency);
+        }
+    }
+    Ok(dependencies)
+}
+
+fn convert_profiles(src: &schema_v1::Manifest) -> Result<BTreeMap<String, v1::Profile>, Error> {
+    let profiles = match &src.profile {
+        Some(profiles) => profiles,
+        None => return Ok(BTreeMap::new()),
+    };
+    let named = vec![
+        ("dev", &profiles.dev),
+        ("release", &profiles.release),
+        ("test", &profiles.test),
+        ("bench", &profiles.bench),
+        ("doc", &profiles.doc),
+    ];
+    let mut converted = BTreeMap::new();
+    for (name, profile) in &profiles.custom {
+        if profile.inherits.is_none() {
+            return Err(Error::Profile(name.to_owned(), profile::Error::MissingInherits));
+        }
+    }
+    let custom = profiles.custom.iter().map(|(name, profile)| (name.as_str(), Some(profile)));
+    for (name, profile) in named.into_iter().map(|(name, profile)| (name, profile.as_ref())).chain(custom) {
+        if let Some(profile) = profile {
+            let (profile, _) = profile::convert_profile(profile.to_owned())
+                .map_err(|err| Error::Profile(name.to_owned(), err))?;
+            converted.insert(name.to_owned(), profile);
+        }
+    }
+    Ok(converted)
+}
+
+/// Name of a target, inferring a missing one as cargo does
+fn target_name(kind: v1::TargetKind, package: &str, src: &schema_v1::Target) -> Result<String, Error> {
+    if let Some(name) = &src.name {
+        return Ok(name.to_owned());
+    }
+    let inferred = match kind {
+        v1::TargetKind::Lib => Some(package.replace('-', "_")),
+        _ => src.path.as_ref()
+            .and_then(|path| path.0.file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned()),
+    };
+    inferred.ok_or(Error::MissingTargetName(kind))
+}
+
+fn convert_target(kind: v1::TargetKind, package: &str, src: &schema_v1::Target) -> Result<v1::Target, Error> {
+    Ok(v1::Target {
+        kind,
+        name: target_name(kind, package, src)?,
+        path: src.path.as_ref().map(|path| path.0.to_owned()),
+        crate_types: src.crate_type.to_owned().unwrap_or_default(),
+        required_features: src.required_features.to_owned().unwrap_or_default(),
+        edition: src.edition.map(convert_edition),
+        test: src.test,
+        doctest: src.doctest,
+        bench: src.bench,
+        doc: src.doc,
+        harness: src.harness,
+        proc_macro: src.proc_macro,
+    })
+}
+
+fn convert_targets(src: &schema_v1::Manifest, package: &str) -> Result<Vec<v1::Target>, Error> {
+    src.lib.iter().map(|target| (v1::TargetKind::Lib, target))
+        .chain(src.bin.iter().flatten().map(|target| (v1::TargetKind::Bin, target)))
+        .chain(src.example.iter().flatten().map(|target| (v1::TargetKind::Example, target)))
+        .chain(src.test.iter().flatten().map(|target| (v1::TargetKind::Test, target)))
+        .chain(src.bench.iter().flatten().map(|target| (v1::TargetKind::Bench, target)))
+        .map(|(kind, target)| convert_target(kind, package, target))
+        .collect()
+}
+
+/// Converts the `[package]` section of `src` along with its dependencies,
+/// checking that the `rust-version` is new enough for the package and
+/// target editions and that the targets agree with the features
+pub fn convert_project(
+    src: &schema_v1::Manifest,
+    package: &schema_v1::Project,
+    warnings: &mut Vec<Warning>,
+) -> Result<v1::Project, Error> {
+    validate_package_name(&package.name, warnings)?;
+    let version = match &package.version {
+        schema_v1::MaybeWorkspace::Defined(version) => version.to_owned(),
+        schema_v1::MaybeWorkspace::Workspace(_) => return Err(Error::UnresolvedWorkspaceField("version".to_owned())),
+    };
+
+    let edition = match defined("edition", &package.edition)? {
+        Some(edition) => convert_edition(*edition),
+        None => {
+            warnings.push(Warning::NoEditionSet(v1::Edition::default(), v1::Edition::LATEST));
+            v1::Edition::default()
+        },
+    };
+    let rust_version = defined("rust-version", &package.rust_version)?.cloned().map(convert_rust_version);
+    if let Some(rust_version) = rust_version {
+        validate_rust_version(rust_version, edition)?;
+        for target_edition in target_editions(src) {
+            validate_rust_version(rust_version, convert_edition(target_edition))?;
+        }
+    }
+
+    let project = v1::Project {
+        name: package.name.to_owned(),
+        version,
+        edition,
+        rust_version,
+        resolver: package.resolver
+            .map(convert_resolver)
+            .unwrap_or_else(|| edition.default_resolver()),
+        metadata: convert_metadata(src, package, warnings)?,
+        publish: convert_publish(defined("publish", &package.publish)?),
+        build: convert_build(package.build.as_ref()),
+        default_run: package.default_run.to_owned(),
+        dependencies: convert_dependencies(src, warnings)?,
+        features: src.features.to_owned().unwrap_or_default(),
+        profiles: convert_profiles(src)?,
+        targets: convert_targets(src, &package.name)?,
+        autobins: targets::autobins(src, package, edition),
+        lints: lints::convert_package_lints(src.lints.as_ref(), warnings)?,
+    };
+    targets::validate_targets(&project, warnings)?;
+    Ok(project)
+}
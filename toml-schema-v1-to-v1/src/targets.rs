@@ -0,0 +1,111 @@
+use std::collections::{ BTreeMap };
+
+use crate::{ Error, Warning };
+
+/// Whether binaries are discovered besides the `[[bin]]` targets, which
+/// declaring any `[[bin]]` turns off for the 2015 edition
+pub fn autobins(src: &schema_v1::Manifest, package: &schema_v1::Project, edition: v1::Edition) -> bool {
+    package.autobins.unwrap_or(edition >= v1::Edition::Edition2018 || src.bin.is_none())
+}
+
+/// Where a target is declared, e.g. `[lib]` or `[[bin]] #2`
+fn locations(targets: &[v1::Target]) -> Vec<String> {
+    let mut counts: BTreeMap<v1::TargetKind, usize> = BTreeMap::new();
+    targets.iter()
+        .map(|target| match target.kind {
+            v1::TargetKind::Lib => "[lib]".to_owned(),
+            kind => {
+                let count = counts.entry(kind).or_default();
+                *count += 1;
+                format!("[[{}]] #{}", kind, count)
+            },
+        })
+        .collect()
+}
+
+/// Whether `feature` is a feature of the package, including the implicit
+/// ones of optional dependencies, or a `dep/feature` of one of its
+/// dependencies
+fn is_known_feature(project: &v1::Project, feature: &str) -> bool {
+    let is_dependency = |name: &str| project.dependencies.iter().any(|dep| dep.name == name);
+    match feature.split_once('/') {
+        Some((dep, dep_feature)) => !dep.ends_with('?') && !dep_feature.is_empty() && is_dependency(dep),
+        None if project.features.contains_key(feature) => true,
+        None => {
+            let explicit = format!("dep:{}", feature);
+            let optional = project.dependencies.iter().any(|dep| dep.optional && dep.name == feature);
+            optional && !project.features.values().flatten().any(|value| *value == explicit)
+        },
+    }
+}
+
+fn validate_required_features(project: &v1::Project, locations: &[String]) -> Result<(), Error> {
+    for (target, location) in project.targets.iter().zip(locations) {
+        if let Some(feature) = target.required_features.iter().find(|f| !is_known_feature(project, f)) {
+            return Err(Error::UnknownRequiredFeature(location.to_owned(), target.name.to_owned(), feature.to_owned()));
+        }
+    }
+    Ok(())
+}
+
+/// Checks `default-run` against the `[[bin]]` targets, which are all the
+/// binaries there are once `autobins` is off; discovered ones are checked
+/// by `v1::verify_default_run`
+fn validate_default_run(project: &v1::Project) -> Result<(), Error> {
+    let default_run = match &project.default_run {
+        Some(default_run) if !project.autobins => default_run,
+        _ => return Ok(()),
+    };
+    let binaries: Vec<_> = project.targets.iter()
+        .filter(|target| target.kind == v1::TargetKind::Bin)
+        .map(|target| target.name.to_owned())
+        .collect();
+    if binaries.contains(default_run) {
+        Ok(())
+    } else {
+        Err(Error::V1(v1::Error::DefaultRunNotFound(default_run.to_owned(), binaries)))
+    }
+}
+
+/// Warns about a procedural macro library which also sets `crate-type`
+fn check_proc_macro(project: &v1::Project, warnings: &mut Vec<Warning>) {
+    let lib = project.targets.iter().find(|target| target.kind == v1::TargetKind::Lib);
+    if let Some(lib) = lib.filter(|lib| lib.proc_macro == Some(true)) {
+        let extra: Vec<_> = lib.crate_types.iter().filter(|ty| *ty != "proc-macro").cloned().collect();
+        if !extra.is_empty() {
+            warnings.push(Warning::ProcMacroCrateTypes(lib.name.to_owned(), extra));
+        }
+    }
+}
+
+/// Warns about targets sharing a name, apart from a `[lib]` and a `[[bin]]`
+/// which cargo builds side by side
+fn check_duplicate_names(project: &v1::Project, locations: &[String], warnings: &mut Vec<Warning>) {
+    let mut by_name: BTreeMap<&str, Vec<(v1::TargetKind, &str)>> = BTreeMap::new();
+    for (target, location) in project.targets.iter().zip(locations) {
+        by_name.entry(&target.name).or_default().push((target.kind, location));
+    }
+    for (name, declared) in by_name {
+        let clashing: Vec<_> = declared.iter()
+            .filter(|(kind, _)| *kind != v1::TargetKind::Lib)
+            .collect();
+        let only_lib_and_bin = clashing.len() == 1 && clashing[0].0 == v1::TargetKind::Bin;
+        if declared.len() > 1 && !only_lib_and_bin {
+            warnings.push(Warning::DuplicateTargetName(
+                name.to_owned(),
+                declared.iter().map(|(_, location)| location.to_string()).collect(),
+            ));
+        }
+    }
+}
+
+/// Cross-checks the targets of a converted package against its features
+/// and `default-run`
+pub fn validate_targets(project: &v1::Project, warnings: &mut Vec<Warning>) -> Result<(), Error> {
+    let locations = locations(&project.targets);
+    validate_required_features(project, &locations)?;
+    validate_default_run(project)?;
+    check_proc_macro(project, warnings);
+    check_duplicate_names(project, &locations, warnings);
+    Ok(())
+}
@@ -1,25 +1,54 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(rename_all = "kebab-case")]
+use crate::string_or_vec::{ StringOrVec };
+
+/// A dependency given as a table, e.g. `{ version = "1.0", features = ["derive"] }`.
+// `Dependency` is untagged, which buffers the table out of reach of
+// `serde_ignored`, so a misspelt key has to fail here
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
 pub struct DetailedDependency {
+    /// Version requirement of the dependency, e.g. `"1.0"` or `">= 0.3, < 0.5"`
     pub version: Option<String>,
+    /// Name of the registry in `.cargo/config.toml` to fetch the dependency from
     pub registry: Option<String>,
+    /// Path to the directory of a local package, relative to this manifest
     pub path: Option<String>,
+    /// URL of the git repository holding the dependency
     pub git: Option<String>,
+    /// Branch of the `git` repository to use
     pub branch: Option<String>,
+    /// Tag of the `git` repository to use
     pub tag: Option<String>,
+    /// Commit of the `git` repository to use
     pub rev: Option<String>,
+    /// Features of the dependency to enable
     pub features: Option<Vec<String>>,
+    /// Only builds the dependency when a feature of this package enables it
     pub optional: Option<bool>,
+    /// Whether the `default` feature of the dependency is enabled
     pub default_features: Option<bool>,
+    /// Deprecated alias of `default-features`
+    #[serde(rename = "default_features")]
+    pub default_features2: Option<bool>,
+    /// Name of the package to depend on when it differs from the dependency name
     pub package: Option<String>,
+    /// Exposes the dependency in the public API of this package
     pub public: Option<bool>,
+    /// Inherits the dependency from `[workspace.dependencies]`
+    pub workspace: Option<bool>,
+    /// Unstable artifacts to build, `"bin"`, `"bin:<name>"`, `"cdylib"` or `"staticlib"`
+    pub artifact: Option<StringOrVec>,
+    /// Whether the library of an artifact dependency can be used as well
+    pub lib: Option<bool>,
+    /// Target triple to build the artifacts for, or `"target"` for the target of this package
+    pub target: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+/// A dependency given either as a version requirement or as a table.
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum Dependency {
     Simple(String),
-    Detailed(DetailedDependency),
-}
\ No newline at end of file
+    Detailed(Box<DetailedDependency>),
+}
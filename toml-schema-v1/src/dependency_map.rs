@@ -2,7 +2,8 @@ use std::collections::{ BTreeMap };
 
 use crate::{ Dependency };
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+/// Dependencies keyed by the name the package uses for them.
+#[derive(Clone, Default, Serialize, Deserialize, Debug, JsonSchema)]
 pub struct DependencyMap(pub BTreeMap<String, Dependency>);
 
 impl std::ops::Deref for DependencyMap {
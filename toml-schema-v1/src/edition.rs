@@ -0,0 +1,90 @@
+use schemars::{ JsonSchema, Schema, SchemaGenerator };
+use serde::de;
+use serde::ser;
+use std::borrow::Cow;
+use std::fmt;
+
+/// The `edition` of a package or target.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Edition {
+    Edition2015,
+    Edition2018,
+    Edition2021,
+    Edition2024,
+}
+
+impl Edition {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Edition::Edition2015 => "2015",
+            Edition::Edition2018 => "2018",
+            Edition::Edition2021 => "2021",
+            Edition::Edition2024 => "2024",
+        }
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Edition {
+    fn deserialize<D>(d: D) -> Result<Edition, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Edition;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("an edition")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Edition, E>
+            where
+                E: de::Error,
+            {
+                match value {
+                    "2015" => Ok(Edition::Edition2015),
+                    "2018" => Ok(Edition::Edition2018),
+                    "2021" => Ok(Edition::Edition2021),
+                    "2024" => Ok(Edition::Edition2024),
+                    _ => Err(E::custom(format!(
+                        "supported edition values are `2015`, `2018`, `2021`, \
+                         or `2024`, but `{}` is unknown",
+                        value
+                    ))),
+                }
+            }
+        }
+
+        d.deserialize_str(Visitor)
+    }
+}
+
+impl ser::Serialize for Edition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
+
+impl JsonSchema for Edition {
+    fn schema_name() -> Cow<'static, str> {
+        "Edition".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "The Rust edition",
+            "type": "string",
+            "enum": ["2015", "2018", "2021", "2024"],
+        })
+    }
+}
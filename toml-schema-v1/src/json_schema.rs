@@ -0,0 +1,188 @@
+use schemars::{ Schema };
+use schemars::generate::{ SchemaSettings };
+use schemars::transform::{ RecursiveTransform };
+use serde_json::{ Value };
+
+use crate::{ Manifest };
+
+/// File the published schema is kept in, relative to this crate
+pub const MANIFEST_SCHEMA_FILE: &str = "cargo-toml.schema.json";
+
+/// TOML has no `null`, which schemars allows for every `Option`
+fn remove_null(schema: &mut Schema) {
+    let object = match schema.as_object_mut() {
+        Some(object) => object,
+        None => return,
+    };
+    if let Some(Value::Array(types)) = object.get_mut("type") {
+        types.retain(|t| t != "null");
+        if types.len() == 1 {
+            let single = types.remove(0);
+            object.insert("type".to_owned(), single);
+        }
+    }
+    if let Some(Value::Array(any_of)) = object.get_mut("anyOf") {
+        any_of.retain(|s| s.get("type").is_none_or(|t| t != "null"));
+        if any_of.len() == 1 {
+            if let Some(Value::Object(single)) = any_of.pop() {
+                object.remove("anyOf");
+                for (key, value) in single {
+                    object.entry(key).or_insert(value);
+                }
+            }
+        }
+    }
+}
+
+/// The JSON Schema (draft 2020-12) of a `Cargo.toml`, generated from
+/// `Manifest` and the types of its fields so it follows the parser.
+///
+/// Field descriptions come from the doc comments of the fields.
+pub fn manifest_json_schema() -> Schema {
+    let mut schema = SchemaSettings::draft2020_12()
+        .with_transform(RecursiveTransform(remove_null as fn(&mut Schema)))
+        .into_generator()
+        .into_root_schema_for::<Manifest>();
+    schema.insert("title".to_owned(), "Cargo.toml".into());
+    schema
+}
+
+/// The schema as pretty printed JSON, as written to `MANIFEST_SCHEMA_FILE`
+pub fn manifest_json_schema_string() -> String {
+    let mut data = serde_json::to_string_pretty(&manifest_json_schema())
+        .expect("a schema is always serializable");
+    data.push('\n');
+    data
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    extern crate jsonschema;
+    extern crate serde_ignored;
+
+    use std::fs;
+    use std::path::{ Path, PathBuf };
+
+    use serde_json::{ Value };
+
+    use super::{ manifest_json_schema, manifest_json_schema_string, MANIFEST_SCHEMA_FILE };
+    use crate::{ Manifest };
+
+    fn validator() -> jsonschema::Validator {
+        let schema = serde_json::to_value(manifest_json_schema()).unwrap();
+        jsonschema::validator_for(&schema).unwrap()
+    }
+
+    fn fixtures() -> Vec<PathBuf> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        let mut paths: Vec<_> = fs::read_dir(dir).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    fn errors(validator: &jsonschema::Validator, value: &Value) -> Vec<String> {
+        validator.iter_errors(value).map(|err| format!("{} at {}", err, err.instance_path())).collect()
+    }
+
+    /// Every field of every table in the schema has a description
+    fn undescribed(schema: &Value, path: &str, missing: &mut Vec<String>) {
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                if property.get("description").is_none() {
+                    missing.push(format!("{}.{}", path, name));
+                }
+            }
+        }
+        if let Some(defs) = schema.get("$defs").and_then(Value::as_object) {
+            for (name, def) in defs {
+                undescribed(def, name, missing);
+            }
+        }
+    }
+
+    #[test]
+    fn describe_every_field() {
+        let schema = serde_json::to_value(manifest_json_schema()).unwrap();
+        let mut missing = vec![];
+        undescribed(&schema, "Manifest", &mut missing);
+        assert_eq!(Vec::<String>::new(), missing);
+    }
+
+    #[test]
+    fn validate_fixture_manifests() {
+        let validator = validator();
+        let paths = fixtures();
+        assert!(!paths.is_empty(), "no fixture manifests found");
+        for path in paths {
+            let data = fs::read_to_string(&path).unwrap();
+            let raw: toml::Value = toml::from_str(&data).unwrap();
+            let raw = serde_json::to_value(&raw).unwrap();
+            assert_eq!(Vec::<String>::new(), errors(&validator, &raw), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn round_trip_fixture_manifests() {
+        let validator = validator();
+        for path in fixtures() {
+            let data = fs::read_to_string(&path).unwrap();
+            let manifest: Manifest = match toml::from_str(&data) {
+                Ok (manifest) => manifest,
+                Err (err) => { assert!(false, "{}: {}", path.display(), err); continue },
+            };
+            // Going through `toml::Value` leaves out unset fields rather than writing `null`
+            let json = serde_json::to_value(toml::Value::try_from(&manifest).unwrap()).unwrap();
+            assert_eq!(Vec::<String>::new(), errors(&validator, &json), "{}", path.display());
+
+            let value: toml::Value = serde_json::from_value(json).unwrap();
+            assert_eq!(manifest, value.try_into::<Manifest>().unwrap(), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn reject_what_the_parser_rejects() {
+        let validator = validator();
+        let invalid = [
+            "[package]\nname = \"foo\"\nversion = \"1.0.0\"\nedition = \"2019\"",
+            "[package]\nname = \"foo\"\nversion = \"1.0.0\"\nrust-version = \"1.70-beta\"",
+            "[package]\nname = \"foo\"\nversion = { workspace = false }",
+            "[package]\nversion = \"1.0.0\"",
+            "[dependencies]\nlog = { version = \"0.4\", features = \"std\" }",
+            "[profile.release]\nopt-level = \"fast\"",
+            "[lints.rust]\nunsafe_code = \"error\"",
+            "[lints.clippy]\npedantic = { priority = 1 }",
+            "[package]\nname = \"foo\"\nversion = \"1.0.0\"\nedtion = \"2021\"",
+            "[dependencies]\nlog = { verison = \"0.4\" }",
+            "[lib]\npath = \"src/lib.rs\"\ncrate_typ = [\"rlib\"]",
+            "[profile.dev]\nopt-levl = 1",
+            "[workspace]\nmember = [\"foo\"]",
+            "[pakage]\nname = \"foo\"",
+        ];
+        for data in invalid.iter() {
+            // The parser reports keys serde ignores as unused
+            let mut unused = false;
+            let parsed: Result<Manifest, _> = serde_ignored::deserialize(&mut toml::Deserializer::new(data), |_| unused = true);
+            assert!(parsed.is_err() || unused, "parser accepted:\n{}", data);
+            let raw = serde_json::to_value(toml::from_str::<toml::Value>(data).unwrap()).unwrap();
+            assert!(!validator.is_valid(&raw), "schema accepted:\n{}", data);
+        }
+    }
+
+    /// Run with `BLESS_SCHEMA=1` to update the published schema
+    #[test]
+    fn published_schema_is_current() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(MANIFEST_SCHEMA_FILE);
+        let generated = manifest_json_schema_string();
+        if std::env::var_os("BLESS_SCHEMA").is_some() {
+            fs::write(&path, &generated).unwrap();
+        }
+        match fs::read_to_string(&path) {
+            Ok (published) => assert!(published == generated, "`{}` is out of date", MANIFEST_SCHEMA_FILE),
+            Err (err) => assert!(false, "failed to read `{}`: {}", path.display(), err),
+        }
+    }
+}
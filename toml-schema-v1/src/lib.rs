@@ -1,12 +1,20 @@
 // extern crate failure;
+#[macro_use]
+extern crate schemars;
 extern crate semver;
 #[macro_use]
 extern crate serde;
+extern crate serde_json;
+extern crate toml;
 extern crate url;
 
 mod dependency;
 mod dependency_map;
+mod edition;
+mod json_schema;
+mod lints;
 mod manifest;
+mod maybe_workspace;
 mod opt_level;
 mod path_value;
 mod platform;
@@ -14,16 +22,24 @@ mod profile;
 mod profile_package_spec;
 mod profiles;
 mod project;
+mod resolver;
+mod rust_version;
 pub mod string_or_bool;
 pub mod string_or_vec;
 mod target;
 mod u32_or_bool;
 mod vec_string_or_bool;
 mod workspace;
+mod workspace_inheritance;
+mod workspace_package;
 
 pub use self::dependency::*;
 pub use self::dependency_map::*;
+pub use self::edition::*;
+pub use self::json_schema::*;
+pub use self::lints::*;
 pub use self::manifest::*;
+pub use self::maybe_workspace::*;
 pub use self::opt_level::*;
 pub use self::path_value::*;
 pub use self::platform::*;
@@ -31,10 +47,14 @@ pub use self::profile::*;
 pub use self::profile_package_spec::*;
 pub use self::profiles::*;
 pub use self::project::*;
+pub use self::resolver::*;
+pub use self::rust_version::*;
 pub use self::target::*;
 pub use self::u32_or_bool::*;
 pub use self::vec_string_or_bool::*;
 pub use self::workspace::*;
+pub use self::workspace_inheritance::*;
+pub use self::workspace_package::*;
 
 // use failure::{ Fail };
 // use std::fmt;
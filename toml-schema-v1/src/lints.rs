@@ -0,0 +1,157 @@
+use schemars::{ JsonSchema, Schema, SchemaGenerator };
+use serde::de;
+use serde::ser;
+use std::borrow::Cow;
+use std::collections::{ BTreeMap };
+use std::fmt;
+
+/// Level a lint is reported at.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum LintLevel {
+    Forbid,
+    Deny,
+    Warn,
+    Allow,
+}
+
+impl LintLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LintLevel::Forbid => "forbid",
+            LintLevel::Deny => "deny",
+            LintLevel::Warn => "warn",
+            LintLevel::Allow => "allow",
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> de::Deserialize<'de> for LintLevel {
+    fn deserialize<D>(d: D) -> Result<LintLevel, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = LintLevel;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a lint level")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<LintLevel, E>
+            where
+                E: de::Error,
+            {
+                match value {
+                    "forbid" => Ok(LintLevel::Forbid),
+                    "deny" => Ok(LintLevel::Deny),
+                    "warn" => Ok(LintLevel::Warn),
+                    "allow" => Ok(LintLevel::Allow),
+                    _ => Err(E::custom(format!(
+                        "lint level `{}` is not valid, valid options are \"forbid\", \"deny\", \"warn\" or \"allow\"",
+                        value
+                    ))),
+                }
+            }
+        }
+
+        d.deserialize_str(Visitor)
+    }
+}
+
+impl ser::Serialize for LintLevel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
+
+impl JsonSchema for LintLevel {
+    fn schema_name() -> Cow<'static, str> {
+        "LintLevel".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Level a lint is reported at",
+            "type": "string",
+            "enum": ["forbid", "deny", "warn", "allow"],
+        })
+    }
+}
+
+/// A lint given as a table.
+// Unknown keys are refused here rather than reported as unused, as
+// `Lint` goes through `toml::Value` and `[lints]` is flattened, both of
+// which hide them from `serde_ignored`
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    /// Level the lint is reported at
+    pub level: LintLevel,
+    /// Order the lint is passed in relative to the others, lowest first so
+    /// higher priorities take precedence
+    pub priority: Option<i8>,
+}
+
+/// A lint given either as its level or as a table.
+#[derive(Clone, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Lint {
+    Level(LintLevel),
+    Config(LintConfig),
+}
+
+impl Lint {
+    pub fn level(&self) -> LintLevel {
+        match self {
+            Lint::Level(level) => *level,
+            Lint::Config(config) => config.level,
+        }
+    }
+
+    pub fn priority(&self) -> i8 {
+        match self {
+            Lint::Level(_) => 0,
+            Lint::Config(config) => config.priority.unwrap_or(0),
+        }
+    }
+}
+
+/// Reads a table as `LintConfig` and anything else as a `LintLevel`, keeping
+/// the error messages of each which an untagged enum would hide.
+impl<'de> de::Deserialize<'de> for Lint {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        match toml::Value::deserialize(deserializer)? {
+            value @ toml::Value::Table(_) => LintConfig::deserialize(value).map(Lint::Config).map_err(de::Error::custom),
+            value => LintLevel::deserialize(value).map(Lint::Level).map_err(de::Error::custom),
+        }
+    }
+}
+
+/// Lints keyed by tool, such as `rust`, `clippy` or `rustdoc`, and then by
+/// lint name.
+pub type Lints = BTreeMap<String, BTreeMap<String, Lint>>;
+
+/// The `[lints]` section of a package.
+#[derive(Clone, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+pub struct PackageLints {
+    /// Inherits `[workspace.lints]`, which leaves no room for other lints
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<bool>,
+    /// Lints keyed by tool and then by lint name
+    #[serde(flatten)]
+    pub tools: Lints,
+}
@@ -1,6 +1,6 @@
 use std::collections::{ BTreeMap };
 
-use crate::{ DependencyMap, Platform, Profiles, Project, Target, Workspace };
+use crate::{ DependencyMap, PackageLints, Platform, Profiles, Project, Target, Workspace };
 
 pub type LibTarget = Target;
 pub type BinTarget = Target;
@@ -8,26 +8,54 @@ pub type ExampleTarget = Target;
 pub type TestTarget = Target;
 pub type BenchTarget = Target;
 
+/// A `Cargo.toml` manifest.
 #[derive(Clone, Debug, Default, PartialEq)]
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct Manifest {
+    /// Unstable cargo features the manifest opts into
     pub cargo_features: Option<Vec<String>>,
+    /// The package defined by the manifest
     pub package: Option<Project>,
+    /// Deprecated alias of `package`
     pub project: Option<Project>,
+    /// Compiler settings of the `dev`, `release`, `test`, `bench` and `doc` profiles
     pub profile: Option<Profiles>,
+    /// The library target
     pub lib: Option<LibTarget>,
+    /// Binary targets
     pub bin: Option<Vec<BinTarget>>,
+    /// Example targets
     pub example: Option<Vec<ExampleTarget>>,
+    /// Integration test targets
     pub test: Option<Vec<TestTarget>>,
+    /// Benchmark targets
     pub bench: Option<Vec<TestTarget>>,
+    /// Dependencies of the package
     pub dependencies: Option<DependencyMap>,
+    /// Dependencies of tests, examples and benchmarks
     pub dev_dependencies: Option<DependencyMap>,
+    /// Deprecated alias of `dev-dependencies`
+    #[serde(rename = "dev_dependencies")]
+    pub dev_dependencies2: Option<DependencyMap>,
+    /// Dependencies of the build script
     pub build_dependencies: Option<DependencyMap>,
+    /// Deprecated alias of `build-dependencies`
+    #[serde(rename = "build_dependencies")]
+    pub build_dependencies2: Option<DependencyMap>,
+    /// Features of the package, mapped to the features and dependencies they enable
     pub features: Option<BTreeMap<String, Vec<String>>>,
+    /// Lint levels of the package, or `workspace = true` to inherit them
+    pub lints: Option<PackageLints>,
+    /// Dependencies which only apply to a target triple or `cfg(..)` expression
     pub target: Option<BTreeMap<String, Platform>>,
+    /// Deprecated overrides of dependencies, keyed by package id spec
     pub replace: Option<DependencyMap>,
+    /// Overrides of dependencies, keyed by registry name or source URL
     pub patch: Option<BTreeMap<String, DependencyMap>>,
+    /// Makes the manifest the root of a workspace
     pub workspace: Option<Workspace>,
+    /// Badges shown on the registry, no longer displayed by crates.io
     pub badges: Option<BTreeMap<String, BTreeMap<String, String>>>,
 }
\ No newline at end of file
@@ -0,0 +1,77 @@
+use serde::de;
+use std::fmt;
+
+/// The `{ workspace = true }` table used by a package field which inherits
+/// its value from `[workspace.package]` or `[workspace.dependencies]`.
+// `MaybeWorkspace` reads this from a `toml::Value`, out of reach of
+// `serde_ignored`, so a misspelt key has to fail here
+#[derive(Clone, Copy, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceInherit {
+    /// Must be `true`
+    #[schemars(extend("const" = true))]
+    pub workspace: bool,
+}
+
+/// A `[package]` field which is either set directly or inherited from the
+/// workspace.
+#[derive(Clone, Debug, JsonSchema, PartialEq, Serialize)]
+#[serde(untagged)]
+#[schemars(rename = "MaybeWorkspace_{T}")]
+pub enum MaybeWorkspace<T> {
+    Defined(T),
+    Workspace(WorkspaceInherit),
+}
+
+impl<T> MaybeWorkspace<T> {
+    /// The value when it is set directly rather than inherited
+    pub fn as_defined(&self) -> Option<&T> {
+        match self {
+            MaybeWorkspace::Defined(value) => Some(value),
+            MaybeWorkspace::Workspace(_) => None,
+        }
+    }
+
+    pub fn is_workspace(&self) -> bool {
+        match self {
+            MaybeWorkspace::Defined(_) => false,
+            MaybeWorkspace::Workspace(_) => true,
+        }
+    }
+}
+
+impl<T> From<T> for MaybeWorkspace<T> {
+    fn from(value: T) -> MaybeWorkspace<T> {
+        MaybeWorkspace::Defined(value)
+    }
+}
+
+/// Reads a table as `{ workspace = true }` and anything else as `T`, keeping
+/// the error messages of `T` which an untagged enum would hide.
+impl<'de, T> de::Deserialize<'de> for MaybeWorkspace<T>
+where
+    T: de::DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        match toml::Value::deserialize(deserializer)? {
+            value @ toml::Value::Table(_) => {
+                let inherit = WorkspaceInherit::deserialize(value).map_err(de::Error::custom)?;
+                if inherit.workspace {
+                    Ok(MaybeWorkspace::Workspace(inherit))
+                } else {
+                    Err(de::Error::custom("`workspace` cannot be false"))
+                }
+            },
+            value => T::deserialize(value).map(MaybeWorkspace::Defined).map_err(de::Error::custom),
+        }
+    }
+}
+
+impl fmt::Display for WorkspaceInherit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{ workspace = {} }}", self.workspace)
+    }
+}
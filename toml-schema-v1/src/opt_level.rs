@@ -1,5 +1,7 @@
+use schemars::{ JsonSchema, Schema, SchemaGenerator };
 use serde::de;
 use serde::ser;
+use std::borrow::Cow;
 use std::fmt;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -56,4 +58,20 @@ impl ser::Serialize for OptLevel {
             Err(_) => self.0.serialize(serializer),
         }
     }
-}
\ No newline at end of file
+}
+
+impl JsonSchema for OptLevel {
+    fn schema_name() -> Cow<'static, str> {
+        "OptLevel".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "An optimization level, an integer or `\"s\"` or `\"z\"` to optimize for size",
+            "anyOf": [
+                { "type": "integer", "minimum": 0 },
+                { "type": "string", "enum": ["s", "z"] },
+            ],
+        })
+    }
+}
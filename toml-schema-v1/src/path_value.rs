@@ -1,5 +1,7 @@
+use schemars::{ JsonSchema, Schema, SchemaGenerator };
 use serde::de;
 use serde::ser;
+use std::borrow::Cow;
 use std::fmt;
 use std::path::{ PathBuf };
 
@@ -28,4 +30,17 @@ impl fmt::Debug for PathValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
     }
-}
\ No newline at end of file
+}
+
+impl JsonSchema for PathValue {
+    fn schema_name() -> Cow<'static, str> {
+        "PathValue".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A path relative to the manifest",
+            "type": "string",
+        })
+    }
+}
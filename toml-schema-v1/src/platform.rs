@@ -1,10 +1,19 @@
 use crate::{ DependencyMap };
 
 /// Corresponds to a `target` entry, but `Target` is already used.
-#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct Platform {
+    /// Dependencies of the package on this platform
     pub dependencies: Option<DependencyMap>,
+    /// Dependencies of the build script on this platform
     pub build_dependencies: Option<DependencyMap>,
+    /// Deprecated alias of `build-dependencies`
+    #[serde(rename = "build_dependencies")]
+    pub build_dependencies2: Option<DependencyMap>,
+    /// Dependencies of tests, examples and benchmarks on this platform
     pub dev_dependencies: Option<DependencyMap>,
+    /// Deprecated alias of `dev-dependencies`
+    #[serde(rename = "dev_dependencies")]
+    pub dev_dependencies2: Option<DependencyMap>,
 }
\ No newline at end of file
@@ -3,19 +3,34 @@ use std::collections::{ BTreeMap };
 use crate::{ OptLevel, ProfilePackageSpec, U32OrBool };
 use crate::string_or_bool::{ StringOrBool };
 
-#[derive(Deserialize, Serialize, Clone, Debug, Default, Eq, PartialEq)]
+/// Compiler settings of a profile.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, Eq, JsonSchema, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct Profile {
+    /// Optimization level, `0` to `3`, `"s"` or `"z"`
     pub opt_level: Option<OptLevel>,
+    /// Link time optimization, a boolean or `"thin"`, `"fat"` or `"off"`
     pub lto: Option<StringOrBool>,
+    /// Number of code generation units a crate is split into
     pub codegen_units: Option<u32>,
+    /// Amount of debug information, a boolean or `0` to `2`
     pub debug: Option<U32OrBool>,
+    /// Enables `debug_assert!` and `cfg(debug_assertions)`
     pub debug_assertions: Option<bool>,
+    /// Sets the rpath of binaries
     pub rpath: Option<bool>,
+    /// Panic strategy, `"unwind"` or `"abort"`
     pub panic: Option<String>,
+    /// Panics on integer overflow
     pub overflow_checks: Option<bool>,
+    /// Enables incremental compilation
     pub incremental: Option<bool>,
+    /// Profile a custom profile takes the settings it leaves unset from
+    pub inherits: Option<String>,
+    /// Settings of individual packages, keyed by package id spec or `"*"`
     pub overrides: Option<BTreeMap<ProfilePackageSpec, Profile>>,
+    /// Settings of build scripts, proc macros and their dependencies
     pub build_override: Option<Box<Profile>>,
 }
 
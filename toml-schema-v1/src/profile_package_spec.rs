@@ -1,5 +1,7 @@
+use schemars::{ JsonSchema, Schema, SchemaGenerator };
 use serde::de;
 use serde::ser;
+use std::borrow::Cow;
 
 pub type PackageIdSpec = String;
 
@@ -33,4 +35,17 @@ impl<'de> de::Deserialize<'de> for ProfilePackageSpec {
             Ok(ProfilePackageSpec::Spec(string))
         }
     }
-}
\ No newline at end of file
+}
+
+impl JsonSchema for ProfilePackageSpec {
+    fn schema_name() -> Cow<'static, str> {
+        "ProfilePackageSpec".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A package id spec, or `*` for every package",
+            "type": "string",
+        })
+    }
+}
@@ -1,12 +1,23 @@
+use std::collections::{ BTreeMap };
+
 use crate::Profile;
 
-#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+/// The `[profile]` section.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, PartialEq)]
 pub struct Profiles {
+    /// Profile used by `cargo test`
     pub test: Option<Profile>,
+    /// Deprecated profile which has no effect
     pub doc: Option<Profile>,
+    /// Profile used by `cargo bench`
     pub bench: Option<Profile>,
+    /// Profile used by default during development
     pub dev: Option<Profile>,
+    /// Profile used by `--release`
     pub release: Option<Profile>,
+    /// Custom profiles keyed by name
+    #[serde(flatten)]
+    pub custom: BTreeMap<String, Profile>,
 }
 
 // parsing logic for profiles
@@ -1,45 +1,87 @@
-use crate::{ VecStringOrBool };
+use crate::{ Edition, MaybeWorkspace, Resolver, RustVersion, VecStringOrBool };
 use crate::string_or_vec::StringOrVec;
 use crate::string_or_bool::StringOrBool;
 
 /// Represents the `package`/`project` sections of a `Cargo.toml`.
 ///
+/// Fields which may be inherited from `[workspace.package]` are wrapped in
+/// `MaybeWorkspace`.
+///
 /// Note that the order of the fields matters, since this is the order they
 /// are serialized to a TOML file. For example, you cannot have values after
 /// the field `metadata`, since it is a table and values cannot appear after
 /// tables.
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[derive(Deserialize, JsonSchema, Serialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct Project {
-    pub edition: Option<String>,
+    /// Edition the package is compiled with
+    pub edition: Option<MaybeWorkspace<Edition>>,
+    /// Oldest Rust version the package supports
+    pub rust_version: Option<MaybeWorkspace<RustVersion>>,
+    /// Name of the package
     pub name: String,
-    pub version: semver::Version,
-    pub authors: Option<Vec<String>>,
+    /// Version of the package, following semver
+    #[schemars(with = "MaybeWorkspace<String>")]
+    pub version: MaybeWorkspace<semver::Version>,
+    /// Authors of the package
+    pub authors: Option<MaybeWorkspace<Vec<String>>>,
+    /// Path to the build script, or `false` to disable detecting `build.rs`
     pub build: Option<StringOrBool>,
+    /// Unstable metabuild packages to run as the build script
     pub metabuild: Option<StringOrVec>,
+    /// Unstable target the package is always compiled for
+    pub forced_target: Option<String>,
+    /// Unstable target the package is compiled for when none is given
+    pub default_target: Option<String>,
+    /// Name of the native library the package links
     pub links: Option<String>,
-    pub exclude: Option<Vec<String>>,
-    pub include: Option<Vec<String>>,
-    pub publish: Option<VecStringOrBool>,
+    /// Gitignore style patterns of files to leave out of the package
+    pub exclude: Option<MaybeWorkspace<Vec<String>>>,
+    /// Gitignore style patterns of the only files to package
+    pub include: Option<MaybeWorkspace<Vec<String>>>,
+    /// Whether, or to which registries, the package may be published
+    pub publish: Option<MaybeWorkspace<VecStringOrBool>>,
+    /// Deprecated flag to include `Cargo.lock` in the package
     pub publish_lockfile: Option<bool>,
+    /// Path to the root of the workspace the package belongs to
     pub workspace: Option<String>,
     // im_a_teapot: Option<bool>,
+    /// Whether binary targets are discovered from `src/bin`
     pub autobins: Option<bool>,
+    /// Whether example targets are discovered from `examples`
     pub autoexamples: Option<bool>,
+    /// Whether test targets are discovered from `tests`
     pub autotests: Option<bool>,
+    /// Whether benchmark targets are discovered from `benches`
     pub autobenches: Option<bool>,
+    /// Unstable flag for the `dep:` feature syntax
     pub namespaced_features: Option<bool>,
+    /// Binary run by `cargo run` when there are several
     pub default_run: Option<String>,
+    /// Dependency resolver version of the package
+    pub resolver: Option<Resolver>,
 
     // Package metadata.
-    pub description: Option<String>,
-    pub homepage: Option<String>,
-    pub documentation: Option<String>,
-    pub readme: Option<String>,
-    pub keywords: Option<Vec<String>>,
-    pub categories: Option<Vec<String>>,
-    pub license: Option<String>,
-    pub license_file: Option<String>,
-    pub repository: Option<String>,
+    /// Short description of the package
+    pub description: Option<MaybeWorkspace<String>>,
+    /// URL of the home page of the package
+    pub homepage: Option<MaybeWorkspace<String>>,
+    /// URL of the documentation of the package
+    pub documentation: Option<MaybeWorkspace<String>>,
+    /// Path to the readme file, relative to the manifest
+    pub readme: Option<MaybeWorkspace<String>>,
+    /// Search keywords for the registry
+    pub keywords: Option<MaybeWorkspace<Vec<String>>>,
+    /// Registry category slugs
+    pub categories: Option<MaybeWorkspace<Vec<String>>>,
+    /// SPDX license expression
+    pub license: Option<MaybeWorkspace<String>>,
+    /// Path to a file holding a non-standard license
+    pub license_file: Option<MaybeWorkspace<String>>,
+    /// URL of the source repository
+    pub repository: Option<MaybeWorkspace<String>>,
+    /// Settings for external tools, which cargo ignores
+    #[schemars(with = "Option<serde_json::Map<String, serde_json::Value>>")]
     pub metadata: Option<toml::Value>,
 }
\ No newline at end of file
@@ -0,0 +1,86 @@
+use schemars::{ JsonSchema, Schema, SchemaGenerator };
+use serde::de;
+use serde::ser;
+use std::borrow::Cow;
+use std::fmt;
+
+/// The dependency `resolver` version of a package or workspace.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Resolver {
+    V1,
+    V2,
+    V3,
+}
+
+impl Resolver {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Resolver::V1 => "1",
+            Resolver::V2 => "2",
+            Resolver::V3 => "3",
+        }
+    }
+}
+
+impl fmt::Display for Resolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Resolver {
+    fn deserialize<D>(d: D) -> Result<Resolver, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Resolver;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a resolver version")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Resolver, E>
+            where
+                E: de::Error,
+            {
+                match value {
+                    "1" => Ok(Resolver::V1),
+                    "2" => Ok(Resolver::V2),
+                    "3" => Ok(Resolver::V3),
+                    _ => Err(E::custom(format!(
+                        "`resolver` setting `{}` is not valid, valid options are \"1\", \"2\" or \"3\"",
+                        value
+                    ))),
+                }
+            }
+        }
+
+        d.deserialize_str(Visitor)
+    }
+}
+
+impl ser::Serialize for Resolver {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
+
+impl JsonSchema for Resolver {
+    fn schema_name() -> Cow<'static, str> {
+        "Resolver".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "The dependency resolver version",
+            "type": "string",
+            "enum": ["1", "2", "3"],
+        })
+    }
+}
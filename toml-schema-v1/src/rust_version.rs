@@ -0,0 +1,103 @@
+use schemars::{ JsonSchema, Schema, SchemaGenerator };
+use serde::de;
+use serde::ser;
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+/// A `rust-version`, which is a partial semver version without pre-release
+/// or build metadata, e.g. `1.70` or `1.70.1`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RustVersion {
+    pub major: u64,
+    pub minor: Option<u64>,
+    pub patch: Option<u64>,
+}
+
+impl FromStr for RustVersion {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<RustVersion, String> {
+        let expected = || format!("expected a version like \"1.32\" but found `{}`", value);
+        if value.contains('-') || value.contains('+') {
+            return Err(format!("unexpected pre-release or build metadata in `{}`, {}", value, expected()));
+        }
+        let parts = value.split('.')
+            .map(|part| {
+                if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+                    Err(expected())
+                } else {
+                    part.parse::<u64>().map_err(|_| expected())
+                }
+            })
+            .collect::<Result<Vec<u64>, String>>()?;
+        match parts[..] {
+            [major] => Ok(RustVersion { major, minor: None, patch: None }),
+            [major, minor] => Ok(RustVersion { major, minor: Some(minor), patch: None }),
+            [major, minor, patch] => Ok(RustVersion { major, minor: Some(minor), patch: Some(patch) }),
+            _ => Err(expected()),
+        }
+    }
+}
+
+impl fmt::Display for RustVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{}", minor)?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{}", patch)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> de::Deserialize<'de> for RustVersion {
+    fn deserialize<D>(d: D) -> Result<RustVersion, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = RustVersion;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a rust version")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<RustVersion, E>
+            where
+                E: de::Error,
+            {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        d.deserialize_str(Visitor)
+    }
+}
+
+impl ser::Serialize for RustVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl JsonSchema for RustVersion {
+    fn schema_name() -> Cow<'static, str> {
+        "RustVersion".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A Rust version without pre-release or build metadata, e.g. `1.70`",
+            "type": "string",
+            "pattern": "^[0-9]+(\\.[0-9]+(\\.[0-9]+)?)?$",
+        })
+    }
+}
@@ -2,7 +2,7 @@ use serde::de;
 use serde::{ Serialize };
 use std::fmt;
 
-#[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+#[derive(Clone, Debug, JsonSchema, Serialize, Eq, PartialEq)]
 #[serde(untagged)]
 pub enum StringOrBool {
     String(String),
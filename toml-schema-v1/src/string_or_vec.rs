@@ -1,4 +1,6 @@
+use schemars::{ JsonSchema, Schema, SchemaGenerator };
 use serde::de;
+use std::borrow::Cow;
 use std::fmt;
 use serde::{ Deserialize };
 
@@ -37,4 +39,19 @@ impl<'de> de::Deserialize<'de> for StringOrVec {
 
         deserializer.deserialize_any(Visitor)
     }
-}
\ No newline at end of file
+}
+
+impl JsonSchema for StringOrVec {
+    fn schema_name() -> Cow<'static, str> {
+        "StringOrVec".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "anyOf": [
+                { "type": "string" },
+                { "type": "array", "items": { "type": "string" } },
+            ],
+        })
+    }
+}
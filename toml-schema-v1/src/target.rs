@@ -1,18 +1,38 @@
-use crate::{ PathValue };
+use crate::{ Edition, PathValue };
 
-#[derive(Default, Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A library, binary, example, test or benchmark target.
+#[derive(Default, Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct Target {
+    /// Name of the target
     pub name: Option<String>,
+    /// Kinds of crate the target builds, e.g. `"lib"`, `"cdylib"` or `"staticlib"`
     pub crate_type: Option<Vec<String>>,
+    /// Deprecated alias of `crate-type`
+    #[serde(rename = "crate_type")]
+    pub crate_type2: Option<Vec<String>>,
+    /// Path to the root source file of the target, relative to the manifest
     pub path: Option<PathValue>,
+    /// Whether the target is tested by `cargo test`
     pub test: Option<bool>,
+    /// Whether documentation examples are tested by `cargo test`
     pub doctest: Option<bool>,
+    /// Whether the target is benchmarked by `cargo bench`
     pub bench: Option<bool>,
+    /// Whether the target is documented by `cargo doc`
     pub doc: Option<bool>,
+    /// Deprecated flag for compiler plugins
     pub plugin: Option<bool>,
+    /// Builds the library as a procedural macro
     pub proc_macro: Option<bool>,
+    /// Deprecated alias of `proc-macro`
+    #[serde(rename = "proc_macro")]
+    pub proc_macro2: Option<bool>,
+    /// Uses the libtest harness to run tests and benchmarks
     pub harness: Option<bool>,
+    /// Features which must be enabled to build the target
     pub required_features: Option<Vec<String>>,
-    pub edition: Option<String>,
+    /// Edition the target is compiled with
+    pub edition: Option<Edition>,
 }
\ No newline at end of file
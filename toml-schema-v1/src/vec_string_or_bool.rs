@@ -2,7 +2,7 @@ use serde::de;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Clone, Debug, Serialize, PartialEq)]
+#[derive(Clone, Debug, JsonSchema, Serialize, PartialEq)]
 #[serde(untagged)]
 pub enum VecStringOrBool {
     VecString(Vec<String>),
@@ -1,7 +1,25 @@
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+use crate::{ DependencyMap, Lints, Resolver, WorkspacePackage };
+
+/// The `[workspace]` section.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct Workspace {
+    /// Paths or globs of the member packages
     pub members: Option<Vec<String>>,
+    /// Members used when no package is selected
     pub default_members: Option<Vec<String>>,
+    /// Paths excluded from the workspace
     pub exclude: Option<Vec<String>>,
-}
\ No newline at end of file
+    /// Dependency resolver version of the workspace
+    pub resolver: Option<Resolver>,
+    /// Values members may inherit with `{ workspace = true }`
+    pub package: Option<WorkspacePackage>,
+    /// Dependencies members may inherit with `{ workspace = true }`
+    pub dependencies: Option<DependencyMap>,
+    /// Lint levels members may inherit with `lints.workspace = true`
+    pub lints: Option<Lints>,
+    /// Settings for external tools, which cargo ignores
+    #[schemars(with = "Option<serde_json::Map<String, serde_json::Value>>")]
+    pub metadata: Option<toml::Value>,
+}
@@ -0,0 +1,475 @@
+use std::fmt;
+use std::path::{ Component, Path, PathBuf };
+
+use crate::{
+    Dependency, DependencyMap, DetailedDependency, Manifest, MaybeWorkspace, PackageLints, Project,
+    Workspace, WorkspacePackage,
+};
+
+/// Reason a `{ workspace = true }` value cannot be inherited
+#[derive(Clone, Debug, PartialEq)]
+pub enum InheritError {
+    /// The field is not set in `[workspace.package]`
+    MissingWorkspaceField(String),
+    /// The dependency is not declared in `[workspace.dependencies]`
+    MissingWorkspaceDependency(String),
+    /// `lints.workspace = true` without `[workspace.lints]`
+    MissingWorkspaceLints,
+    /// A dependency sets `workspace = false`
+    WorkspaceCannotBeFalse(String),
+    /// `lints.workspace = true` along with lints of the package
+    WorkspaceLintsOverridden,
+}
+
+impl fmt::Display for InheritError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InheritError::MissingWorkspaceField(field) => write!(
+                f,
+                "error inheriting `{}` from workspace root manifest's `workspace.package.{}`: `workspace.package.{}` was not defined",
+                field, field, field
+            ),
+            InheritError::MissingWorkspaceDependency(name) => write!(
+                f,
+                "error inheriting `{}` from workspace root manifest's `workspace.dependencies.{}`: `dependency.{}` was not found in `workspace.dependencies`",
+                name, name, name
+            ),
+            InheritError::MissingWorkspaceLints => write!(
+                f,
+                "error inheriting `lints` from workspace root manifest's `workspace.lints`: `workspace.lints` was not defined"
+            ),
+            InheritError::WorkspaceCannotBeFalse(name) => {
+                write!(f, "dependency `{}`: `workspace` cannot be false", name)
+            },
+            InheritError::WorkspaceLintsOverridden => write!(
+                f,
+                "cannot override `workspace.lints` in `lints`, either remove the overrides or `lints.workspace = true`"
+            ),
+        }
+    }
+}
+
+fn normalize(path: &Path) -> Vec<Component<'_>> {
+    let mut parts: Vec<Component> = vec![];
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => match parts.last() {
+                Some(Component::Normal(_)) => { parts.pop(); },
+                _ => parts.push(component),
+            },
+            _ => parts.push(component),
+        }
+    }
+    parts
+}
+
+/// Lexically computes the path to `target` from the directory `base`,
+/// where both are absolute or both relative to the same directory
+pub fn relative_path(base: &Path, target: &Path) -> PathBuf {
+    let base = normalize(base);
+    let target = normalize(target);
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    let mut path = PathBuf::new();
+    for _ in common..base.len() {
+        path.push("..");
+    }
+    for component in &target[common..] {
+        path.push(component.as_os_str());
+    }
+    path
+}
+
+/// Resolves workspace relative paths from the point of view of the member
+struct Paths<'a> {
+    workspace_root: &'a Path,
+    package_root: &'a Path,
+}
+
+impl<'a> Paths<'a> {
+    fn rebase(&self, path: &str) -> String {
+        relative_path(self.package_root, &self.workspace_root.join(path))
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+fn inherit_field<T: Clone>(
+    field: &str,
+    value: &Option<MaybeWorkspace<T>>,
+    inherited: Option<&T>,
+) -> Result<Option<MaybeWorkspace<T>>, InheritError> {
+    match value {
+        Some(MaybeWorkspace::Workspace(_)) => inherited
+            .map(|v| Some(MaybeWorkspace::Defined(v.to_owned())))
+            .ok_or_else(|| InheritError::MissingWorkspaceField(field.to_owned())),
+        other => Ok(other.to_owned()),
+    }
+}
+
+fn inherit_path(
+    field: &str,
+    value: &Option<MaybeWorkspace<String>>,
+    inherited: Option<&String>,
+    paths: &Paths,
+) -> Result<Option<MaybeWorkspace<String>>, InheritError> {
+    match value {
+        Some(MaybeWorkspace::Workspace(_)) => {
+            inherit_field(field, value, inherited.map(|path| paths.rebase(path)).as_ref())
+        },
+        other => Ok(other.to_owned()),
+    }
+}
+
+fn inherit_package(
+    package: &Project,
+    workspace: Option<&WorkspacePackage>,
+    paths: &Paths,
+) -> Result<Project, InheritError> {
+    let ws = workspace.cloned().unwrap_or_default();
+    Ok(Project {
+        version: inherit_field("version", &Some(package.version.to_owned()), ws.version.as_ref())?
+            .unwrap_or_else(|| package.version.to_owned()),
+        authors: inherit_field("authors", &package.authors, ws.authors.as_ref())?,
+        description: inherit_field("description", &package.description, ws.description.as_ref())?,
+        documentation: inherit_field("documentation", &package.documentation, ws.documentation.as_ref())?,
+        edition: inherit_field("edition", &package.edition, ws.edition.as_ref())?,
+        exclude: inherit_field("exclude", &package.exclude, ws.exclude.as_ref())?,
+        homepage: inherit_field("homepage", &package.homepage, ws.homepage.as_ref())?,
+        include: inherit_field("include", &package.include, ws.include.as_ref())?,
+        keywords: inherit_field("keywords", &package.keywords, ws.keywords.as_ref())?,
+        categories: inherit_field("categories", &package.categories, ws.categories.as_ref())?,
+        license: inherit_field("license", &package.license, ws.license.as_ref())?,
+        license_file: inherit_path("license-file", &package.license_file, ws.license_file.as_ref(), paths)?,
+        publish: inherit_field("publish", &package.publish, ws.publish.as_ref())?,
+        readme: inherit_path("readme", &package.readme, ws.readme.as_ref(), paths)?,
+        repository: inherit_field("repository", &package.repository, ws.repository.as_ref())?,
+        rust_version: inherit_field("rust-version", &package.rust_version, ws.rust_version.as_ref())?,
+        .. package.to_owned()
+    })
+}
+
+/// Merges a member's `{ workspace = true, .. }` dependency into the
+/// dependency declared by the workspace.
+///
+/// Features are added to the workspace's, `optional` comes from the member
+/// and the member may only turn `default-features` on.
+fn inherit_dependency(
+    name: &str,
+    member: &DetailedDependency,
+    workspace: &DependencyMap,
+    paths: &Paths,
+) -> Result<Dependency, InheritError> {
+    let mut dep = match workspace.get(name) {
+        Some(Dependency::Simple(version)) => Box::new(DetailedDependency {
+            version: Some(version.to_owned()),
+            .. Default::default()
+        }),
+        Some(Dependency::Detailed(detailed)) => detailed.to_owned(),
+        None => return Err(InheritError::MissingWorkspaceDependency(name.to_owned())),
+    };
+    dep.path = dep.path.map(|path| paths.rebase(&path));
+    if let Some(features) = &member.features {
+        let mut all = dep.features.unwrap_or_default();
+        all.extend(features.iter().filter(|f| !all.contains(f)).cloned().collect::<Vec<_>>());
+        dep.features = Some(all);
+    }
+    if member.optional.is_some() {
+        dep.optional = member.optional;
+    }
+    if member.default_features.or(member.default_features2) == Some(true) {
+        dep.default_features = Some(true);
+        dep.default_features2 = None;
+    }
+    if member.public.is_some() {
+        dep.public = member.public;
+    }
+    dep.workspace = None;
+    Ok(Dependency::Detailed(dep))
+}
+
+fn inherit_dependencies(
+    deps: &Option<DependencyMap>,
+    workspace: &DependencyMap,
+    paths: &Paths,
+) -> Result<Option<DependencyMap>, InheritError> {
+    let deps = match deps {
+        Some(deps) => deps,
+        None => return Ok(None),
+    };
+    deps.iter()
+        .map(|(name, dep)| match dep {
+            Dependency::Detailed(detailed) => match detailed.workspace {
+                Some(true) => inherit_dependency(name, detailed, workspace, paths).map(|d| (name.to_owned(), d)),
+                Some(false) => Err(InheritError::WorkspaceCannotBeFalse(name.to_owned())),
+                None => Ok((name.to_owned(), dep.to_owned())),
+            },
+            Dependency::Simple(_) => Ok((name.to_owned(), dep.to_owned())),
+        })
+        .collect::<Result<_, _>>()
+        .map(|deps| Some(DependencyMap(deps)))
+}
+
+fn inherit_lints(lints: &Option<PackageLints>, workspace: &Workspace) -> Result<Option<PackageLints>, InheritError> {
+    match lints {
+        Some(lints) if lints.workspace == Some(true) => {
+            if !lints.tools.is_empty() {
+                return Err(InheritError::WorkspaceLintsOverridden);
+            }
+            let tools = workspace.lints.to_owned().ok_or(InheritError::MissingWorkspaceLints)?;
+            Ok(Some(PackageLints { workspace: None, tools }))
+        },
+        other => Ok(other.to_owned()),
+    }
+}
+
+/// Replaces every `{ workspace = true }` value of `manifest` with the value
+/// inherited from `workspace`.
+///
+/// Inherited `readme`, `license-file` and dependency `path` values are
+/// relative to `workspace_root` and are rewritten relative to
+/// `package_root`, the directory of `manifest`.
+pub fn inherit_workspace(
+    manifest: &Manifest,
+    workspace: &Workspace,
+    workspace_root: &Path,
+    package_root: &Path,
+) -> Result<Manifest, InheritError> {
+    let paths = Paths { workspace_root, package_root };
+    let ws_deps = workspace.dependencies.clone().unwrap_or_default();
+    let inherit_package = |package: &Option<Project>| match package {
+        Some(package) => inherit_package(package, workspace.package.as_ref(), &paths).map(Some),
+        None => Ok(None),
+    };
+
+    let mut target = manifest.target.to_owned();
+    for platform in target.iter_mut().flat_map(|t| t.values_mut()) {
+        platform.dependencies = inherit_dependencies(&platform.dependencies, &ws_deps, &paths)?;
+        platform.dev_dependencies = inherit_dependencies(&platform.dev_dependencies, &ws_deps, &paths)?;
+        platform.build_dependencies = inherit_dependencies(&platform.build_dependencies, &ws_deps, &paths)?;
+        platform.dev_dependencies2 = inherit_dependencies(&platform.dev_dependencies2, &ws_deps, &paths)?;
+        platform.build_dependencies2 = inherit_dependencies(&platform.build_dependencies2, &ws_deps, &paths)?;
+    }
+
+    Ok(Manifest {
+        package: inherit_package(&manifest.package)?,
+        project: inherit_package(&manifest.project)?,
+        dependencies: inherit_dependencies(&manifest.dependencies, &ws_deps, &paths)?,
+        dev_dependencies: inherit_dependencies(&manifest.dev_dependencies, &ws_deps, &paths)?,
+        build_dependencies: inherit_dependencies(&manifest.build_dependencies, &ws_deps, &paths)?,
+        dev_dependencies2: inherit_dependencies(&manifest.dev_dependencies2, &ws_deps, &paths)?,
+        build_dependencies2: inherit_dependencies(&manifest.build_dependencies2, &ws_deps, &paths)?,
+        target,
+        lints: inherit_lints(&manifest.lints, workspace)?,
+        .. manifest.to_owned()
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path, PathBuf };
+
+    use semver;
+    use toml;
+
+    use crate::{ Dependency, DetailedDependency, Lint, LintLevel, Manifest, MaybeWorkspace, Workspace };
+    use super::{ inherit_workspace, relative_path, InheritError };
+
+    fn workspace() -> Workspace {
+        toml::from_str::<Manifest>(r#"
+            [workspace.package]
+            version = "1.2.3"
+            license = "MIT"
+            readme = "README.md"
+
+            [workspace.dependencies]
+            serde = { version = "1.0", features = ["derive"], default-features = false }
+            bar = { path = "crates/bar", version = "0.1" }
+            log = "0.4"
+
+            [workspace.lints.rust]
+            unsafe_code = "forbid"
+
+            [workspace.lints.clippy]
+            pedantic = { level = "warn", priority = -1 }
+        "#).unwrap().workspace.unwrap()
+    }
+
+    fn member(data: &str) -> Manifest {
+        toml::from_str(data).unwrap()
+    }
+
+    fn inherit(manifest: &Manifest) -> Result<Manifest, InheritError> {
+        inherit_workspace(manifest, &workspace(), Path::new("/ws"), Path::new("/ws/crates/foo"))
+    }
+
+    #[test]
+    fn compute_relative_paths() {
+        assert_eq!(PathBuf::from("../../README.md"), relative_path(Path::new("/ws/crates/foo"), Path::new("/ws/README.md")));
+        assert_eq!(PathBuf::from("../bar"), relative_path(Path::new("/ws/crates/foo"), Path::new("/ws/./crates/bar")));
+        assert_eq!(PathBuf::from("src"), relative_path(Path::new("a"), Path::new("a/b/../src")));
+    }
+
+    #[test]
+    fn inherit_package_fields() {
+        let manifest = member(r#"
+            [package]
+            name = "foo"
+            version.workspace = true
+            license = { workspace = true }
+            readme.workspace = true
+            description = "Foo"
+        "#);
+        let package = inherit(&manifest).unwrap().package.unwrap();
+        assert_eq!(MaybeWorkspace::Defined(semver::Version::parse("1.2.3").unwrap()), package.version);
+        assert_eq!(Some(MaybeWorkspace::Defined("MIT".to_owned())), package.license);
+        assert_eq!(Some(MaybeWorkspace::Defined("../../README.md".to_owned())), package.readme);
+        assert_eq!(Some(MaybeWorkspace::Defined("Foo".to_owned())), package.description);
+    }
+
+    #[test]
+    fn fail_to_inherit_undefined_field() {
+        let manifest = member(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            homepage.workspace = true
+        "#);
+        assert_eq!(Err(InheritError::MissingWorkspaceField("homepage".to_owned())), inherit(&manifest));
+    }
+
+    #[test]
+    fn inherit_dependencies() {
+        let manifest = member(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [dependencies]
+            serde = { workspace = true, features = ["rc"], optional = true }
+            bar.workspace = true
+
+            [target.'cfg(unix)'.dev-dependencies]
+            log = { workspace = true }
+        "#);
+        let manifest = inherit(&manifest).unwrap();
+        let deps = manifest.dependencies.unwrap();
+        assert_eq!(Some(&Dependency::Detailed(Box::new(DetailedDependency {
+            version: Some("1.0".to_owned()),
+            features: Some(vec!["derive".to_owned(), "rc".to_owned()]),
+            default_features: Some(false),
+            optional: Some(true),
+            .. Default::default()
+        }))), deps.get("serde"));
+        assert_eq!(Some(&Dependency::Detailed(Box::new(DetailedDependency {
+            version: Some("0.1".to_owned()),
+            path: Some("../bar".to_owned()),
+            .. Default::default()
+        }))), deps.get("bar"));
+        let unix = &manifest.target.unwrap()["cfg(unix)"];
+        assert_eq!(Some(&Dependency::Detailed(Box::new(DetailedDependency {
+            version: Some("0.4".to_owned()),
+            .. Default::default()
+        }))), unix.dev_dependencies.as_ref().unwrap().get("log"));
+    }
+
+    #[test]
+    fn inherit_dependencies_of_legacy_tables() {
+        let manifest = member(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [dev_dependencies]
+            serde = { workspace = true, default_features = true }
+        "#);
+        let manifest = inherit(&manifest).unwrap();
+        assert_eq!(Some(&Dependency::Detailed(Box::new(DetailedDependency {
+            version: Some("1.0".to_owned()),
+            features: Some(vec!["derive".to_owned()]),
+            default_features: Some(true),
+            .. Default::default()
+        }))), manifest.dev_dependencies2.unwrap().get("serde"));
+    }
+
+    #[test]
+    fn fail_to_inherit_missing_dependency() {
+        let manifest = member(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [build-dependencies]
+            cc.workspace = true
+        "#);
+        assert_eq!(Err(InheritError::MissingWorkspaceDependency("cc".to_owned())), inherit(&manifest));
+    }
+
+    #[test]
+    fn reject_workspace_false_package_field() {
+        let result = toml::from_str::<Manifest>(r#"
+            [package]
+            name = "foo"
+            version.workspace = false
+        "#);
+        match result {
+            Ok (_) => assert!(false, "`workspace = false` should not deserialize"),
+            Err (err) => assert!(err.to_string().contains("`workspace` cannot be false"), "unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn inherit_lints() {
+        let manifest = member(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [lints]
+            workspace = true
+        "#);
+        let lints = inherit(&manifest).unwrap().lints.unwrap();
+        assert_eq!(None, lints.workspace);
+        assert_eq!(Some(&Lint::Level(LintLevel::Forbid)), lints.tools["rust"].get("unsafe_code"));
+        assert_eq!(-1, lints.tools["clippy"]["pedantic"].priority());
+
+        let own = member(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [lints.rust]
+            missing_docs = "warn"
+        "#);
+        assert_eq!(own.lints, inherit(&own).unwrap().lints);
+    }
+
+    #[test]
+    fn fail_to_inherit_overridden_or_missing_lints() {
+        let manifest = member(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [lints]
+            workspace = true
+            rust.missing_docs = "warn"
+        "#);
+        assert_eq!(Err(InheritError::WorkspaceLintsOverridden), inherit(&manifest));
+
+        let manifest = member(r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [lints]
+            workspace = true
+        "#);
+        let workspace = Workspace::default();
+        assert_eq!(
+            Err(InheritError::MissingWorkspaceLints),
+            inherit_workspace(&manifest, &workspace, Path::new("/ws"), Path::new("/ws/crates/foo")),
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{ Edition, RustVersion, VecStringOrBool };
+
+/// The `[workspace.package]` section, holding values members may inherit
+/// with `{ workspace = true }`.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct WorkspacePackage {
+    /// Version of the members, following semver
+    #[schemars(with = "Option<String>")]
+    pub version: Option<semver::Version>,
+    /// Authors of the members
+    pub authors: Option<Vec<String>>,
+    /// Short description of the members
+    pub description: Option<String>,
+    /// URL of the documentation of the members
+    pub documentation: Option<String>,
+    /// Edition the members are compiled with
+    pub edition: Option<Edition>,
+    /// Gitignore style patterns of files to leave out of packages
+    pub exclude: Option<Vec<String>>,
+    /// URL of the home page of the members
+    pub homepage: Option<String>,
+    /// Gitignore style patterns of the only files to package
+    pub include: Option<Vec<String>>,
+    /// Search keywords for the registry
+    pub keywords: Option<Vec<String>>,
+    /// Registry category slugs
+    pub categories: Option<Vec<String>>,
+    /// SPDX license expression
+    pub license: Option<String>,
+    /// Path to a file holding a non-standard license, relative to the workspace root
+    pub license_file: Option<String>,
+    /// Whether, or to which registries, members may be published
+    pub publish: Option<VecStringOrBool>,
+    /// Path to the readme file, relative to the workspace root
+    pub readme: Option<String>,
+    /// URL of the source repository
+    pub repository: Option<String>,
+    /// Oldest Rust version the members support
+    pub rust_version: Option<RustVersion>,
+}
@@ -0,0 +1,151 @@
+use semver::Version;
+
+use crate::{ Error, Result };
+
+/// A locked package
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphPackage {
+    pub name: String,
+    pub version: Version,
+    /// Lockfile source, which is `None` for path packages
+    pub source: Option<String>,
+}
+
+impl GraphPackage {
+    /// The source where it is worth showing, that is for anything but
+    /// crates.io and local paths
+    pub fn display_source(&self) -> Option<&str> {
+        self.source.as_deref().filter(|s| !s.ends_with("github.com/rust-lang/crates.io-index") && !s.ends_with("index.crates.io/"))
+    }
+}
+
+/// A dependency of one package on another as its manifest declares it
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphEdge {
+    /// Index of the dependent package
+    pub from: usize,
+    /// Index of the package depended on
+    pub to: usize,
+    pub kind: v1::DependencyKind,
+    /// `[target.*]` table of the declaration
+    pub platform: Option<String>,
+    /// Features the declaration enables
+    pub features: Vec<String>,
+}
+
+/// The packages of a lockfile and the dependencies between them, which
+/// take their kind, platform and features from the manifests of the
+/// dependent packages.
+///
+/// Dependencies of packages without a manifest are taken as normal ones.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DependencyGraph {
+    pub packages: Vec<GraphPackage>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// The edges one locked dependency stands for
+fn declared_edges(from: usize, to: usize, dep: &lockfile::LockedPackage, manifest: Option<&v1::Project>) -> Vec<GraphEdge> {
+    let mut edges: Vec<GraphEdge> = vec![];
+    let declarations = manifest.into_iter()
+        .flat_map(|m| &m.dependencies)
+        .filter(|d| d.package_name() == dep.name && d.version.as_ref().is_none_or(|req| req.matches(&dep.version)));
+    for declaration in declarations {
+        // Declarations of the same kind and platform are merged
+        match edges.iter_mut().find(|e| e.kind == declaration.kind && e.platform == declaration.platform) {
+            Some(edge) => {
+                for feature in &declaration.features {
+                    if !edge.features.contains(feature) {
+                        edge.features.push(feature.to_owned());
+                    }
+                }
+            },
+            None => edges.push(GraphEdge {
+                from,
+                to,
+                kind: declaration.kind,
+                platform: declaration.platform.to_owned(),
+                features: declaration.features.to_owned(),
+            }),
+        }
+    }
+    if edges.is_empty() {
+        edges.push(GraphEdge { from, to, kind: v1::DependencyKind::Normal, platform: None, features: vec![] });
+    }
+    edges
+}
+
+impl DependencyGraph {
+    /// Builds the graph of `lockfile`, where `manifests` holds the
+    /// manifests of the locked packages which are known
+    pub fn new(lockfile: &lockfile::Lockfile, manifests: &[v1::Project]) -> DependencyGraph {
+        let packages = lockfile.packages.iter()
+            .map(|p| GraphPackage { name: p.name.to_owned(), version: p.version.to_owned(), source: p.source.to_owned() })
+            .collect();
+        let mut edges = vec![];
+        for (from, package) in lockfile.packages.iter().enumerate() {
+            let manifest = manifests.iter().find(|m| m.name == package.name && m.version == package.version);
+            for dep in lockfile.dependencies_of(package) {
+                let to = lockfile.packages.iter().position(|p| p == dep).unwrap_or_default();
+                edges.extend(declared_edges(from, to, dep, manifest));
+            }
+        }
+        DependencyGraph { packages, edges }
+    }
+
+    /// Path packages, which include the members of the workspace
+    pub fn members(&self) -> Vec<usize> {
+        (0..self.packages.len()).filter(|i| self.packages[*i].source.is_none()).collect()
+    }
+
+    /// Packages matching `name` or `name@version`
+    pub fn find(&self, spec: &str) -> Result<Vec<usize>> {
+        let (name, version) = match spec.find('@') {
+            Some(at) => (&spec[..at], Some(&spec[at + 1..])),
+            None => (spec, None),
+        };
+        let found: Vec<_> = (0..self.packages.len())
+            .filter(|i| {
+                let package = &self.packages[*i];
+                package.name == name && version.is_none_or(|v| package.version.to_string() == v)
+            })
+            .collect();
+        if found.is_empty() {
+            return Err(Error::UnknownPackage(spec.to_owned()));
+        }
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use crate::{ Error };
+    use crate::tree::tests::{ graph };
+
+    #[test]
+    fn take_edges_from_manifests() {
+        let graph = graph();
+        let edges: Vec<_> = graph.edges.iter()
+            .filter(|e| graph.packages[e.from].name == "app")
+            .map(|e| (graph.packages[e.to].name.as_str(), e.kind, e.platform.as_deref(), e.features.join(",")))
+            .collect();
+        assert_eq!(vec![
+            ("cc", v1::DependencyKind::Build, None, "".to_owned()),
+            ("libc", v1::DependencyKind::Normal, Some("cfg(unix)"), "".to_owned()),
+            ("rand", v1::DependencyKind::Development, None, "".to_owned()),
+            ("rand_core", v1::DependencyKind::Normal, None, "".to_owned()),
+            ("serde", v1::DependencyKind::Normal, None, "derive".to_owned()),
+            ("winapi", v1::DependencyKind::Normal, Some("cfg(windows)"), "winuser".to_owned()),
+        ], edges);
+    }
+
+    #[test]
+    fn find_packages_by_spec() {
+        let graph = graph();
+        assert_eq!(2, graph.find("rand_core").unwrap().len());
+        let found = graph.find("rand_core@0.6.4").unwrap();
+        assert_eq!(vec!["0.6.4".to_owned()], found.iter().map(|i| graph.packages[*i].version.to_string()).collect::<Vec<_>>());
+        assert_eq!(Err(Error::UnknownPackage("rand_core@0.7.0".to_owned())), graph.find("rand_core@0.7.0"));
+    }
+}
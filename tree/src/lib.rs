@@ -0,0 +1,33 @@
+extern crate omni_manifest_lockfile as lockfile;
+extern crate omni_manifest_v1 as v1;
+extern crate semver;
+#[macro_use]
+extern crate serde_json;
+
+mod dependency_graph;
+mod render;
+mod tree;
+
+pub use self::dependency_graph::*;
+pub use self::tree::*;
+
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// A `[target.*]` platform which cannot be evaluated
+    Platform(v1::Error),
+    /// No package of the graph matches a `name` or `name@version` spec
+    UnknownPackage(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Platform(err) => write!(f, "{}", err),
+            Error::UnknownPackage(spec) => write!(f, "package `{}` is not in the dependency graph", spec),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,245 @@
+use serde_json::{ Value };
+
+use crate::{ GraphEdge, Tree, TreeNode };
+use crate::tree::{ KINDS };
+
+/// Short label of an edge for graph formats, e.g. `build: derive`
+fn edge_label(edge: &GraphEdge) -> String {
+    let mut parts = vec![];
+    match edge.kind {
+        v1::DependencyKind::Normal => {},
+        v1::DependencyKind::Build => parts.push("build".to_owned()),
+        v1::DependencyKind::Development => parts.push("dev".to_owned()),
+    }
+    if !edge.features.is_empty() {
+        parts.push(edge.features.join(", "));
+    }
+    parts.join(": ")
+}
+
+impl Tree<'_> {
+    fn edge(&self, node: &TreeNode) -> Option<&GraphEdge> {
+        node.edge.map(|edge| &self.graph.edges[edge])
+    }
+
+    fn kind(&self, node: &TreeNode) -> v1::DependencyKind {
+        self.edge(node).map_or(v1::DependencyKind::Normal, |edge| edge.kind)
+    }
+
+    fn package_id(&self, package: usize) -> String {
+        let package = &self.graph.packages[package];
+        format!("{} v{}", package.name, package.version)
+    }
+
+    fn label(&self, node: &TreeNode) -> String {
+        let mut label = self.package_id(node.package);
+        if let Some(source) = self.graph.packages[node.package].display_source() {
+            label.push_str(&format!(" ({})", source));
+        }
+        if let Some(edge) = self.edge(node).filter(|edge| !edge.features.is_empty()) {
+            label.push_str(&format!(" (features: {})", edge.features.join(", ")));
+        }
+        if node.repeated {
+            label.push_str(" (*)");
+        }
+        label
+    }
+
+    fn write_children(&self, out: &mut String, node: &TreeNode, prefix: &str) {
+        for kind in &KINDS {
+            let children: Vec<_> = node.children.iter().filter(|child| self.kind(child) == *kind).collect();
+            if children.is_empty() {
+                continue;
+            }
+            if *kind != v1::DependencyKind::Normal {
+                out.push_str(&format!("{}[{}]\n", prefix, kind));
+            }
+            for (index, child) in children.iter().enumerate() {
+                let last = index == children.len() - 1;
+                out.push_str(&format!("{}{}{}\n", prefix, if last { "└── " } else { "├── " }, self.label(child)));
+                self.write_children(out, child, &format!("{}{}", prefix, if last { "    " } else { "│   " }));
+            }
+        }
+    }
+
+    /// `cargo tree` style text, with the dependencies of each kind other
+    /// than normal listed under a `[build-dependencies]` or
+    /// `[dev-dependencies]` heading and repeated packages marked `(*)`
+    pub fn to_text(&self) -> String {
+        let trees: Vec<_> = self.roots.iter()
+            .map(|root| {
+                let mut out = format!("{}\n", self.label(root));
+                self.write_children(&mut out, root, "");
+                out
+            })
+            .collect();
+        trees.join("\n")
+    }
+
+    fn node_json(&self, node: &TreeNode) -> Value {
+        let package = &self.graph.packages[node.package];
+        let edge = self.edge(node);
+        let children: Vec<_> = node.children.iter().map(|child| self.node_json(child)).collect();
+        let mut value = json!({
+            "name": package.name,
+            "version": package.version.to_string(),
+            "source": package.source,
+            "kind": edge.map(|edge| edge.kind.to_string()),
+            "platform": edge.and_then(|edge| edge.platform.to_owned()),
+            "features": edge.map_or_else(Vec::new, |edge| edge.features.to_owned()),
+            "repeated": node.repeated,
+        });
+        value[if self.inverted { "dependents" } else { "dependencies" }] = json!(children);
+        value
+    }
+
+    /// Nested objects where `kind`, `platform` and `features` describe the
+    /// dependency leading to the package
+    pub fn to_json(&self) -> Value {
+        json!(self.roots.iter().map(|root| self.node_json(root)).collect::<Vec<_>>())
+    }
+
+    /// Packages and dependency edges of the tree in the order they appear,
+    /// each one once
+    fn flatten(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut packages = vec![];
+        let mut edges = vec![];
+        let mut pending: Vec<&TreeNode> = self.roots.iter().rev().collect();
+        while let Some(node) = pending.pop() {
+            if !packages.contains(&node.package) {
+                packages.push(node.package);
+            }
+            if let Some(edge) = node.edge.filter(|edge| !edges.contains(edge)) {
+                edges.push(edge);
+            }
+            pending.extend(node.children.iter().rev());
+        }
+        (packages, edges)
+    }
+
+    /// A Graphviz digraph with an arrow from each package to the ones it
+    /// depends on, where build dependencies are dashed and development
+    /// dependencies dotted
+    pub fn to_dot(&self) -> String {
+        let (packages, edges) = self.flatten();
+        let mut out = "digraph dependencies {\n".to_owned();
+        for package in packages {
+            out.push_str(&format!("    \"{}\";\n", self.package_id(package)));
+        }
+        for edge in edges {
+            let edge = &self.graph.edges[edge];
+            let mut attributes = vec![];
+            let label = edge_label(edge);
+            if !label.is_empty() {
+                attributes.push(format!("label=\"{}\"", label));
+            }
+            match edge.kind {
+                v1::DependencyKind::Normal => {},
+                v1::DependencyKind::Build => attributes.push("style=dashed".to_owned()),
+                v1::DependencyKind::Development => attributes.push("style=dotted".to_owned()),
+            }
+            let attributes = if attributes.is_empty() { String::new() } else { format!(" [{}]", attributes.join(", ")) };
+            out.push_str(&format!(
+                "    \"{}\" -> \"{}\"{};\n", self.package_id(edge.from), self.package_id(edge.to), attributes
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// A Mermaid flowchart with an arrow from each package to the ones it
+    /// depends on, where build dependencies are thick and development
+    /// dependencies dotted
+    pub fn to_mermaid(&self) -> String {
+        let (packages, edges) = self.flatten();
+        let mut out = "graph TD\n".to_owned();
+        for package in packages {
+            out.push_str(&format!("    n{}[\"{}\"]\n", package, self.package_id(package)));
+        }
+        for edge in edges {
+            let edge = &self.graph.edges[edge];
+            let arrow = match edge.kind {
+                v1::DependencyKind::Normal => "-->",
+                v1::DependencyKind::Build => "==>",
+                v1::DependencyKind::Development => "-.->",
+            };
+            let label = edge_label(edge);
+            let label = if label.is_empty() { String::new() } else { format!("|{}|", label) };
+            out.push_str(&format!("    n{} {}{} n{}\n", edge.from, arrow, label, edge.to));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use crate::{ Tree, TreeOptions };
+    use crate::tree::tests::{ graph };
+
+    fn options() -> TreeOptions {
+        TreeOptions {
+            kinds: vec![v1::DependencyKind::Normal, v1::DependencyKind::Build],
+            target: Some(v1::TargetPlatform::from_triple("x86_64-unknown-linux-gnu")),
+        }
+    }
+
+    #[test]
+    fn render_json() {
+        let graph = graph();
+        let options = options();
+        let json = Tree::new(&graph, &graph.members(), &options).unwrap().to_json();
+        assert_eq!("app", json[0]["name"]);
+        assert_eq!(json!(null), json[0]["kind"]);
+        assert_eq!(json!({
+            "name": "libc",
+            "version": "0.2.150",
+            "source": "registry+https://github.com/rust-lang/crates.io-index",
+            "kind": "dependencies",
+            "platform": "cfg(unix)",
+            "features": [],
+            "repeated": false,
+            "dependencies": [],
+        }), json[0]["dependencies"][0]);
+        assert_eq!("build-dependencies", json[0]["dependencies"][3]["kind"]);
+
+        let inverted = Tree::inverted(&graph, "serde_derive", &options).unwrap().to_json();
+        assert_eq!("app", inverted[0]["dependents"][0]["dependents"][0]["name"]);
+    }
+
+    #[test]
+    fn render_dot() {
+        let graph = graph();
+        let options = options();
+        assert_eq!("\
+digraph dependencies {
+    \"app v0.1.0\";
+    \"libc v0.2.150\";
+    \"rand_core v0.5.1\";
+    \"serde v1.0.190\";
+    \"serde_derive v1.0.190\";
+    \"cc v1.0.83\";
+    \"app v0.1.0\" -> \"libc v0.2.150\";
+    \"app v0.1.0\" -> \"rand_core v0.5.1\";
+    \"app v0.1.0\" -> \"serde v1.0.190\" [label=\"derive\"];
+    \"serde v1.0.190\" -> \"serde_derive v1.0.190\";
+    \"app v0.1.0\" -> \"cc v1.0.83\" [label=\"build\", style=dashed];
+}
+", Tree::new(&graph, &graph.members(), &options).unwrap().to_dot());
+    }
+
+    #[test]
+    fn render_mermaid() {
+        let graph = graph();
+        let options = TreeOptions::default();
+        let tree = Tree::inverted(&graph, "rand_core@0.6.4", &options).unwrap();
+        assert_eq!("\
+graph TD
+    n5[\"rand_core v0.6.4\"]
+    n3[\"rand v0.8.5\"]
+    n0[\"app v0.1.0\"]
+    n3 -->|std| n5
+    n0 -.->|dev| n3
+", tree.to_mermaid());
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::{ BTreeMap, BTreeSet };
+
+use crate::{ DependencyGraph, GraphEdge, Error, Result };
+
+/// Order children are listed in by the kind of their dependency
+pub(crate) const KINDS: [v1::DependencyKind; 3] = [
+    v1::DependencyKind::Normal,
+    v1::DependencyKind::Build,
+    v1::DependencyKind::Development,
+];
+
+/// Which dependencies a tree follows
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeOptions {
+    /// Kinds of dependencies to follow, where development dependencies are
+    /// only followed from workspace members as in a build
+    pub kinds: Vec<v1::DependencyKind>,
+    /// Platform `[target.*]` dependencies must apply to, or `None` to
+    /// follow them for every platform
+    pub target: Option<v1::TargetPlatform>,
+}
+
+impl Default for TreeOptions {
+    fn default() -> TreeOptions {
+        TreeOptions {
+            kinds: KINDS.to_vec(),
+            target: None,
+        }
+    }
+}
+
+/// A package in a tree, reached through `edge` from its parent
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeNode {
+    pub package: usize,
+    /// Index of the edge in the graph, which is `None` for roots
+    pub edge: Option<usize>,
+    pub children: Vec<TreeNode>,
+    /// The package was already expanded elsewhere in the tree, so its
+    /// children are left out
+    pub repeated: bool,
+}
+
+/// Packages laid out as trees from one or more roots, like `cargo tree`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tree<'a> {
+    pub graph: &'a DependencyGraph,
+    pub roots: Vec<TreeNode>,
+    /// Children are the packages depending on their parent, rather than
+    /// the ones it depends on
+    pub inverted: bool,
+}
+
+struct Builder<'a> {
+    graph: &'a DependencyGraph,
+    options: &'a TreeOptions,
+    inverted: bool,
+    members: Vec<usize>,
+    /// Packages the members depend on through the dependencies which are
+    /// followed, the members included, in breadth-first order
+    reachable: Vec<usize>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(graph: &'a DependencyGraph, options: &'a TreeOptions, inverted: bool) -> Result<Builder<'a>> {
+        let members = graph.members();
+        let mut builder = Builder { graph, options, inverted: false, members: members.to_owned(), reachable: members };
+        let mut next = 0;
+        while next < builder.reachable.len() {
+            for (_, child) in builder.child_edges(builder.reachable[next])? {
+                if !builder.reachable.contains(&child) {
+                    builder.reachable.push(child);
+                }
+            }
+            next += 1;
+        }
+        builder.inverted = inverted;
+        Ok(builder)
+    }
+
+    fn follows(&self, edge: &GraphEdge) -> Result<bool> {
+        if !self.options.kinds.contains(&edge.kind) {
+            return Ok(false);
+        }
+        if edge.kind == v1::DependencyKind::Development && !self.members.contains(&edge.from) {
+            return Ok(false);
+        }
+        match (&self.options.target, &edge.platform) {
+            (Some(target), Some(platform)) => target.matches(platform).map_err(Error::Platform),
+            _ => Ok(true),
+        }
+    }
+
+    /// Edges to the children of `package`, ordered by kind and by the
+    /// name and version of the child
+    fn child_edges(&self, package: usize) -> Result<Vec<(usize, usize)>> {
+        let mut edges = vec![];
+        for (index, edge) in self.graph.edges.iter().enumerate() {
+            let (parent, child) = if self.inverted { (edge.to, edge.from) } else { (edge.from, edge.to) };
+            if parent == package && (!self.inverted || self.reachable.contains(&child)) && self.follows(edge)? {
+                edges.push((index, child));
+            }
+        }
+        edges.sort_by_key(|(index, child)| {
+            let child = &self.graph.packages[*child];
+            let kind = KINDS.iter().position(|k| *k == self.graph.edges[*index].kind);
+            (kind, child.name.to_owned(), child.version.to_owned())
+        });
+        Ok(edges)
+    }
+
+    fn node(&self, package: usize, edge: Option<usize>, visited: &mut BTreeSet<usize>) -> Result<TreeNode> {
+        let edges = self.child_edges(package)?;
+        if !visited.insert(package) {
+            return Ok(TreeNode { package, edge, children: vec![], repeated: !edges.is_empty() });
+        }
+        let children = edges.into_iter()
+            .map(|(edge, child)| self.node(child, Some(edge), visited))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(TreeNode { package, edge, children, repeated: false })
+    }
+
+    fn tree(self, roots: &[usize]) -> Result<Tree<'a>> {
+        let roots = roots.iter()
+            .map(|root| self.node(*root, None, &mut BTreeSet::new()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Tree { graph: self.graph, roots, inverted: self.inverted })
+    }
+}
+
+impl<'a> Tree<'a> {
+    /// The dependencies of `roots`, such as the members of a workspace
+    pub fn new(graph: &'a DependencyGraph, roots: &[usize], options: &'a TreeOptions) -> Result<Tree<'a>> {
+        Builder::new(graph, options, false)?.tree(roots)
+    }
+
+    /// The packages depending on those matching `spec`, which is `name` or
+    /// `name@version`, up to the workspace members
+    pub fn inverted(graph: &'a DependencyGraph, spec: &str, options: &'a TreeOptions) -> Result<Tree<'a>> {
+        let roots = graph.find(spec)?;
+        Builder::new(graph, options, true)?.tree(&roots)
+    }
+
+    /// Inverted trees of every package which the workspace members depend
+    /// on in more than one version
+    pub fn duplicates(graph: &'a DependencyGraph, options: &'a TreeOptions) -> Result<Tree<'a>> {
+        let builder = Builder::new(graph, options, true)?;
+        let mut by_name: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for package in &builder.reachable {
+            by_name.entry(graph.packages[*package].name.to_owned()).or_default().push(*package);
+        }
+        let mut roots: Vec<_> = by_name.into_iter()
+            .filter(|(_, packages)| packages.len() > 1)
+            .flat_map(|(_, packages)| packages)
+            .collect();
+        roots.sort_by_key(|p| (graph.packages[*p].name.to_owned(), graph.packages[*p].version.to_owned()));
+        builder.tree(&roots)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+pub(crate) mod tests {
+    use semver::{ Version, VersionReq };
+
+    use super::{ Tree, TreeOptions };
+    use crate::{ DependencyGraph, Error };
+
+    const LOCKFILE: &str = r#"
+        version = 3
+
+        [[package]]
+        name = "app"
+        version = "0.1.0"
+        dependencies = ["cc", "libc", "rand", "rand_core 0.5.1", "serde", "winapi"]
+
+        [[package]]
+        name = "cc"
+        version = "1.0.83"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "libc"
+        version = "0.2.150"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "rand"
+        version = "0.8.5"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        dependencies = ["libc", "rand_core 0.6.4"]
+
+        [[package]]
+        name = "rand_core"
+        version = "0.5.1"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "rand_core"
+        version = "0.6.4"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "serde"
+        version = "1.0.190"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        dependencies = ["serde_derive"]
+
+        [[package]]
+        name = "serde_derive"
+        version = "1.0.190"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "winapi"
+        version = "0.3.9"
+        source = "git+https://github.com/retep998/winapi-rs?branch=0.3#5b1829956ef645f3c2f8236ba18bb198ca4c2468"
+    "#;
+
+    fn dependency(name: &str, kind: v1::DependencyKind, req: &str, platform: Option<&str>, features: &[&str]) -> v1::DeclaredDependency {
+        v1::DeclaredDependency {
+            platform: platform.map(|p| p.to_owned()),
+            features: features.iter().map(|f| f.to_string()).collect(),
+            .. v1::DeclaredDependency::registry(name, kind, VersionReq::parse(req).unwrap())
+        }
+    }
+
+    fn project(name: &str, version: &str, dependencies: Vec<v1::DeclaredDependency>) -> v1::Project {
+        v1::Project { dependencies, .. v1::Project::new(name, Version::parse(version).unwrap()) }
+    }
+
+    pub(crate) fn graph() -> DependencyGraph {
+        use v1::DependencyKind::*;
+        let lockfile = lockfile::parse_lockfile(LOCKFILE).unwrap();
+        let manifests = vec![
+            project("app", "0.1.0", vec![
+                dependency("serde", Normal, "1.0", None, &["derive"]),
+                dependency("rand_core", Normal, "0.5", None, &[]),
+                dependency("libc", Normal, "0.2", Some("cfg(unix)"), &[]),
+                dependency("winapi", Normal, "0.3", Some("cfg(windows)"), &["winuser"]),
+                dependency("cc", Build, "1.0", None, &[]),
+                dependency("rand", Development, "0.8", None, &[]),
+            ]),
+            project("rand", "0.8.5", vec![
+                dependency("libc", Normal, "0.2", Some("cfg(unix)"), &[]),
+                dependency("rand_core", Normal, "0.6", None, &["std"]),
+            ]),
+        ];
+        DependencyGraph::new(&lockfile, &manifests)
+    }
+
+    fn windows() -> TreeOptions {
+        TreeOptions {
+            kinds: vec![v1::DependencyKind::Normal],
+            target: Some(v1::TargetPlatform::from_triple("x86_64-pc-windows-msvc")),
+        }
+    }
+
+    #[test]
+    fn render_tree() {
+        let graph = graph();
+        let options = TreeOptions::default();
+        match Tree::new(&graph, &graph.members(), &options) {
+            Ok (tree) => assert_eq!("\
+app v0.1.0
+├── libc v0.2.150
+├── rand_core v0.5.1
+├── serde v1.0.190 (features: derive)
+│   └── serde_derive v1.0.190
+└── winapi v0.3.9 (git+https://github.com/retep998/winapi-rs?branch=0.3#5b1829956ef645f3c2f8236ba18bb198ca4c2468) (features: winuser)
+[build-dependencies]
+└── cc v1.0.83
+[dev-dependencies]
+└── rand v0.8.5
+    ├── libc v0.2.150
+    └── rand_core v0.6.4 (features: std)
+", tree.to_text()),
+            Err (err) => assert!(false, "unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn filter_by_kind_and_platform() {
+        let graph = graph();
+        let options = windows();
+        let tree = Tree::new(&graph, &graph.members(), &options).unwrap();
+        assert_eq!("\
+app v0.1.0
+├── rand_core v0.5.1
+├── serde v1.0.190 (features: derive)
+│   └── serde_derive v1.0.190
+└── winapi v0.3.9 (git+https://github.com/retep998/winapi-rs?branch=0.3#5b1829956ef645f3c2f8236ba18bb198ca4c2468) (features: winuser)
+", tree.to_text());
+    }
+
+    #[test]
+    fn invert_tree() {
+        let graph = graph();
+        let options = TreeOptions::default();
+        let tree = Tree::inverted(&graph, "libc", &options).unwrap();
+        assert_eq!("\
+libc v0.2.150
+├── app v0.1.0
+└── rand v0.8.5
+    [dev-dependencies]
+    └── app v0.1.0
+", tree.to_text());
+
+        let linux = TreeOptions { target: Some(v1::TargetPlatform::from_triple("x86_64-unknown-linux-gnu")), .. windows() };
+        let tree = Tree::inverted(&graph, "libc", &linux).unwrap();
+        assert_eq!("libc v0.2.150\n└── app v0.1.0\n", tree.to_text());
+
+        match Tree::inverted(&graph, "openssl", &options) {
+            Ok (_) => assert!(false, "should fail on an unknown package"),
+            Err (err) => assert_eq!(Error::UnknownPackage("openssl".to_owned()), err),
+        }
+    }
+
+    #[test]
+    fn list_duplicates() {
+        let graph = graph();
+        let options = TreeOptions::default();
+        let tree = Tree::duplicates(&graph, &options).unwrap();
+        assert_eq!("\
+rand_core v0.5.1
+└── app v0.1.0
+
+rand_core v0.6.4
+└── rand v0.8.5 (features: std)
+    [dev-dependencies]
+    └── app v0.1.0
+", tree.to_text());
+
+        let options = TreeOptions { kinds: vec![v1::DependencyKind::Normal], target: None };
+        assert_eq!("", Tree::duplicates(&graph, &options).unwrap().to_text());
+    }
+
+    #[test]
+    fn fail_on_invalid_platform() {
+        let mut graph = graph();
+        graph.edges[1].platform = Some("cfg(unix".to_owned());
+        let options = windows();
+        match Tree::new(&graph, &graph.members(), &options) {
+            Ok (_) => assert!(false, "should fail on an invalid platform"),
+            Err (Error::Platform(v1::Error::InvalidCfg(cfg, _))) => assert_eq!("cfg(unix", cfg),
+            Err (err) => assert!(false, "expected Platform: {}", err),
+        }
+    }
+}
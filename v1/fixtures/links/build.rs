@@ -0,0 +1,4 @@
+fn main() {
+    println!("cargo:rustc-link-lib=git2");
+    println!("cargo:root=/usr");
+}
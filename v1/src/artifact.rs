@@ -0,0 +1,144 @@
+use std::fmt;
+
+use crate::links::{ envify };
+use crate::{ Error, Result };
+
+/// An artifact of a dependency a package asks for with `artifact = ..`
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ArtifactKind {
+    /// `bin`, every binary of the dependency
+    AllBinaries,
+    /// `bin:<name>`
+    Binary(String),
+    Cdylib,
+    Staticlib,
+}
+
+impl fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArtifactKind::AllBinaries => write!(f, "bin"),
+            ArtifactKind::Binary(name) => write!(f, "bin:{}", name),
+            ArtifactKind::Cdylib => write!(f, "cdylib"),
+            ArtifactKind::Staticlib => write!(f, "staticlib"),
+        }
+    }
+}
+
+impl ArtifactKind {
+    pub fn parse(kind: &str) -> Result<ArtifactKind> {
+        match kind {
+            "bin" => Ok(ArtifactKind::AllBinaries),
+            "cdylib" => Ok(ArtifactKind::Cdylib),
+            "staticlib" => Ok(ArtifactKind::Staticlib),
+            _ => match kind.strip_prefix("bin:") {
+                Some(name) if !name.trim().is_empty() => Ok(ArtifactKind::Binary(name.to_owned())),
+                _ => Err(Error::InvalidArtifactKind(kind.to_owned())),
+            },
+        }
+    }
+
+    /// `BIN`, `CDYLIB` or `STATICLIB`, as used in environment variables
+    pub fn env_type(&self) -> &'static str {
+        match self {
+            ArtifactKind::AllBinaries | ArtifactKind::Binary(_) => "BIN",
+            ArtifactKind::Cdylib => "CDYLIB",
+            ArtifactKind::Staticlib => "STATICLIB",
+        }
+    }
+}
+
+/// The `artifact`, `lib` and `target` keys of an artifact dependency
+#[derive(Clone, Debug, PartialEq)]
+pub struct Artifact {
+    pub kinds: Vec<ArtifactKind>,
+    /// Whether the library of the dependency can be used as well
+    pub lib: bool,
+    /// Triple to build the artifacts for, or `target` for the target of the
+    /// dependent package
+    pub target: Option<String>,
+}
+
+impl Artifact {
+    /// Names of the variables cargo sets for the dependent package to find
+    /// the artifacts of the dependency declared as `dep`, given the name,
+    /// binary targets and library name of the package depended on.
+    ///
+    /// There is a `CARGO_<TYPE>_DIR_<DEP>` per artifact type and a
+    /// `CARGO_<TYPE>_FILE_<DEP>_<NAME>` per artifact, where the name is kept
+    /// as is, e.g. `CARGO_BIN_FILE_MY_TOOL_my-tool`. An artifact named after
+    /// the package also gets a `CARGO_<TYPE>_FILE_<DEP>`.
+    pub fn env_vars(&self, dep: &str, package: &str, binaries: &[String], lib: &str) -> Vec<String> {
+        let dep = envify(dep);
+        let mut vars = vec![];
+        let mut add = |var: String| if !vars.contains(&var) { vars.push(var) };
+        for kind in &self.kinds {
+            add(format!("CARGO_{}_DIR_{}", kind.env_type(), dep));
+            let names = match kind {
+                ArtifactKind::AllBinaries => binaries.to_owned(),
+                ArtifactKind::Binary(name) => vec![name.to_owned()],
+                ArtifactKind::Cdylib | ArtifactKind::Staticlib => vec![lib.to_owned()],
+            };
+            for name in names {
+                if name == package {
+                    add(format!("CARGO_{}_FILE_{}", kind.env_type(), dep));
+                }
+                add(format!("CARGO_{}_FILE_{}_{}", kind.env_type(), dep, name));
+            }
+        }
+        vars
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use super::{ Artifact, ArtifactKind };
+    use crate::{ Error };
+
+    #[test]
+    fn parse_artifact_kinds() {
+        let cases = vec![
+            ("bin", ArtifactKind::AllBinaries),
+            ("bin:my-tool", ArtifactKind::Binary("my-tool".to_owned())),
+            ("cdylib", ArtifactKind::Cdylib),
+            ("staticlib", ArtifactKind::Staticlib),
+        ];
+        for (kind, expected) in cases {
+            assert_eq!(Ok(expected.to_owned()), ArtifactKind::parse(kind));
+            assert_eq!(kind, expected.to_string());
+        }
+        for kind in ["bin:", "lib", "dylib", "BIN"].iter() {
+            assert_eq!(Err(Error::InvalidArtifactKind(kind.to_string())), ArtifactKind::parse(kind));
+        }
+    }
+
+    #[test]
+    fn compute_env_vars() {
+        let artifact = Artifact {
+            kinds: vec![ArtifactKind::AllBinaries, ArtifactKind::Binary("helper".to_owned()), ArtifactKind::Cdylib],
+            lib: false,
+            target: None,
+        };
+        let binaries = vec!["my-tool".to_owned(), "helper".to_owned()];
+        assert_eq!(vec![
+            "CARGO_BIN_DIR_MY_TOOL",
+            "CARGO_BIN_FILE_MY_TOOL_my-tool",
+            "CARGO_BIN_FILE_MY_TOOL_helper",
+            "CARGO_CDYLIB_DIR_MY_TOOL",
+            "CARGO_CDYLIB_FILE_MY_TOOL_my_tool",
+        ], artifact.env_vars("my-tool", "my-tool-impl", &binaries, "my_tool"));
+    }
+
+    #[test]
+    fn compute_env_vars_of_binary_named_after_package() {
+        let artifact = Artifact { kinds: vec![ArtifactKind::AllBinaries], lib: false, target: None };
+        let binaries = vec!["tool".to_owned(), "helper".to_owned()];
+        assert_eq!(vec![
+            "CARGO_BIN_DIR_RENAMED",
+            "CARGO_BIN_FILE_RENAMED",
+            "CARGO_BIN_FILE_RENAMED_tool",
+            "CARGO_BIN_FILE_RENAMED_helper",
+        ], artifact.env_vars("renamed", "tool", &binaries, "tool"));
+    }
+}
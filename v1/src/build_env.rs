@@ -0,0 +1,351 @@
+use std::collections::{ BTreeMap };
+use std::path::{ Path, PathBuf };
+
+use crate::links::{ envify };
+use crate::{ Profile, Project, Target, TargetKind, TargetPlatform };
+
+/// Variables set from an environment key to its value
+pub type Env = BTreeMap<String, String>;
+
+/// How a build script is run, which decides the variables cargo sets for
+/// it beyond those describing the package
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuildScriptRun {
+    /// Platform the package is compiled for
+    pub target: TargetPlatform,
+    /// Triple of the platform running the build
+    pub host: String,
+    /// Name of the profile, e.g. `dev` or `release`
+    pub profile: String,
+    /// Features enabled for the package
+    pub features: Vec<String>,
+    /// Directory the build script writes its output to
+    pub out_dir: PathBuf,
+}
+
+/// Built-in profile whose settings a built-in profile falls back to
+fn builtin_parent(profile: &str) -> Option<&'static str> {
+    match profile {
+        "bench" => Some("release"),
+        "test" | "doc" => Some("dev"),
+        _ => None,
+    }
+}
+
+/// The profile followed by those it takes its settings from, the profiles
+/// it `inherits` from and the built-in profiles those fall back to
+fn profile_chain<'a>(project: &'a Project, profile: &'a str) -> Vec<&'a str> {
+    let mut chain = vec![];
+    let mut next = Some(profile);
+    // Bounded by the number of profiles in case `inherits` goes round in a loop
+    while let Some(profile) = next.filter(|_| chain.len() <= project.profiles.len()) {
+        chain.push(profile);
+        next = match project.profiles.get(profile).and_then(|p| p.inherits.as_deref()) {
+            Some(inherits) => Some(inherits),
+            None => builtin_parent(profile),
+        };
+    }
+    chain
+}
+
+/// Whether a profile is `release` or `bench`, or a custom profile which
+/// inherits from one of them
+fn is_release(project: &Project, profile: &str) -> bool {
+    profile_chain(project, profile).iter()
+        .find_map(|profile| match *profile {
+            "release" | "bench" => Some(true),
+            "dev" | "test" => Some(false),
+            _ => None,
+        })
+        .unwrap_or(false)
+}
+
+/// First value of a setting along the profile chain
+fn profile_setting<T>(project: &Project, profile: &str, setting: impl Fn(&Profile) -> Option<T>) -> Option<T> {
+    profile_chain(project, profile).iter().find_map(|profile| project.profiles.get(*profile).and_then(&setting))
+}
+
+/// `opt-level` of the profile, or the default of the built-in profile
+fn opt_level(project: &Project, profile: &str) -> String {
+    profile_setting(project, profile, |p| p.opt_level.to_owned())
+        .unwrap_or_else(|| if is_release(project, profile) { "3" } else { "0" }.to_owned())
+}
+
+/// Whether the profile produces debug info, by its `debug` setting or the
+/// default of the built-in profile
+fn debug(project: &Project, profile: &str) -> bool {
+    match profile_setting(project, profile, |p| p.debug.to_owned()) {
+        Some(level) => !matches!(level.as_str(), "false" | "0" | "none"),
+        None => !is_release(project, profile),
+    }
+}
+
+/// `CARGO_PKG_*` variables and `CARGO_MANIFEST_DIR`, which every target
+/// of a package and its build script see, where `manifest_dir` is the
+/// directory containing the manifest
+pub fn package_env(project: &Project, manifest_dir: &Path) -> Env {
+    let metadata = &project.metadata;
+    let version = &project.version;
+    let optional = |value: &Option<String>| value.to_owned().unwrap_or_default();
+    let url = |value: &Option<url::Url>| value.as_ref().map(|url| url.to_string()).unwrap_or_default();
+    vec![
+        ("CARGO_MANIFEST_DIR", manifest_dir.display().to_string()),
+        ("CARGO_MANIFEST_PATH", manifest_dir.join("Cargo.toml").display().to_string()),
+        ("CARGO_PKG_NAME", project.name.to_owned()),
+        ("CARGO_PKG_VERSION", version.to_string()),
+        ("CARGO_PKG_VERSION_MAJOR", version.major.to_string()),
+        ("CARGO_PKG_VERSION_MINOR", version.minor.to_string()),
+        ("CARGO_PKG_VERSION_PATCH", version.patch.to_string()),
+        ("CARGO_PKG_VERSION_PRE", version.pre.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(".")),
+        ("CARGO_PKG_AUTHORS", metadata.authors.join(":")),
+        ("CARGO_PKG_DESCRIPTION", optional(&metadata.description)),
+        ("CARGO_PKG_HOMEPAGE", url(&metadata.homepage)),
+        ("CARGO_PKG_REPOSITORY", url(&metadata.repository)),
+        ("CARGO_PKG_LICENSE", optional(&metadata.license)),
+        ("CARGO_PKG_LICENSE_FILE", optional(&metadata.license_file)),
+        ("CARGO_PKG_README", optional(&metadata.readme)),
+        ("CARGO_PKG_RUST_VERSION", project.rust_version.map(|v| v.to_string()).unwrap_or_default()),
+    ].into_iter()
+        .map(|(key, value)| (key.to_owned(), value))
+        .collect()
+}
+
+/// Variables set while compiling `target`, which add `CARGO_CRATE_NAME`
+/// and, for binaries, `CARGO_BIN_NAME` to those of the package
+pub fn target_env(project: &Project, manifest_dir: &Path, target: &Target) -> Env {
+    let mut env = package_env(project, manifest_dir);
+    env.insert("CARGO_CRATE_NAME".to_owned(), target.name.replace('-', "_"));
+    if target.kind == TargetKind::Bin {
+        env.insert("CARGO_BIN_NAME".to_owned(), target.name.to_owned());
+    }
+    env
+}
+
+/// Variables set while running the build script, which add the profile,
+/// enabled features as `CARGO_FEATURE_*` and cfg options of the target as
+/// `CARGO_CFG_*` to those of the package.
+///
+/// Options with several values, such as `target_family`, are joined by `,`.
+pub fn build_script_env(project: &Project, manifest_dir: &Path, run: &BuildScriptRun) -> Env {
+    let mut env = package_env(project, manifest_dir);
+    let mut set = |key: &str, value: String| env.insert(key.to_owned(), value);
+    set("OUT_DIR", run.out_dir.display().to_string());
+    set("TARGET", run.target.triple.to_owned());
+    set("HOST", run.host.to_owned());
+    set("PROFILE", if is_release(project, &run.profile) { "release" } else { "debug" }.to_owned());
+    set("OPT_LEVEL", opt_level(project, &run.profile));
+    set("DEBUG", debug(project, &run.profile).to_string());
+    if let Some(links) = &project.metadata.links {
+        set("CARGO_MANIFEST_LINKS", links.to_owned());
+    }
+    for feature in &run.features {
+        env.insert(format!("CARGO_FEATURE_{}", envify(feature)), "1".to_owned());
+    }
+    for (key, value) in &run.target.cfg {
+        let value = value.to_owned().unwrap_or_default();
+        env.entry(format!("CARGO_CFG_{}", envify(key)))
+            .and_modify(|values| {
+                values.push(',');
+                values.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    env
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path, PathBuf };
+
+    use semver::{ Version };
+
+    use crate::{ Metadata, Profile, Project, RustVersion, Target, TargetKind, TargetPlatform };
+    use super::{ build_script_env, package_env, target_env, BuildScriptRun };
+
+    fn project() -> Project {
+        Project {
+            rust_version: Some(RustVersion { major: 1, minor: Some(70), patch: None }),
+            metadata: Metadata {
+                authors: vec!["Jane <jane@example.com>".to_owned(), "John".to_owned()],
+                description: Some("Bindings to foo".to_owned()),
+                links: Some("foo".to_owned()),
+                .. Default::default()
+            },
+            .. Project::new("foo-sys", Version::parse("1.2.3-beta.1").unwrap())
+        }
+    }
+
+    fn target(kind: TargetKind, name: &str) -> Target {
+        Target {
+            kind,
+            name: name.to_owned(),
+            path: None,
+            crate_types: vec![],
+            required_features: vec![],
+            edition: None,
+            test: None,
+            doctest: None,
+            bench: None,
+            doc: None,
+            harness: None,
+            proc_macro: None,
+        }
+    }
+
+    fn run(profile: &str) -> BuildScriptRun {
+        BuildScriptRun {
+            target: TargetPlatform::from_triple("x86_64-unknown-linux-gnu"),
+            host: "x86_64-unknown-linux-gnu".to_owned(),
+            profile: profile.to_owned(),
+            features: vec!["default".to_owned(), "static-link".to_owned()],
+            out_dir: PathBuf::from("/work/target/debug/build/foo-sys-1234/out"),
+        }
+    }
+
+    #[test]
+    fn compute_package_env() {
+        let env = package_env(&project(), Path::new("/work/foo-sys"));
+        let get = |key: &str| env.get(key).map(|v| v.as_str());
+        assert_eq!(Some("/work/foo-sys"), get("CARGO_MANIFEST_DIR"));
+        assert_eq!(Some("foo-sys"), get("CARGO_PKG_NAME"));
+        assert_eq!(Some("1.2.3-beta.1"), get("CARGO_PKG_VERSION"));
+        assert_eq!(Some("1"), get("CARGO_PKG_VERSION_MAJOR"));
+        assert_eq!(Some("2"), get("CARGO_PKG_VERSION_MINOR"));
+        assert_eq!(Some("3"), get("CARGO_PKG_VERSION_PATCH"));
+        assert_eq!(Some("beta.1"), get("CARGO_PKG_VERSION_PRE"));
+        assert_eq!(Some("Jane <jane@example.com>:John"), get("CARGO_PKG_AUTHORS"));
+        assert_eq!(Some("Bindings to foo"), get("CARGO_PKG_DESCRIPTION"));
+        assert_eq!(Some(""), get("CARGO_PKG_HOMEPAGE"));
+        assert_eq!(Some("1.70"), get("CARGO_PKG_RUST_VERSION"));
+        assert_eq!(None, get("CARGO_CRATE_NAME"));
+    }
+
+    #[test]
+    fn compute_target_env() {
+        let project = project();
+        let lib = target_env(&project, Path::new("/work/foo-sys"), &target(TargetKind::Lib, "foo_sys"));
+        assert_eq!(Some("foo_sys"), lib.get("CARGO_CRATE_NAME").map(|v| v.as_str()));
+        assert_eq!(None, lib.get("CARGO_BIN_NAME"));
+
+        let bin = target_env(&project, Path::new("/work/foo-sys"), &target(TargetKind::Bin, "foo-cli"));
+        assert_eq!(Some("foo_cli"), bin.get("CARGO_CRATE_NAME").map(|v| v.as_str()));
+        assert_eq!(Some("foo-cli"), bin.get("CARGO_BIN_NAME").map(|v| v.as_str()));
+    }
+
+    #[test]
+    fn compute_build_script_env() {
+        let mut project = project();
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("dev"));
+        let get = |key: &str| env.get(key).map(|v| v.as_str());
+        assert_eq!(Some("/work/target/debug/build/foo-sys-1234/out"), get("OUT_DIR"));
+        assert_eq!(Some("debug"), get("PROFILE"));
+        assert_eq!(Some("0"), get("OPT_LEVEL"));
+        assert_eq!(Some("true"), get("DEBUG"));
+        assert_eq!(Some("foo"), get("CARGO_MANIFEST_LINKS"));
+        assert_eq!(Some("1"), get("CARGO_FEATURE_STATIC_LINK"));
+        assert_eq!(Some("1"), get("CARGO_FEATURE_DEFAULT"));
+        assert_eq!(Some("linux"), get("CARGO_CFG_TARGET_OS"));
+        assert_eq!(Some("unix"), get("CARGO_CFG_TARGET_FAMILY"));
+        assert_eq!(Some(""), get("CARGO_CFG_UNIX"));
+        assert_eq!(Some("foo-sys"), get("CARGO_PKG_NAME"));
+
+        project.profiles.insert("release".to_owned(), Profile {
+            opt_level: Some("s".to_owned()),
+            debug: Some("1".to_owned()),
+            .. Default::default()
+        });
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("release"));
+        assert_eq!(Some("release"), env.get("PROFILE").map(|v| v.as_str()));
+        assert_eq!(Some("s"), env.get("OPT_LEVEL").map(|v| v.as_str()));
+        assert_eq!(Some("true"), env.get("DEBUG").map(|v| v.as_str()));
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("bench"));
+        assert_eq!(Some("s"), env.get("OPT_LEVEL").map(|v| v.as_str()));
+        assert_eq!(Some("true"), env.get("DEBUG").map(|v| v.as_str()));
+        project.profiles.insert("profiling".to_owned(), Profile {
+            inherits: Some("bench".to_owned()),
+            .. Default::default()
+        });
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("profiling"));
+        assert_eq!(Some("release"), env.get("PROFILE").map(|v| v.as_str()));
+        assert_eq!(Some("s"), env.get("OPT_LEVEL").map(|v| v.as_str()));
+
+        let mut wasm = run("dev");
+        wasm.target = TargetPlatform::from_triple("wasm32-unknown-emscripten");
+        wasm.target.cfg.insert(("target_family".to_owned(), Some("unix".to_owned())));
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &wasm);
+        assert_eq!(Some("unix,wasm"), env.get("CARGO_CFG_TARGET_FAMILY").map(|v| v.as_str()));
+    }
+
+    #[test]
+    fn builtin_profiles_fall_back() {
+        let mut project = project();
+        project.profiles.insert("dev".to_owned(), Profile {
+            opt_level: Some("1".to_owned()),
+            debug: Some("false".to_owned()),
+            .. Default::default()
+        });
+        project.profiles.insert("release".to_owned(), Profile {
+            opt_level: Some("s".to_owned()),
+            .. Default::default()
+        });
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("test"));
+        assert_eq!(Some("1"), env.get("OPT_LEVEL").map(|v| v.as_str()));
+        assert_eq!(Some("false"), env.get("DEBUG").map(|v| v.as_str()));
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("bench"));
+        assert_eq!(Some("s"), env.get("OPT_LEVEL").map(|v| v.as_str()));
+        assert_eq!(Some("false"), env.get("DEBUG").map(|v| v.as_str()));
+
+        project.profiles.insert("bench".to_owned(), Profile {
+            opt_level: Some("2".to_owned()),
+            .. Default::default()
+        });
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("bench"));
+        assert_eq!(Some("2"), env.get("OPT_LEVEL").map(|v| v.as_str()));
+    }
+
+    #[test]
+    fn custom_profiles_inherit_settings() {
+        let mut project = project();
+        project.profiles.insert("release".to_owned(), Profile {
+            opt_level: Some("s".to_owned()),
+            debug: Some("true".to_owned()),
+            .. Default::default()
+        });
+        project.profiles.insert("profiling".to_owned(), Profile {
+            inherits: Some("release".to_owned()),
+            .. Default::default()
+        });
+        project.profiles.insert("profiling-small".to_owned(), Profile {
+            inherits: Some("profiling".to_owned()),
+            debug: Some("0".to_owned()),
+            .. Default::default()
+        });
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("profiling"));
+        assert_eq!(Some("release"), env.get("PROFILE").map(|v| v.as_str()));
+        assert_eq!(Some("s"), env.get("OPT_LEVEL").map(|v| v.as_str()));
+        assert_eq!(Some("true"), env.get("DEBUG").map(|v| v.as_str()));
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("profiling-small"));
+        assert_eq!(Some("s"), env.get("OPT_LEVEL").map(|v| v.as_str()));
+        assert_eq!(Some("false"), env.get("DEBUG").map(|v| v.as_str()));
+
+        project.profiles.insert("ci".to_owned(), Profile {
+            inherits: Some("test".to_owned()),
+            .. Default::default()
+        });
+        project.profiles.insert("dev".to_owned(), Profile {
+            opt_level: Some("1".to_owned()),
+            .. Default::default()
+        });
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("ci"));
+        assert_eq!(Some("1"), env.get("OPT_LEVEL").map(|v| v.as_str()));
+        assert_eq!(Some("true"), env.get("DEBUG").map(|v| v.as_str()));
+
+        project.profiles.insert("loop".to_owned(), Profile {
+            inherits: Some("loop".to_owned()),
+            .. Default::default()
+        });
+        let env = build_script_env(&project, Path::new("/work/foo-sys"), &run("loop"));
+        assert_eq!(Some("0"), env.get("OPT_LEVEL").map(|v| v.as_str()));
+    }
+}
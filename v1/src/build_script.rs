@@ -0,0 +1,59 @@
+use std::fmt;
+use std::path::{ Path, PathBuf };
+
+/// Default build script path, relative to the package root
+pub const DEFAULT_BUILD_SCRIPT: &str = "build.rs";
+
+/// The `build` key of a package
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum BuildScript {
+    /// No `build` key, so `build.rs` is used when the package has one
+    #[default]
+    Inferred,
+    /// `build = "path"`, where `build = true` names `build.rs`
+    Path(PathBuf),
+    /// `build = false`, which stops `build.rs` from being detected
+    Disabled,
+}
+
+impl fmt::Display for BuildScript {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildScript::Inferred => write!(f, "inferred"),
+            BuildScript::Path(path) => write!(f, "{}", path.display()),
+            BuildScript::Disabled => write!(f, "false"),
+        }
+    }
+}
+
+impl BuildScript {
+    /// Path of the build script relative to `package_root`, the directory
+    /// containing the manifest, if the package has one
+    pub fn resolve(&self, package_root: &Path) -> Option<PathBuf> {
+        match self {
+            BuildScript::Inferred => Some(PathBuf::from(DEFAULT_BUILD_SCRIPT))
+                .filter(|path| package_root.join(path).is_file()),
+            BuildScript::Path(path) => Some(path.to_owned()),
+            BuildScript::Disabled => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path, PathBuf };
+
+    use super::{ BuildScript };
+
+    #[test]
+    fn resolve_build_scripts() {
+        let with_build = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/links");
+        let without_build = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        assert_eq!(Some(PathBuf::from("build.rs")), BuildScript::Inferred.resolve(&with_build));
+        assert_eq!(None, BuildScript::Inferred.resolve(&without_build));
+        assert_eq!(None, BuildScript::Disabled.resolve(&with_build));
+        let explicit = BuildScript::Path(PathBuf::from("tools/gen.rs"));
+        assert_eq!(Some(PathBuf::from("tools/gen.rs")), explicit.resolve(&without_build));
+    }
+}
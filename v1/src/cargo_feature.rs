@@ -0,0 +1,115 @@
+use crate::{ RustVersion };
+
+/// Whether a manifest feature still has to be listed in `cargo-features`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FeatureStatus {
+    Unstable,
+    /// Usable without listing it from this version on
+    Stabilized(RustVersion),
+    /// Dropped from cargo in this version
+    Removed(RustVersion),
+}
+
+/// An unstable manifest feature enabled by `cargo-features = [..]`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CargoFeature {
+    pub name: &'static str,
+    pub status: FeatureStatus,
+}
+
+const fn unstable(name: &'static str) -> CargoFeature {
+    CargoFeature { name, status: FeatureStatus::Unstable }
+}
+
+const fn stabilized(name: &'static str, minor: u64) -> CargoFeature {
+    CargoFeature { name, status: FeatureStatus::Stabilized(RustVersion::new(1, minor, 0)) }
+}
+
+const fn removed(name: &'static str, minor: u64) -> CargoFeature {
+    CargoFeature { name, status: FeatureStatus::Removed(RustVersion::new(1, minor, 0)) }
+}
+
+/// Manifest features cargo knows of, from cargo's `features.rs`
+pub const CARGO_FEATURES: [CargoFeature; 23] = [
+    stabilized("alternative-registries", 34),
+    unstable("bindeps"),
+    unstable("codegen-backend"),
+    stabilized("default-run", 37),
+    unstable("different-binary-name"),
+    stabilized("edition", 31),
+    stabilized("edition2021", 56),
+    stabilized("edition2024", 85),
+    stabilized("lints", 74),
+    unstable("metabuild"),
+    stabilized("named-profiles", 57),
+    removed("namespaced-features", 60),
+    unstable("open-namespaces"),
+    unstable("per-package-target"),
+    stabilized("profile-overrides", 41),
+    unstable("profile-rustflags"),
+    unstable("public-dependency"),
+    removed("publish-lockfile", 37),
+    stabilized("rename-dependency", 31),
+    stabilized("resolver", 51),
+    stabilized("rust-version", 56),
+    stabilized("strip", 59),
+    stabilized("workspace-inheritance", 64),
+];
+
+impl CargoFeature {
+    pub fn find(name: &str) -> Option<CargoFeature> {
+        CARGO_FEATURES.iter().find(|feature| feature.name == name).copied()
+    }
+
+    /// Whether the feature is stable on the `toolchain` version, where
+    /// `None` stands for the latest one
+    pub fn is_stable_on(&self, toolchain: Option<RustVersion>) -> bool {
+        match self.status {
+            FeatureStatus::Stabilized(version) => toolchain.is_none_or(|toolchain| toolchain >= version),
+            FeatureStatus::Unstable | FeatureStatus::Removed(_) => false,
+        }
+    }
+
+    /// Whether the feature is gone from the `toolchain` version, where
+    /// `None` stands for the latest one
+    pub fn is_removed_on(&self, toolchain: Option<RustVersion>) -> bool {
+        match self.status {
+            FeatureStatus::Removed(version) => toolchain.is_none_or(|toolchain| toolchain >= version),
+            FeatureStatus::Unstable | FeatureStatus::Stabilized(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use super::{ CargoFeature, FeatureStatus, CARGO_FEATURES };
+    use crate::{ RustVersion };
+
+    #[test]
+    fn list_features_in_order() {
+        let names: Vec<_> = CARGO_FEATURES.iter().map(|f| f.name).collect();
+        let mut sorted = names.to_owned();
+        sorted.sort();
+        assert_eq!(sorted, names);
+    }
+
+    #[test]
+    fn decide_stability_by_toolchain() {
+        let named_profiles = CargoFeature::find("named-profiles").unwrap();
+        assert_eq!(FeatureStatus::Stabilized(RustVersion::new(1, 57, 0)), named_profiles.status);
+        assert!(named_profiles.is_stable_on(None));
+        assert!(named_profiles.is_stable_on(Some(RustVersion::new(1, 57, 0))));
+        assert!(!named_profiles.is_stable_on(Some(RustVersion { major: 1, minor: Some(56), patch: None })));
+
+        let per_package_target = CargoFeature::find("per-package-target").unwrap();
+        assert!(!per_package_target.is_stable_on(None));
+        assert!(!per_package_target.is_removed_on(None));
+
+        let publish_lockfile = CargoFeature::find("publish-lockfile").unwrap();
+        assert!(publish_lockfile.is_removed_on(None));
+        assert!(!publish_lockfile.is_removed_on(Some(RustVersion::new(1, 36, 0))));
+
+        assert_eq!(None, CargoFeature::find("im-a-teapot"));
+    }
+}
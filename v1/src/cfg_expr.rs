@@ -0,0 +1,239 @@
+use std::collections::{ BTreeSet };
+use std::fmt;
+
+use crate::{ Error, Result };
+
+/// A `cfg(..)` predicate as used by `[target.'cfg(..)'.dependencies]`
+#[derive(Clone, Debug, PartialEq)]
+pub enum CfgExpr {
+    /// e.g. `unix`
+    Name(String),
+    /// e.g. `target_os = "linux"`
+    KeyValue(String, String),
+    All(Vec<CfgExpr>),
+    Any(Vec<CfgExpr>),
+    Not(Box<CfgExpr>),
+}
+
+impl fmt::Display for CfgExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let list = |all: &[CfgExpr]| all.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ");
+        match self {
+            CfgExpr::Name(name) => write!(f, "{}", name),
+            CfgExpr::KeyValue(key, value) => write!(f, "{} = \"{}\"", key, value),
+            CfgExpr::All(all) => write!(f, "all({})", list(all)),
+            CfgExpr::Any(all) => write!(f, "any({})", list(all)),
+            CfgExpr::Not(expr) => write!(f, "not({})", expr),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Open,
+    Close,
+    Comma,
+    Equals,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "{}", ident),
+            Token::Str(value) => write!(f, "\"{}\"", value),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+            Token::Equals => write!(f, "="),
+        }
+    }
+}
+
+fn tokenize(expr: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = expr.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            ',' => tokens.push(Token::Comma),
+            '=' => tokens.push(Token::Equals),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => value.push(c),
+                        None => return Err("unterminated string".to_owned()),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            },
+            c if c.is_whitespace() => {},
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            },
+            c => return Err(format!("unexpected character `{}`", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+}
+
+impl Parser {
+    fn take(&mut self) -> std::result::Result<&Token, String> {
+        let token = self.tokens.get(self.next).ok_or_else(|| "unexpected end of expression".to_owned())?;
+        self.next += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> std::result::Result<(), String> {
+        match self.take()? {
+            token if *token == expected => Ok(()),
+            token => Err(format!("expected `{}` but found `{}`", expected, token)),
+        }
+    }
+
+    fn list(&mut self) -> std::result::Result<Vec<CfgExpr>, String> {
+        self.expect(Token::Open)?;
+        let mut all = vec![];
+        loop {
+            if self.tokens.get(self.next) == Some(&Token::Close) {
+                self.next += 1;
+                return Ok(all);
+            }
+            all.push(self.expr()?);
+            match self.take()? {
+                Token::Comma => {},
+                Token::Close => return Ok(all),
+                token => return Err(format!("expected `,` or `)` but found `{}`", token)),
+            }
+        }
+    }
+
+    fn expr(&mut self) -> std::result::Result<CfgExpr, String> {
+        let ident = match self.take()? {
+            Token::Ident(ident) => ident.to_owned(),
+            token => return Err(format!("unexpected `{}`", token)),
+        };
+        match (ident.as_str(), self.tokens.get(self.next)) {
+            ("all", Some(Token::Open)) => Ok(CfgExpr::All(self.list()?)),
+            ("any", Some(Token::Open)) => Ok(CfgExpr::Any(self.list()?)),
+            ("not", Some(Token::Open)) => {
+                let mut all = self.list()?;
+                if all.len() != 1 {
+                    return Err("`not` takes exactly one predicate".to_owned());
+                }
+                Ok(CfgExpr::Not(Box::new(all.remove(0))))
+            },
+            (_, Some(Token::Equals)) => {
+                self.next += 1;
+                match self.take()? {
+                    Token::Str(value) => Ok(CfgExpr::KeyValue(ident, value.to_owned())),
+                    token => Err(format!("expected a string but found `{}`", token)),
+                }
+            },
+            _ => Ok(CfgExpr::Name(ident)),
+        }
+    }
+}
+
+impl CfgExpr {
+    /// Parses the predicate inside of `cfg(..)`, e.g. `any(unix, windows)`
+    pub fn parse(expr: &str) -> Result<CfgExpr> {
+        let invalid = |reason: String| Error::InvalidCfg(expr.to_owned(), reason);
+        let mut parser = Parser { tokens: tokenize(expr).map_err(invalid)?, next: 0 };
+        let parsed = parser.expr().map_err(invalid)?;
+        match parser.tokens.get(parser.next) {
+            Some(token) => Err(invalid(format!("unexpected `{}`", token))),
+            None => Ok(parsed),
+        }
+    }
+
+    /// Evaluates the predicate against a set of cfg options, where names
+    /// such as `unix` have no value
+    pub fn eval(&self, cfg: &BTreeSet<(String, Option<String>)>) -> bool {
+        match self {
+            CfgExpr::Name(name) => cfg.contains(&(name.to_owned(), None)),
+            CfgExpr::KeyValue(key, value) => cfg.contains(&(key.to_owned(), Some(value.to_owned()))),
+            CfgExpr::All(all) => all.iter().all(|e| e.eval(cfg)),
+            CfgExpr::Any(all) => all.iter().any(|e| e.eval(cfg)),
+            CfgExpr::Not(expr) => !expr.eval(cfg),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::collections::{ BTreeSet };
+
+    use super::{ CfgExpr };
+    use crate::{ Error };
+
+    #[test]
+    fn parse_predicates() {
+        let cases = vec![
+            ("unix", "unix"),
+            ("target_os = \"linux\"", "target_os = \"linux\""),
+            ("all(unix,target_arch=\"x86_64\")", "all(unix, target_arch = \"x86_64\")"),
+            ("any(windows, not(target_env = \"musl\"),)", "any(windows, not(target_env = \"musl\"))"),
+            ("all()", "all()"),
+        ];
+        for (expr, expected) in cases {
+            match CfgExpr::parse(expr) {
+                Ok (parsed) => assert_eq!(expected, parsed.to_string()),
+                Err (err) => assert!(false, "unexpected error: {}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn fail_on_invalid_predicates() {
+        let cases = vec![
+            ("", "unexpected end of expression"),
+            ("unix windows", "unexpected `windows`"),
+            ("not(unix, windows)", "`not` takes exactly one predicate"),
+            ("target_os = linux", "expected a string but found `linux`"),
+            ("any(unix", "unexpected end of expression"),
+            ("target_os = \"linux", "unterminated string"),
+        ];
+        for (expr, reason) in cases {
+            match CfgExpr::parse(expr) {
+                Ok (parsed) => assert!(false, "should fail to parse `{}`: {}", expr, parsed),
+                Err (err) => assert_eq!(Error::InvalidCfg(expr.to_owned(), reason.to_owned()), err),
+            }
+        }
+    }
+
+    #[test]
+    fn evaluate_predicates() {
+        let cfg: BTreeSet<_> = vec![
+            ("unix".to_owned(), None),
+            ("target_os".to_owned(), Some("linux".to_owned())),
+        ].into_iter().collect();
+        let eval = |expr: &str| CfgExpr::parse(expr).unwrap().eval(&cfg);
+        assert!(eval("unix"));
+        assert!(!eval("windows"));
+        assert!(eval("all(unix, target_os = \"linux\")"));
+        assert!(!eval("all(unix, target_os = \"macos\")"));
+        assert!(eval("any(windows, target_os = \"linux\")"));
+        assert!(eval("not(windows)"));
+        assert!(eval("all()"));
+        assert!(!eval("any()"));
+    }
+}
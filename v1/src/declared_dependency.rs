@@ -0,0 +1,59 @@
+use semver::VersionReq;
+
+use crate::{ Artifact, Dependency, DependencyKind };
+
+/// A dependency as declared by a package, with the source it resolves from
+/// and the options which apply to it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeclaredDependency {
+    /// Key the dependency is declared under, which is the name used in code
+    pub name: String,
+    /// Name of the package when the dependency is renamed with `package`
+    pub package: Option<String>,
+    pub kind: DependencyKind,
+    /// `cfg(..)` expression or target triple of a `[target.*]` table
+    pub platform: Option<String>,
+    pub source: Dependency,
+    /// The `version` requirement when one is declared, which applies to
+    /// path and git sources as well as registries
+    pub version: Option<VersionReq>,
+    pub optional: bool,
+    pub default_features: bool,
+    pub features: Vec<String>,
+    /// Artifacts the dependency is built for, where `None` makes it a
+    /// plain library dependency
+    pub artifact: Option<Artifact>,
+}
+
+impl DeclaredDependency {
+    /// A dependency on `name` with cargo's defaults for every option, as
+    /// if it were declared with nothing but its source
+    pub fn new(name: &str, kind: DependencyKind, source: Dependency) -> Self {
+        DeclaredDependency {
+            name: name.to_owned(),
+            package: None,
+            kind,
+            platform: None,
+            source,
+            version: None,
+            optional: false,
+            default_features: true,
+            features: vec![],
+            artifact: None,
+        }
+    }
+
+    /// A dependency on `name` from the default registry, as declared with
+    /// nothing but a version requirement, e.g. `serde = "1.0"`
+    pub fn registry(name: &str, kind: DependencyKind, req: VersionReq) -> Self {
+        DeclaredDependency {
+            version: Some(req.to_owned()),
+            .. DeclaredDependency::new(name, kind, Dependency::DefaultRegistry(req))
+        }
+    }
+
+    /// Name of the package depended on, taking any rename into account
+    pub fn package_name(&self) -> &str {
+        self.package.as_ref().unwrap_or(&self.name)
+    }
+}
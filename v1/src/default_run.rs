@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+use crate::{ Error, Project, Result, TargetKind };
+
+/// Binaries cargo discovers under `package_root` when `autobins` is on,
+/// `src/main.rs` named after the package along with `src/bin/<name>.rs`
+/// and `src/bin/<name>/main.rs`
+fn discovered_binaries(project: &Project, package_root: &Path) -> Vec<(String, PathBuf)> {
+    let mut found = vec![];
+    if package_root.join("src/main.rs").is_file() {
+        found.push((project.name.to_owned(), PathBuf::from("src/main.rs")));
+    }
+    let mut entries: Vec<_> = match fs::read_dir(package_root.join("src/bin")) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect(),
+        Err(_) => vec![],
+    };
+    entries.sort();
+    for entry in entries {
+        let name = match entry.file_stem() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => continue,
+        };
+        if entry.is_file() && entry.extension().is_some_and(|ext| ext == "rs") {
+            found.push((name.to_owned(), PathBuf::from("src/bin").join(format!("{}.rs", name))));
+        } else if entry.join("main.rs").is_file() {
+            found.push((name.to_owned(), PathBuf::from("src/bin").join(&name).join("main.rs")));
+        }
+    }
+    found
+}
+
+/// Names of the binaries of the package, the `[[bin]]` targets followed by
+/// those discovered under `package_root` which no `[[bin]]` already builds
+pub fn binaries(project: &Project, package_root: &Path) -> Vec<String> {
+    let declared: Vec<_> = project.targets.iter().filter(|target| target.kind == TargetKind::Bin).collect();
+    let mut names: Vec<_> = declared.iter().map(|target| target.name.to_owned()).collect();
+    if project.autobins {
+        for (name, path) in discovered_binaries(project, package_root) {
+            let built = declared.iter().any(|target| target.path.as_ref() == Some(&path));
+            if !built && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Checks that `default-run` names a binary of the package, where
+/// `package_root` is the directory containing the manifest
+pub fn verify_default_run(project: &Project, package_root: &Path) -> Result<()> {
+    match &project.default_run {
+        Some(default_run) => {
+            let binaries = binaries(project, package_root);
+            if binaries.contains(default_run) {
+                Ok(())
+            } else {
+                Err(Error::DefaultRunNotFound(default_run.to_owned(), binaries))
+            }
+        },
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path, PathBuf };
+
+    use semver::{ Version };
+
+    use crate::{ Error, Project, Target, TargetKind };
+    use super::{ binaries, verify_default_run };
+
+    fn project(default_run: Option<&str>, bins: Vec<(&str, Option<&str>)>) -> Project {
+        Project {
+            default_run: default_run.map(str::to_owned),
+            targets: bins.into_iter().map(|(name, path)| Target {
+                kind: TargetKind::Bin,
+                name: name.to_owned(),
+                path: path.map(PathBuf::from),
+                crate_types: vec![],
+                required_features: vec![],
+                edition: None,
+                test: None,
+                doctest: None,
+                bench: None,
+                doc: None,
+                harness: None,
+                proc_macro: None,
+            }).collect(),
+            .. Project::new("tool", Version::parse("1.0.0").unwrap())
+        }
+    }
+
+    #[test]
+    fn discover_binaries() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/bins");
+        assert_eq!(vec!["tool", "helper", "server"], binaries(&project(None, vec![]), &root));
+
+        let declared = project(None, vec![("cli", Some("src/main.rs")), ("helper", Some("tools/helper.rs"))]);
+        assert_eq!(vec!["cli", "helper", "server"], binaries(&declared, &root));
+
+        let mut manual = declared.to_owned();
+        manual.autobins = false;
+        assert_eq!(vec!["cli", "helper"], binaries(&manual, &root));
+    }
+
+    #[test]
+    fn require_default_run_binary() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/bins");
+        assert_eq!(Ok(()), verify_default_run(&project(Some("server"), vec![]), &root));
+        assert_eq!(Ok(()), verify_default_run(&project(None, vec![]), &root));
+        match verify_default_run(&project(Some("client"), vec![]), &root) {
+            Ok (_) => assert!(false, "should not find the `client` binary"),
+            Err (err) => {
+                assert_eq!(
+                    Error::DefaultRunNotFound("client".to_owned(), vec!["tool".to_owned(), "helper".to_owned(), "server".to_owned()]),
+                    err,
+                );
+                assert_eq!(
+                    "default-run target `client` not found, available binaries are: tool, helper, server",
+                    err.to_string(),
+                );
+            },
+        }
+    }
+}
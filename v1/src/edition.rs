@@ -0,0 +1,47 @@
+use std::fmt;
+
+use crate::{ Resolver, RustVersion };
+
+/// The Rust edition a package or target is compiled with.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Edition {
+    #[default]
+    Edition2015,
+    Edition2018,
+    Edition2021,
+    Edition2024,
+}
+
+impl Edition {
+    pub const LATEST: Edition = Edition::Edition2024;
+
+    /// First stable toolchain which supports this edition
+    pub fn first_version(self) -> RustVersion {
+        match self {
+            Edition::Edition2015 => RustVersion::new(1, 0, 0),
+            Edition::Edition2018 => RustVersion::new(1, 31, 0),
+            Edition::Edition2021 => RustVersion::new(1, 56, 0),
+            Edition::Edition2024 => RustVersion::new(1, 85, 0),
+        }
+    }
+
+    /// Resolver used by a package of this edition which does not set one
+    pub fn default_resolver(self) -> Resolver {
+        match self {
+            Edition::Edition2015 | Edition::Edition2018 => Resolver::V1,
+            Edition::Edition2021 => Resolver::V2,
+            Edition::Edition2024 => Resolver::V3,
+        }
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Edition::Edition2015 => "2015",
+            Edition::Edition2018 => "2018",
+            Edition::Edition2021 => "2021",
+            Edition::Edition2024 => "2024",
+        })
+    }
+}
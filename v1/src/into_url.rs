@@ -10,28 +10,28 @@ pub trait IntoUrl {
     fn into_url(self) -> Result<Url>;
 }
 
-impl<'a> IntoUrl for &'a str {
+impl IntoUrl for &str {
     fn into_url(self) -> Result<Url> {
         Url::parse(self)
             .map_err(|err| Error::InvalidStringUrl(self.to_owned(), err))
     }
 }
 
-impl<'a> IntoUrl for String {
+impl IntoUrl for String {
     fn into_url(self) -> Result<Url> {
         Url::parse(&self)
             .map_err(|err| Error::InvalidStringUrl(self, err))
     }
 }
 
-impl<'a> IntoUrl for &'a Path {
+impl IntoUrl for &Path {
     fn into_url(self) -> Result<Url> {
         Url::from_file_path(self)
             .map_err(|()| Error::InvalidPathUrl(self.to_owned()))
     }
 }
 
-impl<'a> IntoUrl for &'a PathBuf {
+impl IntoUrl for &PathBuf {
     fn into_url(self) -> Result<Url> {
         self.as_path().into_url()
     }
@@ -1,21 +1,65 @@
 // extern crate failure;
 extern crate semver;
+extern crate semver_parser;
+#[macro_use]
+extern crate serde_json;
+extern crate unicode_xid;
 extern crate url;
 
+mod artifact;
+mod build_env;
+mod build_script;
+mod cargo_feature;
+mod cfg_expr;
+mod declared_dependency;
+mod default_run;
 mod dependency;
+mod dependency_kind;
+mod edition;
 mod git_reference;
 mod git_repository;
 mod into_url;
+mod links;
+mod lints;
 mod manifest;
+mod manifest_diff;
+mod metadata;
+mod name;
 mod profile;
+mod project;
+mod publish;
+mod resolver;
+mod rust_version;
+mod target;
+mod target_platform;
 mod workspace;
 
+pub use self::artifact::*;
+pub use self::build_env::*;
+pub use self::build_script::*;
+pub use self::cargo_feature::*;
+pub use self::cfg_expr::*;
+pub use self::declared_dependency::*;
+pub use self::default_run::*;
 pub use self::dependency::*;
+pub use self::dependency_kind::*;
+pub use self::edition::*;
 pub use self::git_reference::*;
 pub use self::git_repository::*;
 pub use into_url::*;
+pub use links::*;
+pub use lints::*;
 pub use manifest::*;
+pub use manifest_diff::*;
+pub use metadata::*;
+pub use name::*;
 pub use profile::*;
+pub use project::*;
+pub use publish::*;
+pub use resolver::*;
+pub use rust_version::*;
+pub use target::*;
+pub use target_platform::*;
 pub use workspace::*;
 
 // use failure::{ Fail };
@@ -25,20 +69,50 @@ use std::path::{ PathBuf };
 // #[derive(Debug, Fail, PartialEq)]
 #[derive(Debug, PartialEq)]
 pub enum Error {
+    /// A `default-run` which names none of the binaries, with the binaries
+    DefaultRunNotFound(String, Vec<String>),
+    /// A native library linked by more than one package, with the packages
+    DuplicateLinks(String, Vec<String>),
     GitBaseUrlNotSupported(url::Url),
-    InvalidDependencyName(String),
+    /// An `artifact` other than `bin`, `bin:<name>`, `cdylib` or `staticlib`
+    InvalidArtifactKind(String),
+    /// A `cfg(..)` platform which cannot be parsed, with the reason
+    InvalidCfg(String, String),
+    InvalidDependencyName(String, NameError),
+    InvalidPackageName(String, NameError),
     InvalidPathUrl(PathBuf),
     InvalidStringUrl(String, url::ParseError),
+    /// A package with `links` but no build script, with the library
+    LinksWithoutBuildScript(String, String),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Error::DefaultRunNotFound(default_run, binaries) => write!(
+                f,
+                "default-run target `{}` not found, available binaries are: {}",
+                default_run, binaries.join(", ")
+            ),
+            Error::DuplicateLinks(links, packages) => {
+                write!(f, "the native library `{}` is linked by more than one package: {}", links, packages.join(", "))
+            },
             Error::GitBaseUrlNotSupported(url) => {
                 write!(f, "invalid url `{}`: cannot-be-a-base-URLs are not supported", url)
             },
-            Error::InvalidDependencyName(key) => {
-                write!(f, "invalid dependency name `{}`", key)
+            Error::InvalidArtifactKind(kind) => write!(
+                f,
+                "`artifact = \"{}\"` is not valid, valid options are \"bin\", \"bin:<name>\", \"cdylib\" or \"staticlib\"",
+                kind
+            ),
+            Error::InvalidCfg(cfg, reason) => {
+                write!(f, "invalid cfg expression `{}`: {}", cfg, reason)
+            },
+            Error::InvalidDependencyName(key, err) => {
+                write!(f, "invalid dependency name `{}`: {}", key, err)
+            },
+            Error::InvalidPackageName(name, err) => {
+                write!(f, "invalid package name `{}`: {}", name, err)
             },
             Error::InvalidPathUrl(path) => {
                 write!(f, "invalid path url `{:?}`", path)
@@ -46,6 +120,9 @@ impl fmt::Display for Error {
             Error::InvalidStringUrl(url, err) => {
                 write!(f, "invalid url `{:?}`: {}", url, err)
             },
+            Error::LinksWithoutBuildScript(package, links) => {
+                write!(f, "package `{}` links to the native library `{}` but has no build script", package, links)
+            },
         }
     }
 }
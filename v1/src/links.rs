@@ -0,0 +1,122 @@
+use std::collections::{ BTreeMap };
+use std::path::{ Path };
+
+use crate::{ Error, Project, Result };
+
+/// Name of an environment variable with letters upper cased and `-`
+/// replaced by `_`, as cargo does for features and `links` metadata
+pub(crate) fn envify(name: &str) -> String {
+    name.chars()
+        .map(|ch| if ch == '-' { '_' } else { ch.to_ascii_uppercase() })
+        .collect()
+}
+
+/// The variable a dependent package's build script sees for a
+/// `cargo:<key>=<value>` line printed by the build script of a package
+/// with `links = "<links>"`, e.g. `DEP_GIT2_ROOT` for `git2` and `root`
+pub fn dep_env_var(links: &str, key: &str) -> String {
+    format!("DEP_{}_{}", envify(links), envify(key))
+}
+
+/// Checks that a package with `links` has a build script to link the
+/// library, where `package_root` is the directory containing the manifest
+pub fn verify_links(project: &Project, package_root: &Path) -> Result<()> {
+    match &project.metadata.links {
+        Some(links) if project.build.resolve(package_root).is_none() => {
+            Err(Error::LinksWithoutBuildScript(project.name.to_owned(), links.to_owned()))
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Checks that no two packages of a dependency graph link the same native
+/// library, which is what cargo requires of the packages it resolves
+pub fn verify_unique_links(projects: &[Project]) -> Result<()> {
+    let mut linked: BTreeMap<&str, Vec<&Project>> = BTreeMap::new();
+    for project in projects {
+        if let Some(links) = &project.metadata.links {
+            let packages = linked.entry(links).or_default();
+            if !packages.iter().any(|p| p.name == project.name && p.version == project.version) {
+                packages.push(project);
+            }
+        }
+    }
+    match linked.into_iter().find(|(_, packages)| packages.len() > 1) {
+        Some((links, packages)) => Err(Error::DuplicateLinks(
+            links.to_owned(),
+            packages.iter().map(|p| format!("{} v{}", p.name, p.version)).collect(),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path, PathBuf };
+
+    use semver::{ Version };
+
+    use crate::{ BuildScript, Error, Metadata, Project };
+    use super::{ dep_env_var, verify_links, verify_unique_links };
+
+    fn project(name: &str, version: &str, links: Option<&str>) -> Project {
+        Project {
+            metadata: Metadata { links: links.map(|l| l.to_owned()), .. Default::default() },
+            .. Project::new(name, Version::parse(version).unwrap())
+        }
+    }
+
+    #[test]
+    fn compute_dep_env_vars() {
+        assert_eq!("DEP_GIT2_ROOT", dep_env_var("git2", "root"));
+        assert_eq!("DEP_Z_NG_INCLUDE_DIR", dep_env_var("z-ng", "include-dir"));
+    }
+
+    #[test]
+    fn require_build_script_for_links() {
+        let with_build = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/links");
+        let without_build = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut project = project("libgit2-sys", "0.16.1", Some("git2"));
+        assert_eq!(Ok(()), verify_links(&project, &with_build));
+        assert_eq!(
+            Err(Error::LinksWithoutBuildScript("libgit2-sys".to_owned(), "git2".to_owned())),
+            verify_links(&project, &without_build),
+        );
+        project.build = BuildScript::Path(PathBuf::from("tools/build.rs"));
+        assert_eq!(Ok(()), verify_links(&project, &without_build));
+        project.build = BuildScript::Disabled;
+        match verify_links(&project, &with_build) {
+            Ok (_) => assert!(false, "should fail when the build script is disabled"),
+            Err (err) => assert_eq!(
+                "package `libgit2-sys` links to the native library `git2` but has no build script",
+                err.to_string(),
+            ),
+        }
+        assert_eq!(Ok(()), verify_links(&self::project("serde", "1.0.0", None), &without_build));
+    }
+
+    #[test]
+    fn reject_duplicate_links() {
+        let projects = vec![
+            project("libz-sys", "1.1.12", Some("z")),
+            project("openssl-sys", "0.9.96", Some("openssl")),
+            project("libz-sys", "1.1.12", Some("z")),
+        ];
+        assert_eq!(Ok(()), verify_unique_links(&projects));
+
+        let projects = vec![
+            project("libz-sys", "1.1.12", Some("z")),
+            project("libz-ng-sys", "1.1.15", Some("z")),
+            project("libz-sys", "1.0.25", Some("z")),
+        ];
+        assert_eq!(
+            Err(Error::DuplicateLinks("z".to_owned(), vec![
+                "libz-sys v1.1.12".to_owned(),
+                "libz-ng-sys v1.1.15".to_owned(),
+                "libz-sys v1.0.25".to_owned(),
+            ])),
+            verify_unique_links(&projects),
+        );
+    }
+}
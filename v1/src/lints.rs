@@ -0,0 +1,96 @@
+use std::fmt;
+
+/// Level a lint is reported at
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum LintLevel {
+    Forbid,
+    Deny,
+    Warn,
+    Allow,
+}
+
+impl LintLevel {
+    /// The rustc flag setting a lint to this level
+    pub fn flag(self) -> &'static str {
+        match self {
+            LintLevel::Forbid => "-F",
+            LintLevel::Deny => "-D",
+            LintLevel::Warn => "-W",
+            LintLevel::Allow => "-A",
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            LintLevel::Forbid => "forbid",
+            LintLevel::Deny => "deny",
+            LintLevel::Warn => "warn",
+            LintLevel::Allow => "allow",
+        })
+    }
+}
+
+/// A lint of a `[lints.<tool>]` table
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lint {
+    /// `rust`, `clippy` or `rustdoc`
+    pub tool: String,
+    pub name: String,
+    pub level: LintLevel,
+    /// Lints of a lower priority are passed first, so they can be
+    /// overridden, e.g. a group by one of its lints
+    pub priority: i8,
+}
+
+impl Lint {
+    /// Name as rustc knows it, which has a `clippy::` or `rustdoc::` prefix
+    /// for tool lints
+    pub fn qualified_name(&self) -> String {
+        if self.tool == "rust" {
+            self.name.to_owned()
+        } else {
+            format!("{}::{}", self.tool, self.name)
+        }
+    }
+}
+
+/// The rustc arguments setting the level of each lint, such as
+/// `-W clippy::pedantic`, ordered by priority and then by name
+pub fn lint_flags(lints: &[Lint]) -> Vec<String> {
+    let mut ordered: Vec<_> = lints.iter().map(|lint| (lint.priority, lint.qualified_name(), lint.level)).collect();
+    ordered.sort();
+    ordered.into_iter()
+        .flat_map(|(_, name, level)| vec![level.flag().to_owned(), name])
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use super::{ lint_flags, Lint, LintLevel };
+
+    fn lint(tool: &str, name: &str, level: LintLevel, priority: i8) -> Lint {
+        Lint { tool: tool.to_owned(), name: name.to_owned(), level, priority }
+    }
+
+    #[test]
+    fn render_flags_in_priority_order() {
+        let lints = vec![
+            lint("clippy", "module_name_repetitions", LintLevel::Allow, 0),
+            lint("clippy", "pedantic", LintLevel::Warn, -1),
+            lint("rust", "unsafe_code", LintLevel::Forbid, 0),
+            lint("rustdoc", "broken_intra_doc_links", LintLevel::Deny, 0),
+            lint("rust", "unexpected_cfgs", LintLevel::Warn, 1),
+        ];
+        assert_eq!(vec![
+            "-W", "clippy::pedantic",
+            "-A", "clippy::module_name_repetitions",
+            "-D", "rustdoc::broken_intra_doc_links",
+            "-F", "unsafe_code",
+            "-W", "unexpected_cfgs",
+        ], lint_flags(&lints));
+        assert!(lint_flags(&[]).is_empty());
+    }
+}
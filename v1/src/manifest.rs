@@ -1,7 +1,8 @@
-use crate::Workspace;
+use crate::{ Project, Workspace };
 
 #[derive(Clone, Debug, PartialEq)]
+#[allow(clippy::large_enum_variant)]
 pub enum Manifest {
     Workspace (Workspace),
-    Project,
-}
\ No newline at end of file
+    Project (Project),
+}
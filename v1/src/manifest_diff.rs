@@ -0,0 +1,750 @@
+use std::collections::{ BTreeMap, BTreeSet };
+use std::fmt;
+
+use semver::{ Version, VersionReq };
+use semver_parser::range::{ self, Op, WildcardVersion };
+
+use crate::{
+    BuildScript, DeclaredDependency, Dependency, DependencyKind, GitReference, Lint, Manifest, Profile, Project,
+    Target, TargetKind, Workspace,
+};
+
+/// How far a version requirement moved, by the lowest version it accepts.
+///
+/// As with cargo's compatibility rules the first non-zero component of a
+/// version is its major one, so `^0.1` to `^0.2` is a major change.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SemverChange {
+    Major,
+    Minor,
+    Patch,
+    /// The lowest accepted version did not move or either requirement has
+    /// no lower bound, e.g. `^1.2` to `>=1.2, <1.5` or `1.*` to `*`
+    Other,
+}
+
+impl fmt::Display for SemverChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            SemverChange::Major => "major",
+            SemverChange::Minor => "minor",
+            SemverChange::Patch => "patch",
+            SemverChange::Other => "other",
+        })
+    }
+}
+
+/// The lowest version a requirement accepts, where missing components are
+/// zero, or `None` when it accepts any version
+fn lower_bound(req: &VersionReq) -> Option<Version> {
+    // `VersionReq` keeps its comparators private, so they are read back
+    // through the parser it is built on
+    let req = range::parse(&req.to_string()).ok()?;
+    req.predicates
+        .iter()
+        .filter(|predicate| !matches!(predicate.op, Op::Lt | Op::LtEq | Op::Wildcard(WildcardVersion::Major)))
+        .map(|predicate| Version::new(predicate.major, predicate.minor.unwrap_or(0), predicate.patch.unwrap_or(0)))
+        .max()
+}
+
+/// Versions with the same value are semver compatible with each other
+pub fn compatibility(version: &Version) -> (u64, u64, u64) {
+    if version.major > 0 {
+        (version.major, 0, 0)
+    } else if version.minor > 0 {
+        (0, version.minor, 0)
+    } else {
+        (0, 0, version.patch)
+    }
+}
+
+/// Classifies a change of version requirement by its lowest accepted version
+pub fn classify_requirement_change(old: &VersionReq, new: &VersionReq) -> SemverChange {
+    match (lower_bound(old), lower_bound(new)) {
+        (Some(old), Some(new)) => {
+            if compatibility(&old) != compatibility(&new) {
+                SemverChange::Major
+            } else if old.minor != new.minor {
+                SemverChange::Minor
+            } else if old.patch != new.patch {
+                SemverChange::Patch
+            } else {
+                SemverChange::Other
+            }
+        },
+        _ => SemverChange::Other,
+    }
+}
+
+/// Identifies a dependency by the table it is declared in and its key
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct DependencyId {
+    pub kind: DependencyKind,
+    /// `cfg(..)` expression or target triple of a `[target.*]` table
+    pub platform: Option<String>,
+    pub name: String,
+}
+
+impl DependencyId {
+    fn of(dependency: &DeclaredDependency) -> DependencyId {
+        DependencyId {
+            kind: dependency.kind,
+            platform: dependency.platform.to_owned(),
+            name: dependency.name.to_owned(),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "name": self.name,
+            "kind": self.kind.to_string(),
+            "platform": self.platform,
+        })
+    }
+}
+
+impl fmt::Display for DependencyId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.platform {
+            Some(platform) => write!(f, "target.'{}'.{}.{}", platform, self.kind, self.name),
+            None => write!(f, "{}.{}", self.kind, self.name),
+        }
+    }
+}
+
+/// A single semantic difference between two manifests
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    /// One manifest describes a package and the other a virtual workspace
+    ManifestKind { old: &'static str, new: &'static str },
+    /// A `[package]` field, keyed by its manifest key
+    Package { field: &'static str, old: Option<String>, new: Option<String> },
+    /// A `[workspace]` field of a virtual manifest
+    Workspace { field: &'static str, old: Option<String>, new: Option<String> },
+    DependencyAdded { dependency: DependencyId, requirement: Option<VersionReq> },
+    DependencyRemoved { dependency: DependencyId, requirement: Option<VersionReq> },
+    RequirementChanged {
+        dependency: DependencyId,
+        old: Option<VersionReq>,
+        new: Option<VersionReq>,
+        semver: SemverChange,
+    },
+    /// Any other option of a dependency, such as its source or features
+    DependencyChanged { dependency: DependencyId, field: &'static str, old: Option<String>, new: Option<String> },
+    FeatureAdded { feature: String, enables: Vec<String> },
+    FeatureRemoved { feature: String, enables: Vec<String> },
+    /// Values enabled by a feature which were added or removed
+    FeatureChanged { feature: String, added: Vec<String>, removed: Vec<String> },
+    /// A setting of a profile, where overrides are named like
+    /// `release.package.foo` and `release.build-override`
+    ProfileChanged { profile: String, setting: &'static str, old: Option<String>, new: Option<String> },
+    TargetAdded { kind: TargetKind, name: String },
+    TargetRemoved { kind: TargetKind, name: String },
+    TargetChanged { kind: TargetKind, name: String, field: &'static str, old: Option<String>, new: Option<String> },
+}
+
+fn or_unset(value: &Option<String>) -> &str {
+    value.as_ref().map_or("(unset)", |value| value.as_str())
+}
+
+fn or_any(req: &Option<VersionReq>) -> String {
+    req.as_ref().map_or_else(|| "(no version)".to_owned(), |req| req.to_string())
+}
+
+fn target_header(kind: TargetKind) -> String {
+    match kind {
+        TargetKind::Lib => "[lib]".to_owned(),
+        _ => format!("[[{}]]", kind),
+    }
+}
+
+impl Change {
+    /// The change as a JSON object tagged by its `change` kind
+    pub fn to_json(&self) -> serde_json::Value {
+        let req = |req: &Option<VersionReq>| req.as_ref().map(|req| req.to_string());
+        match self {
+            Change::ManifestKind { old, new } => json!({
+                "change": "manifest-kind", "old": old, "new": new,
+            }),
+            Change::Package { field, old, new } => json!({
+                "change": "package", "field": field, "old": old, "new": new,
+            }),
+            Change::Workspace { field, old, new } => json!({
+                "change": "workspace", "field": field, "old": old, "new": new,
+            }),
+            Change::DependencyAdded { dependency, requirement } => json!({
+                "change": "dependency-added", "dependency": dependency.to_json(), "requirement": req(requirement),
+            }),
+            Change::DependencyRemoved { dependency, requirement } => json!({
+                "change": "dependency-removed", "dependency": dependency.to_json(), "requirement": req(requirement),
+            }),
+            Change::RequirementChanged { dependency, old, new, semver } => json!({
+                "change": "requirement-changed",
+                "dependency": dependency.to_json(),
+                "old": req(old),
+                "new": req(new),
+                "semver": semver.to_string(),
+            }),
+            Change::DependencyChanged { dependency, field, old, new } => json!({
+                "change": "dependency-changed", "dependency": dependency.to_json(), "field": field, "old": old, "new": new,
+            }),
+            Change::FeatureAdded { feature, enables } => json!({
+                "change": "feature-added", "feature": feature, "enables": enables,
+            }),
+            Change::FeatureRemoved { feature, enables } => json!({
+                "change": "feature-removed", "feature": feature, "enables": enables,
+            }),
+            Change::FeatureChanged { feature, added, removed } => json!({
+                "change": "feature-changed", "feature": feature, "added": added, "removed": removed,
+            }),
+            Change::ProfileChanged { profile, setting, old, new } => json!({
+                "change": "profile-changed", "profile": profile, "setting": setting, "old": old, "new": new,
+            }),
+            Change::TargetAdded { kind, name } => json!({
+                "change": "target-added", "kind": kind.to_string(), "name": name,
+            }),
+            Change::TargetRemoved { kind, name } => json!({
+                "change": "target-removed", "kind": kind.to_string(), "name": name,
+            }),
+            Change::TargetChanged { kind, name, field, old, new } => json!({
+                "change": "target-changed", "kind": kind.to_string(), "name": name, "field": field, "old": old, "new": new,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::ManifestKind { old, new } => write!(f, "~ manifest changed from a {} to a {}", old, new),
+            Change::Package { field, old, new } => {
+                write!(f, "~ package.{}: {} -> {}", field, or_unset(old), or_unset(new))
+            },
+            Change::Workspace { field, old, new } => {
+                write!(f, "~ workspace.{}: {} -> {}", field, or_unset(old), or_unset(new))
+            },
+            Change::DependencyAdded { dependency, requirement } => {
+                write!(f, "+ {} {}", dependency, or_any(requirement))
+            },
+            Change::DependencyRemoved { dependency, requirement } => {
+                write!(f, "- {} {}", dependency, or_any(requirement))
+            },
+            Change::RequirementChanged { dependency, old, new, semver } => {
+                write!(f, "~ {}: {} -> {} ({})", dependency, or_any(old), or_any(new), semver)
+            },
+            Change::DependencyChanged { dependency, field, old, new } => {
+                write!(f, "~ {} {}: {} -> {}", dependency, field, or_unset(old), or_unset(new))
+            },
+            Change::FeatureAdded { feature, enables } => {
+                write!(f, "+ features.{} = {:?}", feature, enables)
+            },
+            Change::FeatureRemoved { feature, enables } => {
+                write!(f, "- features.{} = {:?}", feature, enables)
+            },
+            Change::FeatureChanged { feature, added, removed } => {
+                write!(f, "~ features.{}:", feature)?;
+                for value in added {
+                    write!(f, " +{}", value)?;
+                }
+                for value in removed {
+                    write!(f, " -{}", value)?;
+                }
+                Ok(())
+            },
+            Change::ProfileChanged { profile, setting, old, new } => {
+                write!(f, "~ profile.{}.{}: {} -> {}", profile, setting, or_unset(old), or_unset(new))
+            },
+            Change::TargetAdded { kind, name } => write!(f, "+ {} {}", target_header(*kind), name),
+            Change::TargetRemoved { kind, name } => write!(f, "- {} {}", target_header(*kind), name),
+            Change::TargetChanged { kind, name, field, old, new } => {
+                write!(f, "~ {} {} {}: {} -> {}", target_header(*kind), name, field, or_unset(old), or_unset(new))
+            },
+        }
+    }
+}
+
+/// Every change between two manifests, ordered by the section of the
+/// manifest they apply to.
+///
+/// `Display` renders one change per line for people, while `to_json`
+/// serves tools.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ManifestDiff {
+    pub changes: Vec<Change>,
+}
+
+impl ManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({ "changes": self.changes.iter().map(Change::to_json).collect::<Vec<_>>() })
+    }
+
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string_pretty(&self.to_json()).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ManifestDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sorted values joined by `, `, where order carries no meaning
+fn set(values: &[String]) -> Option<String> {
+    if values.is_empty() {
+        return None;
+    }
+    let values: BTreeSet<_> = values.iter().map(|value| value.as_str()).collect();
+    Some(values.into_iter().collect::<Vec<_>>().join(", "))
+}
+
+/// Values joined by `, `, where order carries meaning
+fn list(values: &[String]) -> Option<String> {
+    if values.is_empty() { None } else { Some(values.join(", ")) }
+}
+
+/// Lints as `name = level`, with the priority where it is not the default
+fn lints(lints: &[Lint]) -> Option<String> {
+    let settings: Vec<_> = lints.iter()
+        .map(|lint| match lint.priority {
+            0 => format!("{} = {}", lint.qualified_name(), lint.level),
+            priority => format!("{} = {} (priority {})", lint.qualified_name(), lint.level, priority),
+        })
+        .collect();
+    set(&settings)
+}
+
+fn package_fields(project: &Project) -> Vec<(&'static str, Option<String>)> {
+    let metadata = &project.metadata;
+    let url = |url: &Option<url::Url>| url.as_ref().map(|url| url.to_string());
+    let badges = metadata.badges.iter()
+        .flat_map(|(badge, attributes)| attributes.iter()
+            .map(move |(key, value)| format!("{}.{} = {}", badge, key, value)))
+        .collect::<Vec<_>>();
+    vec![
+        ("name", Some(project.name.to_owned())),
+        ("version", Some(project.version.to_string())),
+        ("edition", Some(project.edition.to_string())),
+        ("rust-version", project.rust_version.map(|v| v.to_string())),
+        ("resolver", Some(project.resolver.to_string())),
+        ("authors", list(&metadata.authors)),
+        ("keywords", set(&metadata.keywords)),
+        ("categories", set(&metadata.categories)),
+        ("license", metadata.license.to_owned()),
+        ("license-file", metadata.license_file.to_owned()),
+        ("description", metadata.description.to_owned()),
+        ("readme", metadata.readme.to_owned()),
+        ("homepage", url(&metadata.homepage)),
+        ("repository", url(&metadata.repository)),
+        ("documentation", url(&metadata.documentation)),
+        ("badges", list(&badges)),
+        ("build", match project.build {
+            BuildScript::Inferred => None,
+            ref build => Some(build.to_string()),
+        }),
+        ("links", metadata.links.to_owned()),
+        ("default-run", project.default_run.to_owned()),
+        ("autobins", if project.autobins { None } else { Some("false".to_owned()) }),
+        ("publish", project.publish.as_ref().map(|registries| {
+            if registries.is_empty() { "false".to_owned() } else { registries.join(", ") }
+        })),
+        ("lints", lints(&project.lints)),
+    ]
+}
+
+fn workspace_fields(workspace: &Workspace) -> Vec<(&'static str, Option<String>)> {
+    let paths = |paths: &Option<Vec<String>>| paths.as_ref().and_then(|paths| set(paths));
+    vec![
+        ("members", paths(&workspace.members)),
+        ("default-members", paths(&workspace.default_members)),
+        ("exclude", paths(&workspace.exclude)),
+        ("resolver", workspace.resolver.map(|r| r.to_string())),
+        ("lints", lints(&workspace.lints)),
+    ]
+}
+
+/// Pairs up fields of the same name which have different values
+fn changed_fields(
+    old: Vec<(&'static str, Option<String>)>,
+    new: Vec<(&'static str, Option<String>)>,
+) -> Vec<(&'static str, Option<String>, Option<String>)> {
+    old.into_iter()
+        .zip(new)
+        .filter(|((_, old), (_, new))| old != new)
+        .map(|((field, old), (_, new))| (field, old, new))
+        .collect()
+}
+
+/// Where a dependency comes from, without the version requirement which
+/// is compared separately
+fn source(dependency: &Dependency) -> String {
+    match dependency {
+        Dependency::DefaultRegistry(_) => "crates.io".to_owned(),
+        Dependency::CustomRegistry(registry) => format!("registry `{}`", registry),
+        Dependency::LocalPath(path) => format!("path `{}`", path.display()),
+        Dependency::Directory(path) => format!("directory `{}`", path.display()),
+        Dependency::Git(repository) => {
+            let reference = match &repository.reference {
+                GitReference::Tag(tag) => format!("tag={}", tag),
+                GitReference::Branch(branch) => format!("branch={}", branch),
+                GitReference::Rev(rev) => format!("rev={}", rev),
+            };
+            format!("git `{}?{}`", repository.repo, reference)
+        },
+    }
+}
+
+fn dependency_fields(dependency: &DeclaredDependency) -> Vec<(&'static str, Option<String>)> {
+    vec![
+        ("source", Some(source(&dependency.source))),
+        ("package", dependency.package.to_owned()),
+        ("optional", Some(dependency.optional.to_string())),
+        ("default-features", Some(dependency.default_features.to_string())),
+        ("features", set(&dependency.features)),
+    ]
+}
+
+fn diff_dependencies(old: &[DeclaredDependency], new: &[DeclaredDependency], changes: &mut Vec<Change>) {
+    let old: BTreeMap<_, _> = old.iter().map(|dep| (DependencyId::of(dep), dep)).collect();
+    let new: BTreeMap<_, _> = new.iter().map(|dep| (DependencyId::of(dep), dep)).collect();
+    for (id, old_dep) in &old {
+        let new_dep = match new.get(id) {
+            Some(new_dep) => new_dep,
+            None => {
+                changes.push(Change::DependencyRemoved { dependency: id.to_owned(), requirement: old_dep.version.to_owned() });
+                continue;
+            },
+        };
+        if old_dep.version != new_dep.version {
+            let semver = match (&old_dep.version, &new_dep.version) {
+                (Some(old), Some(new)) => classify_requirement_change(old, new),
+                _ => SemverChange::Other,
+            };
+            changes.push(Change::RequirementChanged {
+                dependency: id.to_owned(),
+                old: old_dep.version.to_owned(),
+                new: new_dep.version.to_owned(),
+                semver,
+            });
+        }
+        for (field, old, new) in changed_fields(dependency_fields(old_dep), dependency_fields(new_dep)) {
+            changes.push(Change::DependencyChanged { dependency: id.to_owned(), field, old, new });
+        }
+    }
+    for (id, new_dep) in &new {
+        if !old.contains_key(id) {
+            changes.push(Change::DependencyAdded { dependency: id.to_owned(), requirement: new_dep.version.to_owned() });
+        }
+    }
+}
+
+fn diff_features(old: &BTreeMap<String, Vec<String>>, new: &BTreeMap<String, Vec<String>>, changes: &mut Vec<Change>) {
+    for (feature, old_values) in old {
+        let new_values = match new.get(feature) {
+            Some(new_values) => new_values,
+            None => {
+                changes.push(Change::FeatureRemoved { feature: feature.to_owned(), enables: old_values.to_owned() });
+                continue;
+            },
+        };
+        let old_values: BTreeSet<_> = old_values.iter().collect();
+        let new_values: BTreeSet<_> = new_values.iter().collect();
+        if old_values != new_values {
+            changes.push(Change::FeatureChanged {
+                feature: feature.to_owned(),
+                added: new_values.difference(&old_values).map(|v| v.to_string()).collect(),
+                removed: old_values.difference(&new_values).map(|v| v.to_string()).collect(),
+            });
+        }
+    }
+    for (feature, new_values) in new {
+        if !old.contains_key(feature) {
+            changes.push(Change::FeatureAdded { feature: feature.to_owned(), enables: new_values.to_owned() });
+        }
+    }
+}
+
+/// Compares the settings of a profile and its overrides, where a missing
+/// profile or override counts as having no settings
+fn diff_profile(name: &str, old: &Profile, new: &Profile, changes: &mut Vec<Change>) {
+    for (setting, old, new) in changed_fields(old.settings(), new.settings()) {
+        changes.push(Change::ProfileChanged { profile: name.to_owned(), setting, old, new });
+    }
+    let specs: BTreeSet<_> = old.overrides.keys().chain(new.overrides.keys()).collect();
+    let unset = Profile::default();
+    for spec in specs {
+        diff_profile(
+            &format!("{}.package.{}", name, spec),
+            old.overrides.get(spec).unwrap_or(&unset),
+            new.overrides.get(spec).unwrap_or(&unset),
+            changes,
+        );
+    }
+    if old.build_override.is_some() || new.build_override.is_some() {
+        diff_profile(
+            &format!("{}.build-override", name),
+            old.build_override.as_deref().unwrap_or(&unset),
+            new.build_override.as_deref().unwrap_or(&unset),
+            changes,
+        );
+    }
+}
+
+fn diff_profiles(old: &BTreeMap<String, Profile>, new: &BTreeMap<String, Profile>, changes: &mut Vec<Change>) {
+    let names: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+    let unset = Profile::default();
+    for name in names {
+        diff_profile(name, old.get(name).unwrap_or(&unset), new.get(name).unwrap_or(&unset), changes);
+    }
+}
+
+fn target_fields(target: &Target) -> Vec<(&'static str, Option<String>)> {
+    let flag = |flag: Option<bool>| flag.map(|flag| flag.to_string());
+    vec![
+        ("path", target.path.as_ref().map(|path| path.display().to_string())),
+        ("crate-type", set(&target.crate_types)),
+        ("required-features", set(&target.required_features)),
+        ("edition", target.edition.map(|edition| edition.to_string())),
+        ("test", flag(target.test)),
+        ("doctest", flag(target.doctest)),
+        ("bench", flag(target.bench)),
+        ("doc", flag(target.doc)),
+        ("harness", flag(target.harness)),
+        ("proc-macro", flag(target.proc_macro)),
+    ]
+}
+
+fn diff_targets(old: &[Target], new: &[Target], changes: &mut Vec<Change>) {
+    let old: BTreeMap<_, _> = old.iter().map(|target| ((target.kind, target.name.to_owned()), target)).collect();
+    let new: BTreeMap<_, _> = new.iter().map(|target| ((target.kind, target.name.to_owned()), target)).collect();
+    for ((kind, name), old_target) in &old {
+        match new.get(&(*kind, name.to_owned())) {
+            Some(new_target) => {
+                for (field, old, new) in changed_fields(target_fields(old_target), target_fields(new_target)) {
+                    changes.push(Change::TargetChanged { kind: *kind, name: name.to_owned(), field, old, new });
+                }
+            },
+            None => changes.push(Change::TargetRemoved { kind: *kind, name: name.to_owned() }),
+        }
+    }
+    for (kind, name) in new.keys() {
+        if !old.contains_key(&(*kind, name.to_owned())) {
+            changes.push(Change::TargetAdded { kind: *kind, name: name.to_owned() });
+        }
+    }
+}
+
+fn manifest_kind(manifest: &Manifest) -> &'static str {
+    match manifest {
+        Manifest::Workspace(_) => "workspace",
+        Manifest::Project(_) => "package",
+    }
+}
+
+/// Compares two manifests by what they mean rather than how they are
+/// written, so formatting and the order of tables, keys and unordered
+/// lists such as features make no difference.
+pub fn diff(old: &Manifest, new: &Manifest) -> ManifestDiff {
+    let mut changes = vec![];
+    match (old, new) {
+        (Manifest::Project(old), Manifest::Project(new)) => {
+            for (field, old, new) in changed_fields(package_fields(old), package_fields(new)) {
+                changes.push(Change::Package { field, old, new });
+            }
+            diff_dependencies(&old.dependencies, &new.dependencies, &mut changes);
+            diff_features(&old.features, &new.features, &mut changes);
+            diff_profiles(&old.profiles, &new.profiles, &mut changes);
+            diff_targets(&old.targets, &new.targets, &mut changes);
+        },
+        (Manifest::Workspace(old), Manifest::Workspace(new)) => {
+            for (field, old, new) in changed_fields(workspace_fields(old), workspace_fields(new)) {
+                changes.push(Change::Workspace { field, old, new });
+            }
+        },
+        _ => changes.push(Change::ManifestKind { old: manifest_kind(old), new: manifest_kind(new) }),
+    }
+    ManifestDiff { changes }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ PathBuf };
+
+    use semver::{ Version, VersionReq };
+
+    use crate::{
+        DeclaredDependency, DependencyKind, Manifest, Profile, Project, Target, TargetKind, Workspace,
+    };
+    use super::{ classify_requirement_change, diff, Change, DependencyId, SemverChange };
+
+    fn project() -> Project {
+        Project::new("foo", Version::parse("1.0.0").unwrap())
+    }
+
+    fn dependency(name: &str, version: &str) -> DeclaredDependency {
+        DeclaredDependency::registry(name, DependencyKind::Normal, VersionReq::parse(version).unwrap())
+    }
+
+    fn id(name: &str) -> DependencyId {
+        DependencyId { kind: DependencyKind::Normal, platform: None, name: name.to_owned() }
+    }
+
+    fn classify(old: &str, new: &str) -> SemverChange {
+        classify_requirement_change(&VersionReq::parse(old).unwrap(), &VersionReq::parse(new).unwrap())
+    }
+
+    #[test]
+    fn classify_requirement_changes() {
+        assert_eq!(SemverChange::Major, classify("^1.2", "^2"));
+        assert_eq!(SemverChange::Major, classify("0.1", "0.2"));
+        assert_eq!(SemverChange::Major, classify("0.0.1", "0.0.2"));
+        assert_eq!(SemverChange::Minor, classify("1.2", "1.3"));
+        assert_eq!(SemverChange::Minor, classify("~1.2", "~1.3"));
+        assert_eq!(SemverChange::Patch, classify("1.2.3", "1.2.4"));
+        assert_eq!(SemverChange::Patch, classify("0.2.3", "=0.2.5"));
+        assert_eq!(SemverChange::Patch, classify("1.2.3-alpha.1", "1.2.4"));
+        assert_eq!(SemverChange::Minor, classify(">=1.2, <1.5", "1.4"));
+        assert_eq!(SemverChange::Other, classify("^1.2", ">=1.2, <1.5"));
+        assert_eq!(SemverChange::Other, classify("1.*", "*"));
+    }
+
+    #[test]
+    fn find_no_changes_in_equal_manifests() {
+        let mut project = project();
+        project.dependencies.push(dependency("serde", "1.0"));
+        let manifest = Manifest::Project(project);
+        assert!(diff(&manifest, &manifest).is_empty());
+    }
+
+    #[test]
+    fn diff_dependencies() {
+        let mut old = project();
+        old.dependencies = vec![dependency("serde", "^1.2"), dependency("log", "0.4")];
+        let mut new = project();
+        let mut serde = dependency("serde", "^2");
+        serde.features = vec!["derive".to_owned()];
+        new.dependencies = vec![serde, dependency("rand", "0.8")];
+
+        let changes = diff(&Manifest::Project(old), &Manifest::Project(new)).changes;
+        assert_eq!(vec![
+            Change::DependencyRemoved { dependency: id("log"), requirement: Some(VersionReq::parse("0.4").unwrap()) },
+            Change::RequirementChanged {
+                dependency: id("serde"),
+                old: Some(VersionReq::parse("^1.2").unwrap()),
+                new: Some(VersionReq::parse("^2").unwrap()),
+                semver: SemverChange::Major,
+            },
+            Change::DependencyChanged { dependency: id("serde"), field: "features", old: None, new: Some("derive".to_owned()) },
+            Change::DependencyAdded { dependency: id("rand"), requirement: Some(VersionReq::parse("0.8").unwrap()) },
+        ], changes);
+    }
+
+    #[test]
+    fn tell_apart_dependency_tables() {
+        let mut old = project();
+        old.dependencies = vec![dependency("log", "0.4")];
+        let mut new = project();
+        let mut log = dependency("log", "0.4");
+        log.kind = DependencyKind::Development;
+        log.platform = Some("cfg(unix)".to_owned());
+        new.dependencies = vec![log];
+
+        let diff = diff(&Manifest::Project(old), &Manifest::Project(new));
+        assert_eq!("\
+- dependencies.log ^0.4
++ target.'cfg(unix)'.dev-dependencies.log ^0.4
+", diff.to_string());
+    }
+
+    #[test]
+    fn diff_features_profiles_and_targets() {
+        let mut old = project();
+        old.features.insert("default".to_owned(), vec!["std".to_owned()]);
+        old.features.insert("std".to_owned(), vec![]);
+        old.targets.push(Target {
+            kind: TargetKind::Bin,
+            name: "tool".to_owned(),
+            path: Some(PathBuf::from("src/tool.rs")),
+            crate_types: vec![],
+            required_features: vec![],
+            edition: None,
+            test: None,
+            doctest: None,
+            bench: None,
+            doc: None,
+            harness: None,
+            proc_macro: None,
+        });
+        let mut new = old.clone();
+        new.features.insert("default".to_owned(), vec!["alloc".to_owned()]);
+        new.features.remove("std");
+        new.features.insert("alloc".to_owned(), vec![]);
+        let mut release = Profile { lto: Some("thin".to_owned()), ..Default::default() };
+        release.overrides.insert("*".to_owned(), Profile { opt_level: Some("3".to_owned()), ..Default::default() });
+        new.profiles.insert("release".to_owned(), release);
+        new.targets[0].path = Some(PathBuf::from("src/bin/tool.rs"));
+        new.version = Version::parse("1.1.0").unwrap();
+
+        let diff = diff(&Manifest::Project(old), &Manifest::Project(new));
+        assert_eq!("\
+~ package.version: 1.0.0 -> 1.1.0
+~ features.default: +alloc -std
+- features.std = []
++ features.alloc = []
+~ profile.release.lto: (unset) -> thin
+~ profile.release.package.*.opt-level: (unset) -> 3
+~ [[bin]] tool path: src/tool.rs -> src/bin/tool.rs
+", diff.to_string());
+    }
+
+    #[test]
+    fn render_json() {
+        let mut old = project();
+        old.dependencies = vec![dependency("serde", "1.2")];
+        let mut new = project();
+        new.dependencies = vec![dependency("serde", "1.3")];
+        new.metadata.license = Some("MIT".to_owned());
+
+        let diff = diff(&Manifest::Project(old), &Manifest::Project(new));
+        assert_eq!(json!({
+            "changes": [
+                { "change": "package", "field": "license", "old": null, "new": "MIT" },
+                {
+                    "change": "requirement-changed",
+                    "dependency": { "name": "serde", "kind": "dependencies", "platform": null },
+                    "old": "^1.2",
+                    "new": "^1.3",
+                    "semver": "minor",
+                },
+            ],
+        }), diff.to_json());
+    }
+
+    #[test]
+    fn diff_workspaces_and_manifest_kinds() {
+        let workspace = |members: Vec<&str>| Manifest::Workspace(Workspace {
+            members: Some(members.into_iter().map(|m| m.to_owned()).collect()),
+            default_members: None,
+            exclude: None,
+            resolver: None,
+            lints: vec![],
+        });
+        assert!(diff(&workspace(vec!["a", "b"]), &workspace(vec!["b", "a"])).is_empty());
+        assert_eq!(
+            vec![Change::Workspace { field: "members", old: Some("a, b".to_owned()), new: Some("a, b, c".to_owned()) }],
+            diff(&workspace(vec!["a", "b"]), &workspace(vec!["a", "b", "c"])).changes
+        );
+        assert_eq!(
+            vec![Change::ManifestKind { old: "workspace", new: "package" }],
+            diff(&workspace(vec![]), &Manifest::Project(project())).changes
+        );
+    }
+}
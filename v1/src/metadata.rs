@@ -1,9 +1,11 @@
+use std::collections::{ BTreeMap };
+
 use url::{ Url };
 
 /// General metadata about a package.
 ///
 /// These fields are not validated, but rather accept any valid TOML specification values.
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Default)]
 pub struct Metadata {
     pub authors: Vec<String>,
     pub keywords: Vec<String>,
@@ -18,4 +20,4 @@ pub struct Metadata {
     pub documentation: Option<Url>,
     pub badges: BTreeMap<String, BTreeMap<String, String>>,
     pub links: Option<String>,
-}
\ No newline at end of file
+}
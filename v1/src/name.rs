@@ -0,0 +1,197 @@
+use std::fmt;
+
+use unicode_xid::UnicodeXID;
+
+/// Reason a package or dependency name is rejected
+#[derive(Clone, Debug, PartialEq)]
+pub enum NameError {
+    Empty,
+    StartsWithDigit(char),
+    InvalidStartCharacter(char),
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NameError::Empty => write!(f, "name cannot be empty"),
+            NameError::StartsWithDigit(_) => write!(f, "the name cannot start with a digit"),
+            NameError::InvalidStartCharacter(ch) => write!(
+                f,
+                "invalid character `{}`, the first character must be a Unicode XID start character (most letters or `_`)",
+                ch
+            ),
+            NameError::InvalidCharacter(ch) => write!(
+                f,
+                "invalid character `{}`, characters must be Unicode XID characters (numbers, `-`, `_`, or most letters)",
+                ch
+            ),
+        }
+    }
+}
+
+/// Names which are valid but cause problems when used for a package
+#[derive(Clone, Debug, PartialEq)]
+pub enum RestrictedName {
+    /// A Rust keyword, which cannot be used as a crate name in code
+    Keyword,
+    /// Conflicts with Rust's built-in `test` library
+    Test,
+    /// Part of the standard library, e.g. `std` or `core`
+    StandardLibrary,
+    /// Conflicts with a directory cargo creates in the target directory
+    ArtifactDirectory,
+    /// A reserved Windows device name, e.g. `con` or `nul`
+    WindowsReserved,
+    /// Non-ASCII crate names are not supported by Rust
+    NonAscii,
+}
+
+impl fmt::Display for RestrictedName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            RestrictedName::Keyword => "it is a Rust keyword",
+            RestrictedName::Test => "it conflicts with Rust's built-in test library",
+            RestrictedName::StandardLibrary => "it is part of Rust's standard library",
+            RestrictedName::ArtifactDirectory => "it conflicts with cargo's build directory names",
+            RestrictedName::WindowsReserved => "it is a reserved Windows filename",
+            RestrictedName::NonAscii => "it contains non-ASCII characters which are not supported by Rust",
+        })
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "Self", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv",
+    "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "try",
+    "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+const STANDARD_LIBRARY: &[&str] = &["alloc", "core", "proc-macro", "proc_macro", "std"];
+
+const ARTIFACT_DIRECTORIES: &[&str] = &["build", "deps", "examples", "incremental"];
+
+const WINDOWS_RESERVED: &[&str] = &[
+    "aux", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9", "con",
+    "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9", "nul", "prn",
+];
+
+/// Checks a package name, dependency key or `package` rename against the
+/// characters cargo accepts.
+///
+/// The first character must be `_` or a Unicode XID start character, and
+/// the rest Unicode XID continue characters or `-`.
+pub fn validate_name(name: &str) -> std::result::Result<(), NameError> {
+    let mut chars = name.chars();
+    match chars.next() {
+        None => return Err(NameError::Empty),
+        Some(ch) if ch.is_ascii_digit() => return Err(NameError::StartsWithDigit(ch)),
+        Some(ch) if !(UnicodeXID::is_xid_start(ch) || ch == '_') => {
+            return Err(NameError::InvalidStartCharacter(ch))
+        },
+        Some(_) => {},
+    }
+    match chars.find(|&ch| !(UnicodeXID::is_xid_continue(ch) || ch == '-')) {
+        Some(ch) => Err(NameError::InvalidCharacter(ch)),
+        None => Ok(()),
+    }
+}
+
+/// Lists the reasons a valid name is still a poor choice for a package
+pub fn restricted_names(name: &str) -> Vec<RestrictedName> {
+    let lower = name.to_lowercase();
+    let mut restricted = vec![];
+    if KEYWORDS.contains(&name) {
+        restricted.push(RestrictedName::Keyword);
+    }
+    if name == "test" {
+        restricted.push(RestrictedName::Test);
+    }
+    if STANDARD_LIBRARY.contains(&name) {
+        restricted.push(RestrictedName::StandardLibrary);
+    }
+    if ARTIFACT_DIRECTORIES.contains(&name) {
+        restricted.push(RestrictedName::ArtifactDirectory);
+    }
+    if WINDOWS_RESERVED.contains(&lower.as_str()) {
+        restricted.push(RestrictedName::WindowsReserved);
+    }
+    if !name.is_ascii() {
+        restricted.push(RestrictedName::NonAscii);
+    }
+    restricted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ restricted_names, validate_name, NameError, RestrictedName };
+
+    #[test]
+    fn accept_valid_names() {
+        for name in &["foo", "foo-bar", "foo_bar", "_foo", "Foo2", "café"] {
+            assert_eq!(Ok(()), validate_name(name), "should accept `{}`", name);
+        }
+    }
+
+    #[test]
+    fn reject_empty_name() {
+        assert_eq!(Err(NameError::Empty), validate_name(""));
+    }
+
+    #[test]
+    fn reject_leading_digit() {
+        assert_eq!(Err(NameError::StartsWithDigit('1')), validate_name("1foo"));
+    }
+
+    #[test]
+    fn reject_invalid_start_character() {
+        assert_eq!(Err(NameError::InvalidStartCharacter('-')), validate_name("-foo"));
+    }
+
+    #[test]
+    fn reject_invalid_characters() {
+        assert_eq!(Err(NameError::InvalidCharacter('.')), validate_name("foo.bar"));
+        assert_eq!(Err(NameError::InvalidCharacter(' ')), validate_name("foo bar"));
+        assert_eq!(Err(NameError::InvalidCharacter('+')), validate_name("foo+"));
+    }
+
+    #[test]
+    fn restrict_keywords() {
+        assert_eq!(vec![RestrictedName::Keyword], restricted_names("fn"));
+    }
+
+    #[test]
+    fn restrict_test() {
+        assert_eq!(vec![RestrictedName::Test], restricted_names("test"));
+    }
+
+    #[test]
+    fn restrict_standard_library_names() {
+        assert_eq!(vec![RestrictedName::StandardLibrary], restricted_names("std"));
+        assert_eq!(vec![RestrictedName::StandardLibrary], restricted_names("core"));
+        assert_eq!(vec![RestrictedName::StandardLibrary], restricted_names("proc-macro"));
+    }
+
+    #[test]
+    fn restrict_artifact_directories() {
+        assert_eq!(vec![RestrictedName::ArtifactDirectory], restricted_names("deps"));
+    }
+
+    #[test]
+    fn restrict_windows_device_names_ignoring_case() {
+        assert_eq!(vec![RestrictedName::WindowsReserved], restricted_names("nul"));
+        assert_eq!(vec![RestrictedName::WindowsReserved], restricted_names("COM1"));
+    }
+
+    #[test]
+    fn restrict_non_ascii_names() {
+        assert_eq!(vec![RestrictedName::NonAscii], restricted_names("café"));
+    }
+
+    #[test]
+    fn not_restrict_ordinary_names() {
+        assert!(restricted_names("serde").is_empty());
+        assert!(restricted_names("console").is_empty());
+    }
+}
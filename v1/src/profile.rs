@@ -1,3 +1,4 @@
+use std::collections::{ BTreeMap };
 
 pub enum ProfileTypes {
     Test,
@@ -7,5 +8,46 @@ pub enum ProfileTypes {
     Release,
 }
 
-#[derive(Debug, PartialEq)]
-pub struct Profile {}
\ No newline at end of file
+/// Settings of a `[profile.*]` table, where unset values keep the defaults
+/// of the profile.
+///
+/// Values which may be written as either a string or another TOML type keep
+/// the form they were written in, e.g. `lto = true` and `lto = "fat"`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    /// `0` to `3`, `s` or `z`
+    pub opt_level: Option<String>,
+    pub lto: Option<String>,
+    pub codegen_units: Option<u32>,
+    /// `true`, `false` or a debuginfo level
+    pub debug: Option<String>,
+    pub debug_assertions: Option<bool>,
+    pub rpath: Option<bool>,
+    pub panic: Option<String>,
+    pub overflow_checks: Option<bool>,
+    pub incremental: Option<bool>,
+    /// Profile a custom profile takes the settings it leaves unset from
+    pub inherits: Option<String>,
+    /// `[profile.*.package.<spec>]` overrides keyed by package spec, where
+    /// `*` applies to every dependency
+    pub overrides: BTreeMap<String, Profile>,
+    pub build_override: Option<Box<Profile>>,
+}
+
+impl Profile {
+    /// Every setting other than the overrides, by its manifest key
+    pub fn settings(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("opt-level", self.opt_level.to_owned()),
+            ("lto", self.lto.to_owned()),
+            ("codegen-units", self.codegen_units.map(|v| v.to_string())),
+            ("debug", self.debug.to_owned()),
+            ("debug-assertions", self.debug_assertions.map(|v| v.to_string())),
+            ("rpath", self.rpath.map(|v| v.to_string())),
+            ("panic", self.panic.to_owned()),
+            ("overflow-checks", self.overflow_checks.map(|v| v.to_string())),
+            ("incremental", self.incremental.map(|v| v.to_string())),
+            ("inherits", self.inherits.to_owned()),
+        ]
+    }
+}
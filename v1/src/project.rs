@@ -0,0 +1,60 @@
+use std::collections::{ BTreeMap };
+
+use semver::Version;
+
+use crate::{ BuildScript, DeclaredDependency, Edition, Lint, Metadata, Profile, Resolver, RustVersion, Target };
+
+/// A package described by a manifest's `[package]` section.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Project {
+    pub name: String,
+    pub version: Version,
+    pub edition: Edition,
+    pub rust_version: Option<RustVersion>,
+    /// Explicit `resolver` or the default implied by the edition
+    pub resolver: Resolver,
+    pub metadata: Metadata,
+    /// Registries the package may be published to, where `None` allows any
+    /// registry and an empty list (`publish = false`) prevents publishing
+    pub publish: Option<Vec<String>>,
+    pub build: BuildScript,
+    /// Binary `cargo run` runs when none is selected
+    pub default_run: Option<String>,
+    /// Dependencies from every dependency table, including `[target.*]` ones
+    pub dependencies: Vec<DeclaredDependency>,
+    /// `[features]` with the features and dependencies each one enables
+    pub features: BTreeMap<String, Vec<String>>,
+    /// `[profile.*]` tables keyed by profile name
+    pub profiles: BTreeMap<String, Profile>,
+    pub targets: Vec<Target>,
+    /// Whether binaries are discovered from `src/main.rs` and `src/bin`
+    /// besides the `[[bin]]` targets
+    pub autobins: bool,
+    /// `[lints]` of every tool, including those inherited from the workspace
+    pub lints: Vec<Lint>,
+}
+
+impl Project {
+    /// A package with cargo's defaults for everything but its `name` and
+    /// `version`, as if those were the only keys of its `[package]`
+    pub fn new(name: &str, version: Version) -> Self {
+        let edition = Edition::default();
+        Project {
+            name: name.to_owned(),
+            version,
+            edition,
+            rust_version: None,
+            resolver: edition.default_resolver(),
+            metadata: Metadata::default(),
+            publish: None,
+            build: BuildScript::default(),
+            default_run: None,
+            dependencies: vec![],
+            features: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            targets: vec![],
+            autobins: true,
+            lints: vec![],
+        }
+    }
+}
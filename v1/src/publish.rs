@@ -0,0 +1,405 @@
+use std::fmt;
+use std::path::{ Path, PathBuf };
+
+use semver::VersionReq;
+
+use crate::{ DeclaredDependency, Dependency, DependencyKind, Manifest, Project };
+
+/// Name cargo uses for crates.io in `publish` lists
+pub const CRATES_IO_REGISTRY: &str = "crates-io";
+
+/// Most keywords crates.io accepts for a package
+pub const MAX_KEYWORDS: usize = 5;
+
+/// Longest keyword crates.io accepts
+pub const MAX_KEYWORD_LENGTH: usize = 20;
+
+/// Category slugs accepted by crates.io
+pub const CATEGORIES: &[&str] = &[
+    "accessibility", "aerospace", "aerospace::drones", "aerospace::protocols",
+    "aerospace::simulation", "aerospace::space-protocols", "aerospace::unmanned-aerial-vehicles",
+    "algorithms", "api-bindings", "asynchronous", "authentication", "caching",
+    "command-line-interface", "command-line-utilities", "compilers", "compression",
+    "computer-vision", "concurrency", "config", "cryptography", "cryptography::cryptocurrencies",
+    "data-structures", "database", "database-implementations", "date-and-time",
+    "development-tools", "development-tools::build-utils", "development-tools::cargo-plugins",
+    "development-tools::debugging", "development-tools::ffi",
+    "development-tools::procedural-macro-helpers", "development-tools::profiling",
+    "development-tools::testing", "email", "embedded", "emulators", "encoding",
+    "external-ffi-bindings", "filesystem", "finance", "game-development", "game-engines",
+    "games", "graphics", "gui", "hardware-support", "internationalization", "localization",
+    "mathematics", "memory-management", "multimedia", "multimedia::audio",
+    "multimedia::encoding", "multimedia::images", "multimedia::video", "network-programming",
+    "no-std", "no-std::no-alloc", "os", "os::android-apis", "os::freebsd-apis", "os::linux-apis",
+    "os::macos-apis", "os::unix-apis", "os::windows-apis", "parser-implementations", "parsing",
+    "rendering", "rendering::data-formats", "rendering::engine", "rendering::graphics-api",
+    "rust-patterns", "science", "science::bioinformatics", "science::bioinformatics::genomics",
+    "science::bioinformatics::proteomics", "science::bioinformatics::sequence-analysis",
+    "science::geo", "science::neuroscience", "science::robotics", "simulation",
+    "template-engine", "text-editors", "text-processing", "value-formatting", "virtualization",
+    "visualization", "wasm", "web-programming", "web-programming::http-client",
+    "web-programming::http-server", "web-programming::websocket",
+];
+
+/// A problem which would stop, or degrade, publishing a package to crates.io
+#[derive(Clone, Debug, PartialEq)]
+pub enum PublishIssue {
+    /// The manifest describes a workspace rather than a package
+    NotAPackage,
+    MissingDescription,
+    /// Neither `license` nor `license-file` is set
+    MissingLicense,
+    /// `license-file` names a file which does not exist
+    MissingLicenseFile(PathBuf),
+    /// `readme` names a file which does not exist
+    MissingReadme(PathBuf),
+    /// `publish = false`
+    PublishDisabled,
+    /// `publish` lists registries which do not include crates.io
+    RegistryNotAllowed(Vec<String>),
+    /// A path dependency has no `version` to publish in its place
+    PathDependencyWithoutVersion(String),
+    /// A git dependency has no `version` to publish in its place
+    GitDependencyWithoutVersion(String),
+    /// A git dependency's source is replaced by its `version` when published
+    GitSourceStripped(String),
+    /// A dependency comes from a registry other than crates.io
+    RegistryDependency(String, String),
+    /// A dependency requires `*`
+    WildcardVersion(String),
+    TooManyKeywords(usize),
+    InvalidKeyword(String),
+    /// Not a crates.io category slug, which crates.io ignores
+    InvalidCategory(String),
+}
+
+impl fmt::Display for PublishIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PublishIssue::NotAPackage => write!(f, "the manifest is a virtual manifest and cannot be published"),
+            PublishIssue::MissingDescription => write!(f, "missing or empty metadata field: description"),
+            PublishIssue::MissingLicense => write!(f, "missing metadata field: one of license or license-file is required"),
+            PublishIssue::MissingLicenseFile(path) => {
+                write!(f, "license-file `{}` does not appear to exist", path.display())
+            },
+            PublishIssue::MissingReadme(path) => {
+                write!(f, "readme `{}` does not appear to exist", path.display())
+            },
+            PublishIssue::PublishDisabled => write!(f, "the package has `publish = false` set"),
+            PublishIssue::RegistryNotAllowed(registries) => write!(
+                f,
+                "the package may only be published to {:?}, which does not include {}",
+                registries, CRATES_IO_REGISTRY
+            ),
+            PublishIssue::PathDependencyWithoutVersion(name) => {
+                write!(f, "path dependency `{}` does not specify a version", name)
+            },
+            PublishIssue::GitDependencyWithoutVersion(name) => write!(
+                f,
+                "git dependency `{}` does not specify a version, git dependencies are not allowed on crates.io",
+                name
+            ),
+            PublishIssue::GitSourceStripped(name) => {
+                write!(f, "git source of dependency `{}` is replaced by its version when published", name)
+            },
+            PublishIssue::RegistryDependency(name, registry) => {
+                write!(f, "dependency `{}` comes from registry `{}` rather than crates.io", name, registry)
+            },
+            PublishIssue::WildcardVersion(name) => {
+                write!(f, "dependency `{}` has a wildcard (`*`) version requirement which is not allowed", name)
+            },
+            PublishIssue::TooManyKeywords(count) => {
+                write!(f, "{} keywords given but at most {} are allowed", count, MAX_KEYWORDS)
+            },
+            PublishIssue::InvalidKeyword(keyword) => write!(
+                f,
+                "invalid keyword `{}`, keywords must start with a letter or number, contain only letters, numbers, `_`, `-` or `+` and be at most {} characters",
+                keyword, MAX_KEYWORD_LENGTH
+            ),
+            PublishIssue::InvalidCategory(category) => {
+                write!(f, "`{}` is not a valid category slug and will be ignored", category)
+            },
+        }
+    }
+}
+
+/// Result of checking a manifest before publishing it to crates.io
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PublishReport {
+    /// Issues which cargo or crates.io reject
+    pub errors: Vec<PublishIssue>,
+    /// Issues which are accepted but change or drop part of the manifest
+    pub warnings: Vec<PublishIssue>,
+}
+
+impl PublishReport {
+    pub fn is_publishable(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+fn is_blank(value: &Option<String>) -> bool {
+    value.as_ref().is_none_or(|v| v.trim().is_empty())
+}
+
+fn is_valid_keyword(keyword: &str) -> bool {
+    let mut chars = keyword.chars();
+    keyword.len() <= MAX_KEYWORD_LENGTH
+        && chars.next().is_some_and(|ch| ch.is_ascii_alphanumeric())
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' || ch == '+')
+}
+
+fn is_wildcard(req: &VersionReq) -> bool {
+    req.to_string() == "*"
+}
+
+fn verify_dependency(dep: &DeclaredDependency, report: &mut PublishReport) {
+    let name = dep.name.to_owned();
+    if dep.version.as_ref().is_some_and(is_wildcard) {
+        report.errors.push(PublishIssue::WildcardVersion(name.to_owned()));
+    }
+    // Cargo drops dev-dependencies without a version rather than failing
+    let stripped = dep.kind == DependencyKind::Development && dep.version.is_none();
+    match &dep.source {
+        Dependency::DefaultRegistry(req) => {
+            if dep.version.is_none() && is_wildcard(req) {
+                report.errors.push(PublishIssue::WildcardVersion(name));
+            }
+        },
+        Dependency::LocalPath(_) | Dependency::Directory(_) => {
+            if dep.version.is_none() && !stripped {
+                report.errors.push(PublishIssue::PathDependencyWithoutVersion(name));
+            }
+        },
+        Dependency::Git(_) => {
+            if dep.version.is_some() {
+                report.warnings.push(PublishIssue::GitSourceStripped(name));
+            } else if !stripped {
+                report.errors.push(PublishIssue::GitDependencyWithoutVersion(name));
+            }
+        },
+        Dependency::CustomRegistry(registry) => {
+            if registry != CRATES_IO_REGISTRY {
+                report.errors.push(PublishIssue::RegistryDependency(name, registry.to_owned()));
+            }
+        },
+    }
+}
+
+fn verify_project(project: &Project, package_root: &Path, report: &mut PublishReport) {
+    let metadata = &project.metadata;
+    if is_blank(&metadata.description) {
+        report.errors.push(PublishIssue::MissingDescription);
+    }
+    if is_blank(&metadata.license) && is_blank(&metadata.license_file) {
+        report.errors.push(PublishIssue::MissingLicense);
+    }
+    if let Some(license_file) = &metadata.license_file {
+        if !package_root.join(license_file).is_file() {
+            report.errors.push(PublishIssue::MissingLicenseFile(license_file.into()));
+        }
+    }
+    if let Some(readme) = &metadata.readme {
+        if !package_root.join(readme).is_file() {
+            report.errors.push(PublishIssue::MissingReadme(readme.into()));
+        }
+    }
+
+    match &project.publish {
+        Some(registries) if registries.is_empty() => report.errors.push(PublishIssue::PublishDisabled),
+        Some(registries) if !registries.iter().any(|r| r == CRATES_IO_REGISTRY) => {
+            report.errors.push(PublishIssue::RegistryNotAllowed(registries.to_owned()))
+        },
+        _ => {},
+    }
+
+    if metadata.keywords.len() > MAX_KEYWORDS {
+        report.errors.push(PublishIssue::TooManyKeywords(metadata.keywords.len()));
+    }
+    for keyword in metadata.keywords.iter().filter(|k| !is_valid_keyword(k)) {
+        report.errors.push(PublishIssue::InvalidKeyword(keyword.to_owned()));
+    }
+    for category in metadata.categories.iter().filter(|c| !CATEGORIES.contains(&c.as_str())) {
+        report.warnings.push(PublishIssue::InvalidCategory(category.to_owned()));
+    }
+
+    for dep in &project.dependencies {
+        verify_dependency(dep, report);
+    }
+}
+
+/// Checks a manifest against the rules `cargo publish` and crates.io apply.
+///
+/// Files named by `readme` and `license-file` are looked up relative to
+/// `package_root`, the directory containing the manifest.
+pub fn verify_publishable(manifest: &Manifest, package_root: &Path) -> PublishReport {
+    let mut report = PublishReport::default();
+    match manifest {
+        Manifest::Workspace(_) => report.errors.push(PublishIssue::NotAPackage),
+        Manifest::Project(project) => verify_project(project, package_root, &mut report),
+    }
+    report
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use std::path::{ Path, PathBuf };
+
+    use semver::{ Version, VersionReq };
+
+    use crate::{
+        DeclaredDependency, Dependency, DependencyKind, GitRepository, Manifest, Metadata, Project, Workspace,
+    };
+    use super::{ verify_publishable, PublishIssue };
+
+    fn project() -> Project {
+        Project {
+            metadata: Metadata {
+                description: Some("A foo".to_owned()),
+                license: Some("MIT".to_owned()),
+                .. Default::default()
+            },
+            .. Project::new("foo", Version::parse("1.0.0").unwrap())
+        }
+    }
+
+    fn dependency(name: &str, kind: DependencyKind, source: Dependency, version: Option<&str>) -> DeclaredDependency {
+        DeclaredDependency {
+            version: version.map(|v| VersionReq::parse(v).unwrap()),
+            .. DeclaredDependency::new(name, kind, source)
+        }
+    }
+
+    fn verify(project: Project) -> (Vec<PublishIssue>, Vec<PublishIssue>) {
+        let report = verify_publishable(&Manifest::Project(project), Path::new(env!("CARGO_MANIFEST_DIR")));
+        (report.errors, report.warnings)
+    }
+
+    #[test]
+    fn accept_complete_package() {
+        let report = verify_publishable(&Manifest::Project(project()), Path::new("."));
+        assert!(report.is_publishable(), "unexpected issues: {:?}", report);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn reject_workspace() {
+        let workspace = Workspace { members: None, default_members: None, exclude: None, resolver: None, lints: vec![] };
+        let report = verify_publishable(&Manifest::Workspace(workspace), Path::new("."));
+        assert_eq!(vec![PublishIssue::NotAPackage], report.errors);
+    }
+
+    #[test]
+    fn reject_missing_description_and_license() {
+        let mut project = project();
+        project.metadata.description = Some(" ".to_owned());
+        project.metadata.license = None;
+        assert_eq!(vec![PublishIssue::MissingDescription, PublishIssue::MissingLicense], verify(project).0);
+    }
+
+    #[test]
+    fn accept_existing_license_file_and_readme() {
+        let mut project = project();
+        project.metadata.license = None;
+        project.metadata.license_file = Some("Cargo.toml".to_owned());
+        project.metadata.readme = Some("src/lib.rs".to_owned());
+        assert_eq!((vec![], vec![]), verify(project));
+    }
+
+    #[test]
+    fn reject_missing_readme() {
+        let mut project = project();
+        project.metadata.readme = Some("README.md".to_owned());
+        assert_eq!(vec![PublishIssue::MissingReadme(PathBuf::from("README.md"))], verify(project).0);
+    }
+
+    #[test]
+    fn reject_publish_false() {
+        let mut project = project();
+        project.publish = Some(vec![]);
+        assert_eq!(vec![PublishIssue::PublishDisabled], verify(project).0);
+    }
+
+    #[test]
+    fn reject_registry_restrictions() {
+        let mut project = project();
+        project.publish = Some(vec!["internal".to_owned()]);
+        assert_eq!(vec![PublishIssue::RegistryNotAllowed(vec!["internal".to_owned()])], verify(project).0);
+
+        let mut project = self::project();
+        project.publish = Some(vec!["internal".to_owned(), "crates-io".to_owned()]);
+        assert!(verify(project).0.is_empty());
+    }
+
+    #[test]
+    fn reject_path_dependency_without_version() {
+        let mut project = project();
+        project.dependencies = vec![
+            dependency("bar", DependencyKind::Normal, Dependency::LocalPath("../bar".into()), None),
+            dependency("baz", DependencyKind::Build, Dependency::LocalPath("../baz".into()), Some("1.0")),
+            dependency("qux", DependencyKind::Development, Dependency::LocalPath("../qux".into()), None),
+        ];
+        assert_eq!(vec![PublishIssue::PathDependencyWithoutVersion("bar".to_owned())], verify(project).0);
+    }
+
+    #[test]
+    fn reject_git_dependency_without_version() {
+        let repo = GitRepository::from_url_string("https://github.com/foo/bar".to_owned()).unwrap();
+        let mut project = project();
+        project.dependencies = vec![
+            dependency("bar", DependencyKind::Normal, Dependency::Git(repo.to_owned()), None),
+            dependency("baz", DependencyKind::Normal, Dependency::Git(repo), Some("1.0")),
+        ];
+        assert_eq!(
+            (
+                vec![PublishIssue::GitDependencyWithoutVersion("bar".to_owned())],
+                vec![PublishIssue::GitSourceStripped("baz".to_owned())],
+            ),
+            verify(project)
+        );
+    }
+
+    #[test]
+    fn reject_wildcard_versions() {
+        let mut project = project();
+        project.dependencies = vec![
+            dependency("bar", DependencyKind::Normal, Dependency::DefaultRegistry(VersionReq::parse("*").unwrap()), Some("*")),
+            dependency("baz", DependencyKind::Normal, Dependency::DefaultRegistry(VersionReq::parse("*").unwrap()), None),
+            dependency("qux", DependencyKind::Normal, Dependency::DefaultRegistry(VersionReq::parse("1.*").unwrap()), Some("1.*")),
+        ];
+        assert_eq!(
+            vec![PublishIssue::WildcardVersion("bar".to_owned()), PublishIssue::WildcardVersion("baz".to_owned())],
+            verify(project).0
+        );
+    }
+
+    #[test]
+    fn reject_dependency_from_other_registry() {
+        let mut project = project();
+        project.dependencies = vec![
+            dependency("bar", DependencyKind::Normal, Dependency::CustomRegistry("internal".to_owned()), Some("1.0")),
+        ];
+        assert_eq!(
+            vec![PublishIssue::RegistryDependency("bar".to_owned(), "internal".to_owned())],
+            verify(project).0
+        );
+    }
+
+    #[test]
+    fn reject_too_many_and_invalid_keywords() {
+        let mut project = project();
+        project.metadata.keywords = ["a", "b", "c", "d", "e", "-f"].iter().map(|k| k.to_string()).collect();
+        assert_eq!(
+            vec![PublishIssue::TooManyKeywords(6), PublishIssue::InvalidKeyword("-f".to_owned())],
+            verify(project).0
+        );
+    }
+
+    #[test]
+    fn warn_on_invalid_categories() {
+        let mut project = project();
+        project.metadata.categories = vec!["parsing".to_owned(), "parsers".to_owned()];
+        assert_eq!((vec![], vec![PublishIssue::InvalidCategory("parsers".to_owned())]), verify(project));
+    }
+}
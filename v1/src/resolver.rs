@@ -0,0 +1,20 @@
+use std::fmt;
+
+/// Version of the dependency resolver's feature unification behaviour.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Resolver {
+    V1,
+    V2,
+    /// `V2` plus `rust-version` aware version selection
+    V3,
+}
+
+impl fmt::Display for Resolver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Resolver::V1 => "1",
+            Resolver::V2 => "2",
+            Resolver::V3 => "3",
+        })
+    }
+}
@@ -0,0 +1,46 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A partial version of the Rust toolchain, e.g. `1.70`, where missing
+/// components compare as zero.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RustVersion {
+    pub major: u64,
+    pub minor: Option<u64>,
+    pub patch: Option<u64>,
+}
+
+impl RustVersion {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        RustVersion { major, minor: Some(minor), patch: Some(patch) }
+    }
+
+    fn key(&self) -> (u64, u64, u64) {
+        (self.major, self.minor.unwrap_or(0), self.patch.unwrap_or(0))
+    }
+}
+
+impl Ord for RustVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PartialOrd for RustVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for RustVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{}", minor)?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{}", patch)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use std::fmt;
+use std::path::{ PathBuf };
+
+use crate::{ Edition };
+
+/// The table a target was declared in
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum TargetKind {
+    /// `[lib]`
+    Lib,
+    /// `[[bin]]`
+    Bin,
+    /// `[[example]]`
+    Example,
+    /// `[[test]]`
+    Test,
+    /// `[[bench]]`
+    Bench,
+}
+
+impl fmt::Display for TargetKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            TargetKind::Lib => "lib",
+            TargetKind::Bin => "bin",
+            TargetKind::Example => "example",
+            TargetKind::Test => "test",
+            TargetKind::Bench => "bench",
+        })
+    }
+}
+
+/// A target declared in the manifest.
+///
+/// Targets cargo discovers from the layout of the package without a table
+/// in the manifest are not included.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    pub kind: TargetKind,
+    /// Explicit `name`, or the name cargo infers from the package name for
+    /// `[lib]` and from the file name of `path` for other targets
+    pub name: String,
+    pub path: Option<PathBuf>,
+    pub crate_types: Vec<String>,
+    pub required_features: Vec<String>,
+    pub edition: Option<Edition>,
+    pub test: Option<bool>,
+    pub doctest: Option<bool>,
+    pub bench: Option<bool>,
+    pub doc: Option<bool>,
+    pub harness: Option<bool>,
+    pub proc_macro: Option<bool>,
+}
@@ -0,0 +1,152 @@
+use std::collections::{ BTreeSet };
+
+use crate::{ CfgExpr, Error, Result };
+
+const UNIX_OS: [&str; 11] = [
+    "linux", "macos", "ios", "android", "freebsd", "netbsd", "openbsd", "dragonfly", "solaris", "illumos", "haiku",
+];
+
+/// A compilation target which decides whether `[target.*]` dependencies
+/// apply, by its triple and cfg options
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetPlatform {
+    /// e.g. `x86_64-unknown-linux-gnu`
+    pub triple: String,
+    /// Options as printed by `rustc --print cfg`, where names such as `unix`
+    /// have no value
+    pub cfg: BTreeSet<(String, Option<String>)>,
+}
+
+impl TargetPlatform {
+    /// Derives the usual cfg options of a target from its triple, such as
+    /// `target_os`, `target_family`, `target_arch` and `target_env`.
+    ///
+    /// Use `rustc --print cfg` output for an exact set.
+    pub fn from_triple(triple: &str) -> TargetPlatform {
+        let parts: Vec<_> = triple.split('-').collect();
+        let raw_arch = parts[0];
+        let arch = match raw_arch {
+            "i386" | "i586" | "i686" => "x86",
+            a if a.starts_with("armv") || a.starts_with("thumbv") => "arm",
+            a if a.starts_with("riscv64") => "riscv64",
+            a if a.starts_with("riscv32") => "riscv32",
+            a => a,
+        };
+        // Some triples leave out the vendor, e.g. `armv7-linux-androideabi`
+        let has_vendor = parts.len() > 3 || (parts.len() == 3 && !["linux", "none"].contains(&parts[1]));
+        let vendor = if has_vendor { parts[1] } else { "unknown" };
+        let system = if has_vendor { &parts[2..] } else { &parts[1..] };
+        let os = match system.first().copied() {
+            Some("darwin") => "macos",
+            Some("linux") if system.get(1).is_some_and(|env| env.starts_with("android")) => "android",
+            Some("none") | None => "none",
+            Some(os) => os,
+        };
+        let env = match system.get(1) {
+            Some(env) if env.starts_with("gnu") => "gnu",
+            Some(env) if env.starts_with("musl") => "musl",
+            Some(env) if env.starts_with("msvc") => "msvc",
+            Some(env) if env.starts_with("sgx") => "sgx",
+            _ => "",
+        };
+        let pointer_width = match arch {
+            "x86_64" | "aarch64" | "riscv64" | "powerpc64" | "powerpc64le" | "mips64" | "mips64el" | "s390x" | "sparc64"
+            | "wasm64" | "loongarch64" => "64",
+            "avr" | "msp430" => "16",
+            _ => "32",
+        };
+        let endian = match arch {
+            "powerpc" | "powerpc64" | "s390x" | "mips" | "mips64" | "sparc" | "sparc64" => "big",
+            _ => "little",
+        };
+
+        let mut cfg = BTreeSet::new();
+        let mut set = |key: &str, value: &str| cfg.insert((key.to_owned(), Some(value.to_owned())));
+        set("target_arch", arch);
+        set("target_vendor", vendor);
+        set("target_os", os);
+        set("target_env", env);
+        set("target_pointer_width", pointer_width);
+        set("target_endian", endian);
+        let family = if UNIX_OS.contains(&os) {
+            Some("unix")
+        } else if os == "windows" {
+            Some("windows")
+        } else {
+            None
+        };
+        if let Some(family) = family {
+            set("target_family", family);
+            cfg.insert((family.to_owned(), None));
+        }
+        if arch.starts_with("wasm") {
+            cfg.insert(("target_family".to_owned(), Some("wasm".to_owned())));
+        }
+        TargetPlatform { triple: triple.to_owned(), cfg }
+    }
+
+    /// Whether dependencies of a `[target.<platform>]` table apply, where
+    /// `platform` is either a triple or `cfg(..)`
+    pub fn matches(&self, platform: &str) -> Result<bool> {
+        let platform = platform.trim();
+        let invalid = |reason: String| Error::InvalidCfg(platform.to_owned(), reason);
+        let expr = match platform.strip_prefix("cfg(") {
+            Some(expr) => expr.strip_suffix(')').ok_or_else(|| invalid("unclosed `cfg(`".to_owned()))?,
+            None => return Ok(platform == self.triple),
+        };
+        CfgExpr::parse(expr)
+            .map(|expr| expr.eval(&self.cfg))
+            .map_err(|err| match err {
+                Error::InvalidCfg(_, reason) => invalid(reason),
+                err => err,
+            })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::match_like_matches_macro)]
+mod tests {
+    use super::{ TargetPlatform };
+    use crate::{ Error };
+
+    fn value(platform: &TargetPlatform, key: &str) -> Option<String> {
+        platform.cfg.iter().find(|(k, v)| k == key && v.is_some()).and_then(|(_, v)| v.to_owned())
+    }
+
+    #[test]
+    fn derive_cfg_from_triple() {
+        let cases = vec![
+            ("x86_64-unknown-linux-gnu", "x86_64", "linux", "gnu", "64", Some("unix")),
+            ("aarch64-apple-darwin", "aarch64", "macos", "", "64", Some("unix")),
+            ("x86_64-pc-windows-msvc", "x86_64", "windows", "msvc", "64", Some("windows")),
+            ("i686-unknown-linux-musl", "x86", "linux", "musl", "32", Some("unix")),
+            ("armv7-linux-androideabi", "arm", "android", "", "32", Some("unix")),
+            ("thumbv7em-none-eabihf", "arm", "none", "", "32", None),
+            ("wasm32-unknown-unknown", "wasm32", "unknown", "", "32", None),
+        ];
+        for (triple, arch, os, env, width, family) in cases {
+            let platform = TargetPlatform::from_triple(triple);
+            assert_eq!(Some(arch.to_owned()), value(&platform, "target_arch"), "{}", triple);
+            assert_eq!(Some(os.to_owned()), value(&platform, "target_os"), "{}", triple);
+            assert_eq!(Some(env.to_owned()), value(&platform, "target_env"), "{}", triple);
+            assert_eq!(Some(width.to_owned()), value(&platform, "target_pointer_width"), "{}", triple);
+            assert_eq!(family.is_some_and(|f| platform.cfg.contains(&(f.to_owned(), None))), family.is_some(), "{}", triple);
+        }
+    }
+
+    #[test]
+    fn match_platforms() {
+        let linux = TargetPlatform::from_triple("x86_64-unknown-linux-gnu");
+        assert_eq!(Ok(true), linux.matches("x86_64-unknown-linux-gnu"));
+        assert_eq!(Ok(false), linux.matches("x86_64-pc-windows-msvc"));
+        assert_eq!(Ok(true), linux.matches("cfg(unix)"));
+        assert_eq!(Ok(false), linux.matches("cfg(windows)"));
+        assert_eq!(Ok(true), linux.matches("cfg(all(target_os = \"linux\", not(target_env = \"musl\")))"));
+        assert_eq!(Ok(true), TargetPlatform::from_triple("wasm32-unknown-unknown").matches("cfg(target_family = \"wasm\")"));
+        assert_eq!(Err(Error::InvalidCfg("cfg(unix".to_owned(), "unclosed `cfg(`".to_owned())), linux.matches("cfg(unix"));
+        match linux.matches("cfg(unix windows)") {
+            Ok (_) => assert!(false, "should fail on an invalid cfg"),
+            Err (err) => assert_eq!(Error::InvalidCfg("cfg(unix windows)".to_owned(), "unexpected `windows`".to_owned()), err),
+        }
+    }
+}
@@ -1,6 +1,11 @@
+use crate::{ Lint, Resolver };
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Workspace {
     pub members: Option<Vec<String>>,
     pub default_members: Option<Vec<String>>,
     pub exclude: Option<Vec<String>>,
-}
\ No newline at end of file
+    pub resolver: Option<Resolver>,
+    /// `[workspace.lints]`, which members inherit with `lints.workspace = true`
+    pub lints: Vec<Lint>,
+}